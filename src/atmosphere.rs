@@ -0,0 +1,115 @@
+use crate::color::Color;
+use crate::math::ray::Ray;
+use crate::spectrum::Wavelengths;
+
+/// A medium filling the whole scene, like haze or ground fog.
+///
+/// Unlike a participating medium inside an object, the atmosphere does not scatter rays in new
+/// directions. Instead, light travelling along a ray is dimmed by the amount of medium it passes
+/// through, and replaced by the light the medium scatters toward the viewer. That in-scattered
+/// light is approximated by a single color, which is usually a washed out version of the sky.
+///
+/// The density of the medium is `density` at `base_height` and falls off exponentially above it
+/// at a rate of `height_falloff`. With a falloff of zero, the medium is homogeneous.
+#[derive(Clone, Copy)]
+pub struct Atmosphere {
+    pub density: f64,
+    pub base_height: f64,
+    pub height_falloff: f64,
+    pub color: Color,
+}
+
+impl Atmosphere {
+    /// Create a homogeneous medium of the given density.
+    pub fn homogeneous(density: f64, color: Color) -> Self {
+        Self {
+            density,
+            base_height: 0.0,
+            height_falloff: 0.0,
+            color,
+        }
+    }
+
+    /// Create a medium which thins out exponentially with height.
+    pub fn height_fog(density: f64, base_height: f64, height_falloff: f64, color: Color) -> Self {
+        Self {
+            density,
+            base_height,
+            height_falloff,
+            color,
+        }
+    }
+
+    /// Integrate the density of the medium along the ray between `t = 0` and the given time.
+    ///
+    /// With exponential falloff, the integral has a closed form. It is only infinite if the ray
+    /// goes on forever without climbing out of the medium.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::atmosphere::Atmosphere;
+    /// use raytracing::color::Color;
+    /// use raytracing::math::Vec3;
+    /// use raytracing::math::ray::Ray;
+    ///
+    /// let haze = Atmosphere::homogeneous(0.5, Color::ZERO);
+    /// let ray = Ray::new(Vec3::ZERO, Vec3::new(2.0, 0.0, 0.0));
+    ///
+    /// assert_eq!(haze.optical_depth(ray, 3.0), 3.0);
+    ///
+    /// // Far above a thin layer of fog, a level ray to the sky passes through none of it.
+    /// let fog = Atmosphere::height_fog(1.0, 0.0, 50.0, Color::ZERO);
+    /// let ray = Ray::new(Vec3::new(0.0, 100.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+    /// assert_eq!(fog.optical_depth(ray, f64::INFINITY), 0.0);
+    /// ```
+    pub fn optical_depth(&self, ray: Ray, time: f64) -> f64 {
+        if self.density <= 0.0 || time <= 0.0 {
+            return 0.0;
+        }
+
+        let speed = ray.direction.length();
+        let distance = time * speed;
+
+        // Write the density along the ray as a * exp(-b s) in terms of the distance s travelled.
+        let a = self.density * (-self.height_falloff * (ray.origin.y - self.base_height)).exp();
+        let b = self.height_falloff * ray.direction.y / speed;
+
+        // High above a steep falloff, the density can underflow to nothing, which must not be
+        // multiplied by an infinite distance.
+        if a == 0.0 {
+            0.0
+        } else if b.abs() < 1e-9 {
+            a * distance
+        } else if distance.is_infinite() {
+            if b > 0.0 { a / b } else { f64::INFINITY }
+        } else {
+            a * -(-b * distance).exp_m1() / b
+        }
+    }
+
+    /// Get the fraction of light which makes it through the medium along a ray from a point at
+    /// the given time.
+    pub fn transmittance(&self, ray: Ray, time: f64) -> f64 {
+        (-self.optical_depth(ray, time)).exp()
+    }
+
+    /// Apply the medium to light arriving along a ray from a point at the given time.
+    ///
+    /// Light from infinitely far away, like the sky, is allowed. When rendering spectrally, the
+    /// radiance is at the given wavelengths, and the color of the medium is taken at them too.
+    pub fn apply(
+        &self,
+        ray: Ray,
+        time: f64,
+        radiance: Color,
+        wavelengths: Option<Wavelengths>,
+    ) -> Color {
+        let transmittance = self.transmittance(ray, time);
+        let color = match wavelengths {
+            Some(wavelengths) => wavelengths.upsample(self.color),
+            None => self.color,
+        };
+        radiance * transmittance + color * (1.0 - transmittance)
+    }
+}
@@ -0,0 +1,118 @@
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::bezier::{self, Patch};
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+use crate::triangle::Triangle;
+
+/// The most a patch is split along each direction when tessellating it.
+const MAX_RESOLUTION: usize = 64;
+
+/// Represents a ray traceable bicubic Bézier patch.
+///
+/// The patch is tessellated into a grid of triangles, finer the more its control points bend, and
+/// rays are intersected with those. Each row of the grid gets a bounding box of its own, so that
+/// finely tessellated patches stay cheap. The shading normal and surface derivatives at a hit are
+/// then taken from the patch itself, so that the facets do not show.
+#[derive(Clone)]
+pub struct BezierPatch {
+    pub patch: Patch,
+    rows: Vec<(Aabb, Vec<Triangle>)>,
+    bounds: Aabb,
+}
+
+impl BezierPatch {
+    /// Create a new patch, tessellating it so that the triangles stray from the surface by no
+    /// more than about `tolerance`.
+    pub fn new(patch: Patch, tolerance: f64, material: Rc<dyn Material>) -> Self {
+        // The second differences of the control points bound how far the patch can deviate from
+        // a flat grid, and a grid of n segments reduces that by a factor of about n^2.
+        let columns: Patch = std::array::from_fn(|i| patch.map(|row| row[i]));
+        let bend = patch
+            .iter()
+            .chain(&columns)
+            .flat_map(|line| line.windows(3))
+            .map(|p| (p[0] - p[1] * 2.0 + p[2]).length())
+            .fold(0.0, f64::max);
+        let resolution =
+            ((bend / tolerance.max(1e-9)).sqrt().ceil() as usize).clamp(1, MAX_RESOLUTION);
+
+        let mut grid = Vec::with_capacity((resolution + 1) * (resolution + 1));
+        for j in 0..=resolution {
+            for i in 0..=resolution {
+                let (u, v) = (i as f64 / resolution as f64, j as f64 / resolution as f64);
+                grid.push((bezier::evaluate_patch(&patch, u, v), (u, v)));
+            }
+        }
+
+        let mut rows = Vec::with_capacity(resolution);
+        let mut bounds = Aabb::EMPTY;
+        let at = |i: usize, j: usize| grid[j * (resolution + 1) + i];
+        for j in 0..resolution {
+            let mut row_bounds = Aabb::EMPTY;
+            let mut triangles = Vec::with_capacity(2 * resolution);
+            for i in 0..resolution {
+                let corners = [at(i, j), at(i + 1, j), at(i + 1, j + 1), at(i, j + 1)];
+                for [a, b, c] in [[0, 1, 2], [0, 2, 3]] {
+                    let triangle = Triangle::with_uvs(
+                        [corners[a].0, corners[b].0, corners[c].0],
+                        [corners[a].1, corners[b].1, corners[c].1],
+                        material.clone(),
+                    );
+                    row_bounds = row_bounds.union(triangle.bounding_box());
+                    triangles.push(triangle);
+                }
+            }
+            bounds = bounds.union(row_bounds);
+            rows.push((row_bounds, triangles));
+        }
+
+        Self {
+            patch,
+            rows,
+            bounds,
+        }
+    }
+}
+
+impl Hittable for BezierPatch {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        if !self.bounds.hit(ray, interval) {
+            return None;
+        }
+
+        let mut closest = interval;
+        let mut record = None;
+        for (row_bounds, triangles) in &self.rows {
+            if !row_bounds.hit(ray, closest) {
+                continue;
+            }
+            for triangle in triangles {
+                if let Some(hit) = triangle.hit(ray, closest) {
+                    closest.max = hit.time;
+                    record = Some(hit);
+                }
+            }
+        }
+        let mut record = record?;
+
+        // Degenerate edges of the patch, like the pole at the top of the teapot lid, have no
+        // normal, so the triangle's own one is kept there.
+        let (dpdu, dpdv) = bezier::patch_derivatives(&self.patch, record.u, record.v);
+        let outward_normal = dpdu.cross(dpdv);
+        if !outward_normal.is_near_zero() {
+            record.dpdu = dpdu;
+            record.dpdv = dpdv;
+            record.set_face_normal(ray, outward_normal.unit_vector());
+        }
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounds
+    }
+}
@@ -0,0 +1,157 @@
+use crate::math::aabb::Aabb;
+use crate::math::interval::Interval;
+use crate::math::ray::Ray;
+
+/// A node of a [`Bvh`].
+///
+/// Leaves hold a range of the sorted item indices, and interior nodes the index of their second
+/// child, the first one always coming right after the node itself.
+struct Node {
+    bounds: Aabb,
+    start: usize,
+    count: usize,
+}
+
+/// The most items a leaf of the hierarchy holds.
+const LEAF_SIZE: usize = 4;
+
+/// A bounding volume hierarchy over a set of items.
+///
+/// The hierarchy only knows the bounding box of each item, and refers to them by their index, so
+/// shapes made of many small parts, like meshes and point clouds, can keep their parts in
+/// whatever form suits them.
+pub struct Bvh {
+    order: Vec<usize>,
+    nodes: Vec<Node>,
+}
+
+impl Bvh {
+    /// Build a hierarchy over items with the given bounding boxes.
+    pub fn new(bounds: &[Aabb]) -> Self {
+        let mut bvh = Self {
+            order: (0..bounds.len()).collect(),
+            nodes: Vec::new(),
+        };
+        if !bounds.is_empty() {
+            bvh.build(bounds, 0, bounds.len());
+        }
+        bvh
+    }
+
+    /// Build the hierarchy over the items in `order[start..end]`, returning the index of the node.
+    fn build(&mut self, bounds: &[Aabb], start: usize, end: usize) -> usize {
+        let node_bounds = self.order[start..end]
+            .iter()
+            .fold(Aabb::EMPTY, |total, &i| total.union(bounds[i]));
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            bounds: node_bounds,
+            start,
+            count: end - start,
+        });
+        if end - start <= LEAF_SIZE {
+            return node;
+        }
+
+        // Split the items in half along the axis their bounds are longest in. Partitioning around
+        // the median is enough, there is no need to sort them fully.
+        let axis = node_bounds.longest_axis();
+        let middle = (start + end) / 2;
+        let coordinate = |i: &usize| {
+            let centroid = bounds[*i].centroid();
+            [centroid.x, centroid.y, centroid.z][axis]
+        };
+        self.order[start..end].select_nth_unstable_by(middle - start, |a, b| {
+            coordinate(a).total_cmp(&coordinate(b))
+        });
+
+        self.build(bounds, start, middle);
+        let second = self.build(bounds, middle, end);
+        self.nodes[node].count = 0;
+        self.nodes[node].start = second;
+        node
+    }
+
+    /// Get a box containing every item.
+    pub fn bounding_box(&self) -> Aabb {
+        self.nodes
+            .first()
+            .map(|node| node.bounds)
+            .unwrap_or(Aabb::EMPTY)
+    }
+
+    /// Find the nearest item the ray hits.
+    ///
+    /// The `hit` function is called with the index of every item whose box the ray passes through,
+    /// along with the interval still worth searching, and returns the time of its hit with the
+    /// item, if any. The index and time of the nearest hit are returned.
+    pub fn closest_hit<F>(&self, ray: Ray, interval: Interval, mut hit: F) -> Option<(usize, f64)>
+    where
+        F: FnMut(usize, Interval) -> Option<f64>,
+    {
+        let mut closest = interval;
+        let mut nearest = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.bounds.hit(ray, closest) {
+                continue;
+            }
+
+            if node.count > 0 {
+                for &item in &self.order[node.start..node.start + node.count] {
+                    if let Some(time) = hit(item, closest) {
+                        closest.max = time;
+                        nearest = Some((item, time));
+                    }
+                }
+            } else {
+                // Visit the nearer child first, so that its hits can cut the other one short.
+                let (first, second) = (index + 1, node.start);
+                let distance = |child: usize| {
+                    (self.nodes[child].bounds.centroid() - ray.origin).dot(ray.direction)
+                };
+                if distance(first) < distance(second) {
+                    stack.extend([second, first]);
+                } else {
+                    stack.extend([first, second]);
+                }
+            }
+        }
+
+        nearest
+    }
+
+    /// Call `visit` with the index of every item whose box could overlap the region.
+    ///
+    /// The hierarchy does not keep the boxes of the items themselves, so every item in a leaf
+    /// overlapping the region is visited, and some of them may lie a little outside of it.
+    pub fn overlapping<F>(&self, region: Aabb, mut visit: F)
+    where
+        F: FnMut(usize),
+    {
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.bounds.overlaps(region) {
+                continue;
+            }
+
+            if node.count > 0 {
+                self.order[node.start..node.start + node.count]
+                    .iter()
+                    .for_each(|&item| visit(item));
+            } else {
+                stack.extend([index + 1, node.start]);
+            }
+        }
+    }
+}
@@ -13,6 +13,31 @@ pub fn linear_to_gamma(linear_component: f64) -> f64 {
     }
 }
 
+/// Undo gamma correction on a color component.
+///
+/// This is the inverse of [`linear_to_gamma`], used to bring stored image data back into linear
+/// space.
+pub fn gamma_to_linear(gamma_component: f64) -> f64 {
+    if gamma_component > 0.0 {
+        gamma_component * gamma_component
+    } else {
+        0.0
+    }
+}
+
+/// Compute the relative luminance of a linear color.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::color::{self, Color};
+///
+/// assert!((color::luminance(Color::new(1.0, 1.0, 1.0)) - 1.0).abs() < 1e-12);
+/// ```
+pub fn luminance(color: Color) -> f64 {
+    0.2126 * color.x + 0.7152 * color.y + 0.0722 * color.z
+}
+
 /// Convert a normalized color to an RGB tuple.
 pub fn as_rgb_tuple(color: Color) -> (u8, u8, u8) {
     const UNIT: Interval = Interval::new(0.0, 1.0);
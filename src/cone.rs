@@ -0,0 +1,147 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::frame::Frame;
+use raytracing::math::interval::Interval;
+use raytracing::math::polynomial;
+use raytracing::math::ray::Ray;
+use raytracing::math::{Point3, Vec3};
+
+use crate::cylinder;
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// Represents a ray traceable cone, closed off at its base.
+///
+/// The cone stands on the disk around `base` and narrows to a point `height` units along `axis`.
+/// On the side, the `u` coordinate goes once around the axis and the `v` coordinate runs from the
+/// base to the apex. On the base, they are laid out like those of a [`Disk`](crate::disk::Disk).
+#[derive(Clone)]
+pub struct Cone {
+    pub frame: Frame,
+    pub radius: f64,
+    pub height: f64,
+    pub material: Rc<dyn Material>,
+}
+
+impl Cone {
+    /// Create a new cone from the center of its base, its axis, base radius and height.
+    ///
+    /// A height that is not positive is raised to a tiny one.
+    pub fn new(
+        base: Point3,
+        axis: Vec3,
+        radius: f64,
+        height: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            frame: Frame::from_axis(base, axis),
+            radius: radius.max(0.0),
+            // The slope of the side is the radius over the height, so a flat cone is kept from
+            // dividing by zero.
+            height: height.max(1e-12),
+            material,
+        }
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let local = self.frame.ray_to_local(ray);
+        let (o, d) = (local.origin, local.direction);
+
+        // The side is part of the double cone x^2 + y^2 = k^2 (h - z)^2, where k is the ratio of
+        // the base radius to the height. Only the nappe between the base and the apex is kept.
+        let k = self.radius / self.height;
+        let k2 = k * k;
+        let h = self.height - o.z;
+        let side = polynomial::solve_quadratic(
+            d.x * d.x + d.y * d.y - k2 * d.z * d.z,
+            2.0 * (o.x * d.x + o.y * d.y + k2 * h * d.z),
+            o.x * o.x + o.y * o.y - k2 * h * h,
+        )
+        .into_iter()
+        .filter(|&t| (0.0..=self.height).contains(&local.at(t).z))
+        .map(|t| (t, true));
+
+        let base = {
+            let t = -o.z / d.z;
+            let p = local.at(t);
+            (p.x * p.x + p.y * p.y <= self.radius * self.radius).then_some((t, false))
+        };
+
+        let (root, on_side) = side
+            .chain(base)
+            .filter(|&(t, _)| interval.surrounds(t))
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))?;
+
+        let p = local.at(root);
+        let (outward_normal, u, v, dpdu, dpdv) = if on_side {
+            let phi = p.y.atan2(p.x).rem_euclid(2.0 * PI);
+            let (sin_phi, cos_phi) = phi.sin_cos();
+            let slice_radius = k * (self.height - p.z);
+            (
+                Vec3::new(cos_phi, sin_phi, k).unit_vector(),
+                phi / (2.0 * PI),
+                p.z / self.height,
+                Vec3::new(-sin_phi, cos_phi, 0.0) * (2.0 * PI * slice_radius),
+                Vec3::new(-k * cos_phi, -k * sin_phi, 1.0) * self.height,
+            )
+        } else {
+            let (u, v, dpdu, dpdv) = cylinder::cap_coordinates(p, self.radius);
+            (Vec3::new(0.0, 0.0, -1.0), u, v, dpdu, dpdv)
+        };
+
+        let outward_normal = self.frame.vector_to_world(outward_normal);
+        let mut record = HitRecord {
+            point: ray.at(root),
+            normal: outward_normal,
+            u,
+            v,
+            dpdu: self.frame.vector_to_world(dpdu),
+            dpdv: self.frame.vector_to_world(dpdv),
+            material: self.material.clone(),
+            time: root,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, outward_normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = self.radius;
+        self.frame.box_to_world(Aabb::from_points(
+            Point3::new(-r, -r, 0.0),
+            Point3::new(r, r, self.height),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raytracing::color::Color;
+
+    use super::*;
+    use crate::renderer::material::Lambertian;
+
+    #[test]
+    fn flat_cone_hits_its_base() {
+        let cone = Cone::new(
+            Point3::ZERO,
+            Vec3::new(0.0, 0.0, 1.0),
+            1.0,
+            0.0,
+            Rc::new(Lambertian {
+                albedo: Color::ZERO,
+            }),
+        );
+        let ray = Ray::new(Point3::new(0.3, 0.2, 1.0), Vec3::new(0.2, 0.1, -1.0));
+
+        let hit = cone.hit(ray, Interval::new(0.001, f64::INFINITY)).unwrap();
+        assert!((hit.time - 1.0).abs() < 1e-9);
+        assert!(hit.u.is_finite() && hit.v.is_finite());
+    }
+}
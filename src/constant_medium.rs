@@ -0,0 +1,101 @@
+use std::rc::Rc;
+
+use raytracing::color::Color;
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{self, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::{Isotropic, Material};
+
+/// A volume of uniformly dense particles, like smoke or fog.
+///
+/// The volume fills the inside of a closed boundary shape. A ray travelling through it has the
+/// same chance of hitting a particle over every stretch of the same length, so the distance it
+/// gets before hitting one is exponentially distributed. When it does, the phase function decides
+/// where it goes next. Rays can also make it all the way through without hitting anything.
+pub struct ConstantMedium {
+    pub boundary: Rc<dyn Hittable>,
+    pub phase_function: Rc<dyn Material>,
+    negative_inverse_density: f64,
+}
+
+impl ConstantMedium {
+    /// Fill the boundary with particles of the given density and color.
+    pub fn new(boundary: Rc<dyn Hittable>, density: f64, albedo: Color) -> Self {
+        Self::with_phase_function(boundary, density, Rc::new(Isotropic::new(albedo)))
+    }
+
+    /// Fill the boundary with particles of the given density which scatter light according to the
+    /// phase function.
+    pub fn with_phase_function(
+        boundary: Rc<dyn Hittable>,
+        density: f64,
+        phase_function: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            boundary,
+            phase_function,
+            negative_inverse_density: -1.0 / density,
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        // The crossings of the boundary come in pairs, with the ray inside the volume between the
+        // two crossings of each pair. If the first crossing is an exit, the ray starts inside.
+        let crossings = self.boundary.crossings(ray, Interval::UNIVERSE);
+        let mut entry = match crossings.first() {
+            Some(first) if !first.front_face => Some(f64::NEG_INFINITY),
+            _ => None,
+        };
+
+        let ray_length = ray.direction.length();
+        let mut hit_distance = self.negative_inverse_density * raytracing::random_f64().ln();
+
+        for crossing in crossings {
+            let Some(start) = entry else {
+                entry = crossing.front_face.then_some(crossing.time);
+                continue;
+            };
+            entry = None;
+
+            // Since the distribution of the distance to the next particle does not depend on how
+            // far the ray has already gone, the distance spent in earlier segments can simply be
+            // subtracted off.
+            let start = start.max(interval.min);
+            let end = crossing.time.min(interval.max);
+            if start >= end {
+                continue;
+            }
+
+            let distance_inside = (end - start) * ray_length;
+            if hit_distance > distance_inside {
+                hit_distance -= distance_inside;
+                continue;
+            }
+
+            let time = start + hit_distance / ray_length;
+            let (dpdu, dpdv) = math::orthonormal_basis(Vec3::new(1.0, 0.0, 0.0));
+            return Some(HitRecord {
+                point: ray.at(time),
+                normal: Vec3::new(1.0, 0.0, 0.0), // Arbitrary
+                u: 0.0,
+                v: 0.0,
+                dpdu,
+                dpdv,
+                material: self.phase_function.clone(),
+                time,
+                front_face: true, // Also arbitrary
+            });
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
@@ -0,0 +1,155 @@
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+
+use crate::hittable::{HitRecord, Hittable};
+
+/// The ways two solids can be combined.
+#[derive(Clone, Copy)]
+pub enum CsgOperation {
+    /// Everything inside either solid.
+    Union,
+
+    /// Everything inside both solids.
+    Intersection,
+
+    /// Everything inside the left solid but not the right one.
+    Difference,
+}
+
+impl CsgOperation {
+    /// Determine whether a point is inside the combined solid, given whether it is inside each of
+    /// the two operands.
+    fn contains(self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => in_left || in_right,
+            CsgOperation::Intersection => in_left && in_right,
+            CsgOperation::Difference => in_left && !in_right,
+        }
+    }
+}
+
+/// A node of a constructive solid geometry tree.
+///
+/// Both operands must be closed shapes, so that every crossing of their surface is either an
+/// entry or an exit. The crossings of both are merged along the ray, and only those at which the
+/// ray enters or leaves the combined solid are kept. Nodes can be nested to build up complicated
+/// solids from simple ones.
+pub struct Csg {
+    pub operation: CsgOperation,
+    pub left: Rc<dyn Hittable>,
+    pub right: Rc<dyn Hittable>,
+}
+
+impl Csg {
+    /// Combine two solids into everything inside either of them.
+    pub fn union(left: Rc<dyn Hittable>, right: Rc<dyn Hittable>) -> Self {
+        Self {
+            operation: CsgOperation::Union,
+            left,
+            right,
+        }
+    }
+
+    /// Combine two solids into everything inside both of them.
+    pub fn intersection(left: Rc<dyn Hittable>, right: Rc<dyn Hittable>) -> Self {
+        Self {
+            operation: CsgOperation::Intersection,
+            left,
+            right,
+        }
+    }
+
+    /// Carve the right solid out of the left one.
+    pub fn difference(left: Rc<dyn Hittable>, right: Rc<dyn Hittable>) -> Self {
+        Self {
+            operation: CsgOperation::Difference,
+            left,
+            right,
+        }
+    }
+}
+
+impl Hittable for Csg {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        self.crossings(ray, interval).into_iter().next()
+    }
+
+    fn crossings(&self, ray: Ray, interval: Interval) -> Vec<HitRecord> {
+        // Whether the ray is inside each solid can only be told from the crossings along the whole
+        // line, as the interval may start inside a solid or stop short of leaving it.
+        let left = self.left.crossings(ray, Interval::UNIVERSE);
+        let right = self.right.crossings(ray, Interval::UNIVERSE);
+
+        // If the first crossing of a solid is an exit, the line starts out inside of it.
+        let starts_inside =
+            |crossings: &[HitRecord]| crossings.first().is_some_and(|hit| !hit.front_face);
+        let mut in_left = starts_inside(&left);
+        let mut in_right = starts_inside(&right);
+        let mut inside = self.operation.contains(in_left, in_right);
+
+        let mut events: Vec<(HitRecord, bool)> = left
+            .into_iter()
+            .map(|hit| (hit, true))
+            .chain(right.into_iter().map(|hit| (hit, false)))
+            .collect();
+        events.sort_by(|(a, _), (b, _)| a.time.total_cmp(&b.time));
+
+        let mut crossings = Vec::new();
+        for (mut hit, from_left) in events {
+            if from_left {
+                in_left = hit.front_face;
+            } else {
+                in_right = hit.front_face;
+            }
+
+            // The normal of a hit always faces against the ray, which is still correct for the
+            // combined solid. Only whether this is an entry or an exit may have changed.
+            let now_inside = self.operation.contains(in_left, in_right);
+            if now_inside != inside {
+                inside = now_inside;
+                hit.front_face = now_inside;
+                if interval.surrounds(hit.time) {
+                    crossings.push(hit);
+                }
+            }
+        }
+        crossings
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        match self.operation {
+            CsgOperation::Union => self.left.bounding_box().union(self.right.bounding_box()),
+            CsgOperation::Intersection | CsgOperation::Difference => self.left.bounding_box(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raytracing::color::Color;
+    use raytracing::math::{Point3, Vec3};
+
+    use super::*;
+    use crate::renderer::material::Lambertian;
+    use crate::sphere::Sphere;
+
+    #[test]
+    fn hit_does_not_depend_on_how_far_the_interval_reaches() {
+        let material = Rc::new(Lambertian {
+            albedo: Color::ZERO,
+        });
+        let hollow = Csg::difference(
+            Rc::new(Sphere::new(Point3::ZERO, 10.0, material.clone())),
+            Rc::new(Sphere::new(Point3::new(0.0, 0.0, -1.5), 0.5, material)),
+        );
+        let ray = Ray::new(Point3::ZERO, Vec3::new(0.0, 0.0, -1.0));
+
+        for max in [f64::INFINITY, 2.5] {
+            let hit = hollow.hit(ray, Interval::new(0.001, max));
+            assert!(hit.is_some_and(|hit| (hit.time - 1.0).abs() < 1e-9));
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{Point3, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::hittable_list::HittableList;
+use crate::quad::Quad;
+use crate::renderer::material::Material;
+
+/// Represents an axis-aligned box.
+///
+/// The box is made of six quads whose front faces point out of the box.
+pub struct Cuboid {
+    sides: HittableList,
+}
+
+impl Cuboid {
+    /// Create the box with the two given points as opposite corners.
+    pub fn new(a: Point3, b: Point3, material: Rc<dyn Material>) -> Self {
+        let min = Point3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z));
+        let max = Point3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z));
+
+        let dx = Vec3::new(max.x - min.x, 0.0, 0.0);
+        let dy = Vec3::new(0.0, max.y - min.y, 0.0);
+        let dz = Vec3::new(0.0, 0.0, max.z - min.z);
+
+        let mut sides = HittableList::new();
+        let mut add_side = |corner: Point3, u: Vec3, v: Vec3| {
+            sides.add(Rc::new(Quad::new(corner, u, v, material.clone())));
+        };
+        add_side(Point3::new(min.x, min.y, max.z), dx, dy); // Front
+        add_side(Point3::new(max.x, min.y, max.z), -dz, dy); // Right
+        add_side(Point3::new(max.x, min.y, min.z), -dx, dy); // Back
+        add_side(Point3::new(min.x, min.y, min.z), dz, dy); // Left
+        add_side(Point3::new(min.x, max.y, max.z), dx, -dz); // Top
+        add_side(Point3::new(min.x, min.y, min.z), dx, dz); // Bottom
+
+        Self { sides }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        self.sides.hit(ray, interval)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.sides.bounding_box()
+    }
+}
@@ -0,0 +1,228 @@
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::bezier;
+use raytracing::math::frame::Frame;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{Point3, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// The cross section of a [`Curve`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CurveKind {
+    /// A flat strip which is always turned to face the incoming ray. This is the cheapest kind,
+    /// and is what hair, fur and grass are usually made of.
+    Ribbon,
+
+    /// A round tube, with a proper normal all the way around, for things like cables.
+    Tube,
+}
+
+/// Represents a ray traceable cubic Bézier curve with a width.
+///
+/// The width varies linearly along the curve from `widths[0]` to `widths[1]`, so strands can
+/// taper to a point. The `u` coordinate runs along the curve, and `v` runs across it, from 0 on
+/// one edge to 1 on the other.
+///
+/// Curves are meant to be thin. The intersection test treats them as a chain of short straight
+/// segments in the plane facing the ray, which is only accurate when the width is small compared
+/// to how sharply the curve bends.
+#[derive(Clone)]
+pub struct Curve {
+    pub control_points: [Point3; 4],
+    pub widths: [f64; 2],
+    pub kind: CurveKind,
+    pub material: Rc<dyn Material>,
+}
+
+/// How far a curve is split at most when looking for an intersection.
+const MAX_DEPTH: u32 = 10;
+
+impl Curve {
+    /// Create a new curve from its control points and the widths at either end.
+    pub fn new(
+        control_points: [Point3; 4],
+        widths: [f64; 2],
+        kind: CurveKind,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            control_points,
+            widths: [widths[0].max(0.0), widths[1].max(0.0)],
+            kind,
+            material,
+        }
+    }
+
+    fn width_at(&self, u: f64) -> f64 {
+        self.widths[0] * (1.0 - u) + self.widths[1] * u
+    }
+
+    /// Find the nearest intersection of the ray with the part of the curve between `u0` and `u1`.
+    ///
+    /// The control points are given in the frame of the ray, which travels along the z axis from
+    /// the origin, so the curve is hit wherever it passes within half its width of the z axis.
+    /// Hits are returned as the z coordinate along with the `u` and `v` coordinates.
+    fn intersect(
+        &self,
+        points: [Point3; 4],
+        u0: f64,
+        u1: f64,
+        depth: u32,
+        depths: &mut Interval,
+    ) -> Option<(f64, f64, f64)> {
+        // Skip the segment if its control points, widened by the curve, miss the ray. By the
+        // convex hull property of Bézier curves, the segment cannot hit it either.
+        let half_width = self.width_at(u0).max(self.width_at(u1)) / 2.0;
+        let bounds =
+            Aabb::from_points(points[0], points[1]).union(Aabb::from_points(points[2], points[3]));
+        if bounds.x.min - half_width > 0.0
+            || bounds.x.max + half_width < 0.0
+            || bounds.y.min - half_width > 0.0
+            || bounds.y.max + half_width < 0.0
+            || bounds.z.min - half_width > depths.max
+            || bounds.z.max + half_width < depths.min
+        {
+            return None;
+        }
+
+        if depth > 0 {
+            let (left, right) = bezier::split(points);
+            let middle = (u0 + u1) / 2.0;
+            let near = self.intersect(left, u0, middle, depth - 1, depths);
+            if let Some((z, _, _)) = near {
+                depths.max = z;
+            }
+            return self
+                .intersect(right, middle, u1, depth - 1, depths)
+                .or(near);
+        }
+
+        // The segment is now nearly straight. Make sure the ray passes between the lines through
+        // its ends perpendicular to it, so that neighbouring segments do not both claim a hit.
+        let [p0, p1, p2, p3] = points;
+        if (p1.y - p0.y) * -p0.y + p0.x * (p0.x - p1.x) < 0.0
+            || (p2.y - p3.y) * -p3.y + p3.x * (p3.x - p2.x) < 0.0
+        {
+            return None;
+        }
+
+        // Find the point of the segment closest to the ray, treating it as a straight line.
+        let direction = Vec3::new(p3.x - p0.x, p3.y - p0.y, 0.0);
+        let length_squared = direction.length_squared();
+        if length_squared == 0.0 {
+            return None;
+        }
+        let w = (-(p0.x * direction.x + p0.y * direction.y) / length_squared).clamp(0.0, 1.0);
+        let u = u0 + (u1 - u0) * w;
+        let width = self.width_at(u);
+
+        let center = bezier::evaluate(points, w);
+        let distance_squared = center.x * center.x + center.y * center.y;
+        if distance_squared > width * width / 4.0 {
+            return None;
+        }
+
+        let z = match self.kind {
+            CurveKind::Ribbon => Some(center.z).filter(|&z| depths.surrounds(z)),
+            CurveKind::Tube => {
+                // Move from the axis of the tube to the surface facing the ray, or to the far
+                // side if the ray starts inside the tube.
+                let offset = (width * width / 4.0 - distance_squared).sqrt();
+                [center.z - offset, center.z + offset]
+                    .into_iter()
+                    .find(|&z| depths.surrounds(z))
+            }
+        }?;
+
+        // Work out which side of the curve the ray passes on to get the v coordinate.
+        let tangent = bezier::derivative(points, w);
+        let distance = distance_squared.sqrt();
+        let v = if tangent.x * -center.y + center.x * tangent.y > 0.0 {
+            0.5 + distance / width
+        } else {
+            0.5 - distance / width
+        };
+        Some((z, u, v))
+    }
+
+    /// Pick how many times to split the curve so that its segments are close to straight.
+    ///
+    /// This is the bound on the flatness of a Bézier curve used by PBRT, which compares the
+    /// second differences of the control points against a fraction of the width.
+    fn split_depth(&self, points: [Point3; 4]) -> u32 {
+        let mut bend: f64 = 0.0;
+        for i in 0..2 {
+            let difference = points[i] - points[i + 1] * 2.0 + points[i + 2];
+            bend = bend
+                .max(difference.x.abs())
+                .max(difference.y.abs())
+                .max(difference.z.abs());
+        }
+
+        let tolerance = self.widths[0].max(self.widths[1]) * 0.05;
+        if bend <= 0.0 || tolerance <= 0.0 {
+            return 0;
+        }
+        let depth = (std::f64::consts::SQRT_2 * 6.0 * bend / (8.0 * tolerance)).log2() / 2.0;
+        (depth.round().max(0.0) as u32).min(MAX_DEPTH)
+    }
+}
+
+impl Hittable for Curve {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        if !self.bounding_box().hit(ray, interval) {
+            return None;
+        }
+
+        // Distances along the z axis of the ray's frame are distances in world space, so they are
+        // scaled by the speed of the ray to get times.
+        let frame = Frame::from_axis(ray.origin, ray.direction);
+        let speed = ray.direction.length();
+        let points = self.control_points.map(|point| frame.point_to_local(point));
+        let mut depths = Interval::new(interval.min * speed, interval.max * speed);
+        let (z, u, v) = self.intersect(points, 0.0, 1.0, self.split_depth(points), &mut depths)?;
+        let time = z / speed;
+
+        let point = ray.at(time);
+        let dpdu = bezier::derivative(self.control_points, u);
+        let outward_normal = match self.kind {
+            CurveKind::Ribbon => {
+                // Turn the ribbon to face the ray, keeping it parallel to the curve.
+                let facing = -ray.direction;
+                let tangent = dpdu.unit_vector();
+                (facing - tangent * facing.dot(tangent)).unit_vector()
+            }
+            CurveKind::Tube => (point - bezier::evaluate(self.control_points, u)).unit_vector(),
+        };
+        let dpdv = outward_normal.cross(dpdu).unit_vector() * self.width_at(u);
+
+        let mut record = HitRecord {
+            point,
+            normal: outward_normal,
+            u,
+            v,
+            dpdu,
+            dpdv,
+            material: self.material.clone(),
+            time,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, outward_normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let half_width = self.widths[0].max(self.widths[1]) / 2.0;
+        let extent = Vec3::new(half_width, half_width, half_width);
+        self.control_points
+            .iter()
+            .fold(Aabb::EMPTY, |bounds, &point| {
+                bounds.union(Aabb::from_points(point - extent, point + extent))
+            })
+    }
+}
@@ -0,0 +1,145 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::frame::Frame;
+use raytracing::math::interval::Interval;
+use raytracing::math::polynomial;
+use raytracing::math::ray::Ray;
+use raytracing::math::{Point3, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// Represents a ray traceable cylinder, closed off at both ends.
+///
+/// The cylinder stands on the disk around `base` and extends `height` units along `axis`. On the
+/// side, the `u` coordinate goes once around the axis and the `v` coordinate runs from the bottom
+/// to the top. On the caps, they are laid out like those of a [`Disk`](crate::disk::Disk).
+#[derive(Clone)]
+pub struct Cylinder {
+    pub frame: Frame,
+    pub radius: f64,
+    pub height: f64,
+    pub material: Rc<dyn Material>,
+}
+
+impl Cylinder {
+    /// Create a new cylinder from the center of its base, its axis, radius and height.
+    pub fn new(
+        base: Point3,
+        axis: Vec3,
+        radius: f64,
+        height: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            frame: Frame::from_axis(base, axis),
+            radius: radius.max(0.0),
+            height: height.max(0.0),
+            material,
+        }
+    }
+}
+
+/// The parts of a cylinder a ray can hit.
+enum Surface {
+    Side,
+    Bottom,
+    Top,
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let local = self.frame.ray_to_local(ray);
+        let (o, d) = (local.origin, local.direction);
+
+        // The side is the part of the infinite cylinder x^2 + y^2 = r^2 between the caps.
+        let side = polynomial::solve_quadratic(
+            d.x * d.x + d.y * d.y,
+            2.0 * (o.x * d.x + o.y * d.y),
+            o.x * o.x + o.y * o.y - self.radius * self.radius,
+        )
+        .into_iter()
+        .filter(|&t| (0.0..=self.height).contains(&local.at(t).z))
+        .map(|t| (t, Surface::Side));
+
+        let caps = [(0.0, Surface::Bottom), (self.height, Surface::Top)]
+            .into_iter()
+            .filter_map(|(z, surface)| {
+                let t = (z - o.z) / d.z;
+                let p = local.at(t);
+                (p.x * p.x + p.y * p.y <= self.radius * self.radius).then_some((t, surface))
+            });
+
+        let (root, surface) = side
+            .chain(caps)
+            .filter(|&(t, _)| interval.surrounds(t))
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))?;
+
+        let p = local.at(root);
+        let (outward_normal, u, v, dpdu, dpdv) = match surface {
+            Surface::Side => {
+                let phi = p.y.atan2(p.x).rem_euclid(2.0 * PI);
+                (
+                    Vec3::new(p.x, p.y, 0.0) / self.radius,
+                    phi / (2.0 * PI),
+                    p.z / self.height,
+                    Vec3::new(-p.y, p.x, 0.0) * (2.0 * PI),
+                    Vec3::new(0.0, 0.0, self.height),
+                )
+            }
+            Surface::Bottom | Surface::Top => {
+                let (u, v, dpdu, dpdv) = cap_coordinates(p, self.radius);
+                let z = if matches!(surface, Surface::Top) {
+                    1.0
+                } else {
+                    -1.0
+                };
+                (Vec3::new(0.0, 0.0, z), u, v, dpdu, dpdv)
+            }
+        };
+
+        let outward_normal = self.frame.vector_to_world(outward_normal);
+        let mut record = HitRecord {
+            point: ray.at(root),
+            normal: outward_normal,
+            u,
+            v,
+            dpdu: self.frame.vector_to_world(dpdu),
+            dpdv: self.frame.vector_to_world(dpdv),
+            material: self.material.clone(),
+            time: root,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, outward_normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = self.radius;
+        self.frame.box_to_world(Aabb::from_points(
+            Point3::new(-r, -r, 0.0),
+            Point3::new(r, r, self.height),
+        ))
+    }
+}
+
+/// Compute the surface coordinates of a point on a disk of the given radius centered on the z
+/// axis.
+///
+/// The coordinates are laid out like those of a [`Disk`](crate::disk::Disk). The derivatives are
+/// returned in the order `(dpdu, dpdv)`.
+pub fn cap_coordinates(point: Point3, radius: f64) -> (f64, f64, Vec3, Vec3) {
+    let distance = (point.x * point.x + point.y * point.y).sqrt();
+    let phi = point.y.atan2(point.x).rem_euclid(2.0 * PI);
+    let (sin_phi, cos_phi) = phi.sin_cos();
+
+    (
+        phi / (2.0 * PI),
+        distance / radius,
+        Vec3::new(-sin_phi, cos_phi, 0.0) * (2.0 * PI * distance),
+        Vec3::new(cos_phi, sin_phi, 0.0) * radius,
+    )
+}
@@ -0,0 +1,173 @@
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use crate::math::Point3;
+
+/// A three dimensional grid of density values.
+///
+/// The grid covers the unit cube, with one sample at the center of each voxel. Values between
+/// samples are interpolated trilinearly, and everything outside of the unit cube has a density of
+/// zero. Samples are stored with the x index varying fastest, then y, then z.
+pub struct DensityGrid {
+    dimensions: [usize; 3],
+    values: Box<[f64]>,
+    max_value: f64,
+}
+
+impl DensityGrid {
+    /// Create a grid from its dimensions and samples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of samples does not match the dimensions.
+    pub fn new(dimensions: [usize; 3], values: Vec<f64>) -> Self {
+        assert_eq!(
+            Some(values.len()),
+            Self::sample_count(dimensions),
+            "the number of samples does not match the dimensions of the grid"
+        );
+
+        Self {
+            dimensions,
+            max_value: values.iter().copied().fold(0.0, f64::max),
+            values: values.into_boxed_slice(),
+        }
+    }
+
+    /// Get the number of samples a grid of the given dimensions holds, unless it is too many to
+    /// count.
+    fn sample_count(dimensions: [usize; 3]) -> Option<usize> {
+        dimensions
+            .iter()
+            .try_fold(1usize, |count, &size| count.checked_mul(size))
+    }
+
+    /// Load a grid from a text file.
+    ///
+    /// See [`DensityGrid::parse_text`] for the format.
+    pub fn load_text<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Self::parse_text(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse a grid from text.
+    ///
+    /// The text starts with the three dimensions of the grid, followed by all of the samples.
+    /// Numbers are separated by whitespace, and anything from a `#` to the end of a line is
+    /// ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::density_grid::DensityGrid;
+    /// use raytracing::math::Point3;
+    ///
+    /// let grid = DensityGrid::parse_text("# a single voxel\n1 1 1\n0.5").unwrap();
+    ///
+    /// assert_eq!(grid.max_value(), 0.5);
+    /// assert_eq!(grid.sample(Point3::new(0.5, 0.5, 0.5)), 0.5);
+    /// assert_eq!(grid.sample(Point3::new(2.0, 0.5, 0.5)), 0.0);
+    ///
+    /// // Dimensions too large to count are rejected.
+    /// assert!(DensityGrid::parse_text("4294967296 4294967296 2\n0.5").is_err());
+    /// ```
+    pub fn parse_text(text: &str) -> std::io::Result<Self> {
+        let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+
+        let mut tokens = text
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .flat_map(str::split_whitespace);
+
+        let mut dimensions = [0; 3];
+        for dimension in &mut dimensions {
+            *dimension = tokens
+                .next()
+                .and_then(|token| token.parse().ok())
+                .ok_or_else(|| invalid("expected the dimensions of the grid"))?;
+        }
+
+        let count =
+            Self::sample_count(dimensions).ok_or_else(|| invalid("the grid is too large"))?;
+        let values = tokens
+            .map(|token| token.parse())
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|_| invalid("expected a density value"))?;
+        if values.len() != count {
+            return Err(invalid(
+                "the number of samples does not match the dimensions",
+            ));
+        }
+
+        Ok(Self::new(dimensions, values))
+    }
+
+    /// Load a grid from a raw file of little endian 32 bit floats.
+    ///
+    /// Raw files have no header, so the dimensions have to be supplied separately.
+    pub fn load_raw<P: AsRef<Path>>(path: P, dimensions: [usize; 3]) -> std::io::Result<Self> {
+        let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+
+        let size = Self::sample_count(dimensions)
+            .and_then(|count| count.checked_mul(4))
+            .ok_or_else(|| invalid("the grid is too large"))?;
+        let bytes = std::fs::read(path)?;
+        if bytes.len() != size {
+            return Err(invalid(
+                "the size of the file does not match the dimensions",
+            ));
+        }
+
+        let values = bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as f64)
+            .collect();
+        Ok(Self::new(dimensions, values))
+    }
+
+    /// Get the largest sample in the grid.
+    ///
+    /// No interpolated value can exceed this, which makes it a bound on the density everywhere.
+    pub fn max_value(&self) -> f64 {
+        self.max_value
+    }
+
+    /// Look up the density at a point of the unit cube.
+    pub fn sample(&self, point: Point3) -> f64 {
+        let coordinates = [point.x, point.y, point.z];
+        if coordinates.iter().any(|x| !(0.0..=1.0).contains(x)) {
+            return 0.0;
+        }
+
+        // Find the voxel centers surrounding the point and how far along between them it lies.
+        let mut lower = [0; 3];
+        let mut upper = [0; 3];
+        let mut fraction = [0.0; 3];
+        for axis in 0..3 {
+            let size = self.dimensions[axis];
+            if size == 0 {
+                return 0.0;
+            }
+            let x = (coordinates[axis] * size as f64 - 0.5).clamp(0.0, (size - 1) as f64);
+            lower[axis] = x.floor() as usize;
+            upper[axis] = (lower[axis] + 1).min(size - 1);
+            fraction[axis] = x - x.floor();
+        }
+
+        let mut density = 0.0;
+        for corner in 0..8 {
+            let mut index = 0;
+            let mut weight = 1.0;
+            for axis in (0..3).rev() {
+                let (voxel, axis_weight) = if corner & (1 << axis) == 0 {
+                    (lower[axis], 1.0 - fraction[axis])
+                } else {
+                    (upper[axis], fraction[axis])
+                };
+                index = index * self.dimensions[axis] + voxel;
+                weight *= axis_weight;
+            }
+            density += weight * self.values[index];
+        }
+        density
+    }
+}
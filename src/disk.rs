@@ -0,0 +1,94 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{self, Point3, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// Represents a ray traceable flat disk.
+///
+/// The `u` surface coordinate goes once around the disk, and the `v` coordinate runs from the
+/// center out to the rim.
+#[derive(Clone)]
+pub struct Disk {
+    pub center: Point3,
+    pub normal: Vec3,
+    pub radius: f64,
+    pub material: Rc<dyn Material>,
+    tangent: Vec3,
+    bitangent: Vec3,
+}
+
+impl Disk {
+    /// Create a new disk facing in the direction of the given normal.
+    pub fn new(center: Point3, normal: Vec3, radius: f64, material: Rc<dyn Material>) -> Self {
+        let normal = normal.unit_vector();
+        let (tangent, bitangent) = math::orthonormal_basis(normal);
+
+        Self {
+            center,
+            normal,
+            radius: radius.max(0.0),
+            material,
+            tangent,
+            bitangent,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let denominator = self.normal.dot(ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let root = self.normal.dot(self.center - ray.origin) / denominator;
+        if !interval.surrounds(root) {
+            return None;
+        }
+
+        let hit_point = ray.at(root);
+        let planar = hit_point - self.center;
+        let distance = planar.length();
+        if distance > self.radius {
+            return None;
+        }
+
+        let x = planar.dot(self.tangent);
+        let y = planar.dot(self.bitangent);
+        let phi = y.atan2(x).rem_euclid(2.0 * PI);
+        let (sin_phi, cos_phi) = phi.sin_cos();
+
+        let mut record = HitRecord {
+            point: hit_point,
+            normal: self.normal,
+            u: phi / (2.0 * PI),
+            v: distance / self.radius,
+            dpdu: (self.bitangent * cos_phi - self.tangent * sin_phi) * (2.0 * PI * distance),
+            dpdv: (self.tangent * cos_phi + self.bitangent * sin_phi) * self.radius,
+            material: self.material.clone(),
+            time: root,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, self.normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Along each axis, the disk reaches as far as the radius scaled by how much of that axis
+        // lies in its plane.
+        let reach = |component: f64| self.radius * (1.0 - component * component).max(0.0).sqrt();
+        let extent = Vec3::new(
+            reach(self.normal.x),
+            reach(self.normal.y),
+            reach(self.normal.z),
+        );
+        Aabb::from_points(self.center - extent, self.center + extent)
+    }
+}
@@ -0,0 +1,61 @@
+use std::rc::Rc;
+
+use crate::color::Color;
+use crate::texture::Texture;
+
+/// The parameters of a glTF 2.0 material.
+///
+/// This mirrors the metallic roughness model of the specification along with the extensions for
+/// specular (`KHR_materials_specular`), sheen, clearcoat, transmission and refractive index. The
+/// defaults are those of the specification. There is no glTF loader, so this is not read from
+/// files. Reading the JSON and decoding the images is left to whatever loads the rest of the file,
+/// which fills this in, and until then materials can be written out by hand.
+///
+/// As in glTF, each texture is multiplied by its factor. The metallic roughness texture holds
+/// roughness in its green channel and metalness in its blue channel, and the other grayscale
+/// textures use their red channel, except for the clearcoat roughness texture which uses green.
+/// `KHR_materials_specular` keeps its specular strength in the alpha channel of its texture, but
+/// textures here have no alpha, so `specular_alpha_texture` holds that alpha channel decoded into
+/// the red channel of a texture of its own.
+#[derive(Clone)]
+pub struct GltfMaterial {
+    pub base_color_factor: Color,
+    pub base_color_texture: Option<Rc<dyn Texture>>,
+    pub metallic_factor: f64,
+    pub roughness_factor: f64,
+    pub metallic_roughness_texture: Option<Rc<dyn Texture>>,
+    pub specular_factor: f64,
+    pub specular_alpha_texture: Option<Rc<dyn Texture>>,
+    pub sheen_color_factor: Color,
+    pub sheen_color_texture: Option<Rc<dyn Texture>>,
+    pub clearcoat_factor: f64,
+    pub clearcoat_texture: Option<Rc<dyn Texture>>,
+    pub clearcoat_roughness_factor: f64,
+    pub clearcoat_roughness_texture: Option<Rc<dyn Texture>>,
+    pub transmission_factor: f64,
+    pub transmission_texture: Option<Rc<dyn Texture>>,
+    pub ior: f64,
+}
+
+impl Default for GltfMaterial {
+    fn default() -> Self {
+        Self {
+            base_color_factor: Color::new(1.0, 1.0, 1.0),
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+            specular_factor: 1.0,
+            specular_alpha_texture: None,
+            sheen_color_factor: Color::ZERO,
+            sheen_color_texture: None,
+            clearcoat_factor: 0.0,
+            clearcoat_texture: None,
+            clearcoat_roughness_factor: 0.0,
+            clearcoat_roughness_texture: None,
+            transmission_factor: 0.0,
+            transmission_texture: None,
+            ior: 1.5,
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::io::{Error, ErrorKind};
+use std::rc::Rc;
+
+use raytracing::color::{self, Color};
+use raytracing::image::pixmap::PixmapImage;
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{Point3, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+use crate::triangle;
+
+/// Represents a terrain given by a grid of heights.
+///
+/// The samples are laid out on a regular grid over a rectangle of the xz plane starting at
+/// `corner` and spanning `size.x` by `size.z` units, and each one is raised by its height times
+/// `size.y`. Every cell between four neighboring samples is split into two triangles. Rather than
+/// testing all of those, a ray walks across the grid one cell at a time, front to back, so only the
+/// cells it passes over are ever looked at.
+pub struct Heightfield {
+    pub corner: Point3,
+    pub size: Vec3,
+    pub material: Rc<dyn Material>,
+    resolution: [usize; 2],
+    heights: Box<[f64]>,
+    bounds: Aabb,
+}
+
+impl Heightfield {
+    /// Create a heightfield from a grid of heights.
+    ///
+    /// The heights are stored with the x index varying fastest. The resolution is the number of
+    /// samples along x and z, and each must be at least 2.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grid is too small or the number of heights does not match the resolution.
+    pub fn new(
+        resolution: [usize; 2],
+        heights: Vec<f64>,
+        corner: Point3,
+        size: Vec3,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        assert!(
+            resolution[0] >= 2 && resolution[1] >= 2,
+            "a heightfield needs at least two samples along each axis"
+        );
+        assert_eq!(
+            heights.len(),
+            resolution[0] * resolution[1],
+            "the number of heights does not match the resolution"
+        );
+
+        let (lowest, highest) = heights.iter().fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(lowest, highest), &h| (lowest.min(h), highest.max(h)),
+        );
+        let bounds = Aabb::from_points(
+            corner + Vec3::new(0.0, lowest * size.y, 0.0),
+            corner + Vec3::new(size.x, highest * size.y, size.z),
+        );
+
+        Self {
+            corner,
+            size,
+            material,
+            resolution,
+            heights: heights.into_boxed_slice(),
+            bounds,
+        }
+    }
+
+    /// Create a heightfield from a grayscale image.
+    ///
+    /// Each pixel becomes a sample whose height is its luminance, from 0 for black to 1 for
+    /// white. The top row of the image lies along the far edge of the terrain, at the smallest z.
+    ///
+    /// Fails if the image is narrower or shorter than two pixels, as there would be no cells
+    /// between the samples.
+    pub fn from_image(
+        image: &PixmapImage,
+        corner: Point3,
+        size: Vec3,
+        material: Rc<dyn Material>,
+    ) -> std::io::Result<Self> {
+        let (width, height) = (image.width(), image.height());
+        if width < 2 || height < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "a heightfield needs an image at least two pixels wide and tall".to_string(),
+            ));
+        }
+
+        let mut heights = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let (r, g, b) = image.pixel(x, y);
+                let pixel = Color::new(r as f64, g as f64, b as f64) / 255.0;
+                heights.push(color::luminance(pixel));
+            }
+        }
+        Ok(Self::new(
+            [width as usize, height as usize],
+            heights,
+            corner,
+            size,
+            material,
+        ))
+    }
+
+    /// Get the position of the sample with the given indices.
+    fn vertex(&self, i: usize, j: usize) -> Point3 {
+        let [columns, rows] = self.resolution;
+        self.corner
+            + Vec3::new(
+                self.size.x * i as f64 / (columns - 1) as f64,
+                self.size.y * self.heights[j * columns + i],
+                self.size.z * j as f64 / (rows - 1) as f64,
+            )
+    }
+
+    /// Intersect the ray with the two triangles of a single cell.
+    fn hit_cell(&self, ray: Ray, interval: Interval, i: usize, j: usize) -> Option<HitRecord> {
+        let corners = [
+            self.vertex(i, j),
+            self.vertex(i + 1, j),
+            self.vertex(i + 1, j + 1),
+            self.vertex(i, j + 1),
+        ];
+
+        let (time, vertices) = [
+            [corners[0], corners[2], corners[1]],
+            [corners[0], corners[3], corners[2]],
+        ]
+        .into_iter()
+        .filter_map(|vertices| {
+            let (time, _, _) = triangle::intersect(vertices, ray)?;
+            interval.surrounds(time).then_some((time, vertices))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))?;
+
+        let [p0, p1, p2] = vertices;
+        let outward_normal = (p1 - p0).cross(p2 - p0).unit_vector();
+
+        // The surface is the graph of a function over the xz plane, so its derivatives follow from
+        // the slope of the triangle along each axis.
+        let slope_x = -outward_normal.x / outward_normal.y;
+        let slope_z = -outward_normal.z / outward_normal.y;
+
+        let point = ray.at(time);
+        let mut record = HitRecord {
+            point,
+            normal: outward_normal,
+            u: (point.x - self.corner.x) / self.size.x,
+            v: (point.z - self.corner.z) / self.size.z,
+            dpdu: Vec3::new(1.0, slope_x, 0.0) * self.size.x,
+            dpdv: Vec3::new(0.0, slope_z, 1.0) * self.size.z,
+            material: self.material.clone(),
+            time,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, outward_normal);
+        Some(record)
+    }
+}
+
+impl Hittable for Heightfield {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let clipped = self.bounds.clip(ray, interval)?;
+
+        // Work in grid coordinates, where each cell is a unit square.
+        let [columns, rows] = self.resolution;
+        let cell_width = self.size.x / (columns - 1) as f64;
+        let cell_depth = self.size.z / (rows - 1) as f64;
+        let start = ray.at(clipped.min);
+        let grid_x = (start.x - self.corner.x) / cell_width;
+        let grid_z = (start.z - self.corner.z) / cell_depth;
+        let direction_x = ray.direction.x / cell_width;
+        let direction_z = ray.direction.z / cell_depth;
+
+        let mut i = (grid_x.floor().max(0.0) as usize).min(columns - 2);
+        let mut j = (grid_z.floor().max(0.0) as usize).min(rows - 2);
+
+        // This is a two dimensional digital differential analyzer. For each axis, keep track of
+        // the time at which the ray crosses into the next cell along it, and how much time it
+        // takes to cross a whole cell.
+        let setup = |position: f64, cell: usize, direction: f64| {
+            if direction > 0.0 {
+                (
+                    clipped.min + (cell as f64 + 1.0 - position) / direction,
+                    1.0 / direction,
+                )
+            } else if direction < 0.0 {
+                (
+                    clipped.min + (cell as f64 - position) / direction,
+                    -1.0 / direction,
+                )
+            } else {
+                (f64::INFINITY, f64::INFINITY)
+            }
+        };
+        let (mut next_x, delta_x) = setup(grid_x, i, direction_x);
+        let (mut next_z, delta_z) = setup(grid_z, j, direction_z);
+
+        loop {
+            if let Some(hit) = self.hit_cell(ray, clipped, i, j) {
+                return Some(hit);
+            }
+
+            if next_x < next_z {
+                if next_x > clipped.max {
+                    return None;
+                }
+                next_x += delta_x;
+                i = match direction_x > 0.0 {
+                    true if i + 2 < columns => i + 1,
+                    false if i > 0 => i - 1,
+                    _ => return None,
+                };
+            } else {
+                if next_z > clipped.max {
+                    return None;
+                }
+                next_z += delta_z;
+                j = match direction_z > 0.0 {
+                    true if j + 2 < rows => j + 1,
+                    false if j > 0 => j - 1,
+                    _ => return None,
+                };
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounds
+    }
+}
@@ -0,0 +1,77 @@
+use std::rc::Rc;
+
+use raytracing::density_grid::DensityGrid;
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{self, Point3, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// A volume of particles whose density varies through space.
+///
+/// The density comes from a voxel grid stretched over the bounding box, multiplied by a scale.
+/// Free-flight distances are sampled by delta tracking. Tentative collisions are generated as if
+/// the whole volume had the largest density found anywhere in it, and each one is accepted with
+/// probability equal to the actual density there over that maximum. Rejected collisions are
+/// fictitious and the ray carries on unchanged, which keeps the estimate unbiased.
+pub struct HeterogeneousMedium {
+    pub grid: Rc<DensityGrid>,
+    pub bounds: Aabb,
+    pub density_scale: f64,
+    pub phase_function: Rc<dyn Material>,
+}
+
+impl HeterogeneousMedium {
+    /// Look up the density of the medium at a point in space.
+    fn density(&self, point: Point3) -> f64 {
+        let local = |x: f64, extent: Interval| (x - extent.min) / extent.size();
+        let local = Point3::new(
+            local(point.x, self.bounds.x),
+            local(point.y, self.bounds.y),
+            local(point.z, self.bounds.z),
+        );
+        self.grid.sample(local) * self.density_scale
+    }
+}
+
+impl Hittable for HeterogeneousMedium {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let majorant = self.grid.max_value() * self.density_scale;
+        if majorant <= 0.0 {
+            return None;
+        }
+
+        let clipped = self.bounds.clip(ray, interval)?;
+        let ray_length = ray.direction.length();
+
+        let mut time = clipped.min;
+        loop {
+            time -= (1.0 - raytracing::random_f64()).ln() / (majorant * ray_length);
+            if time >= clipped.max {
+                return None;
+            }
+
+            let point = ray.at(time);
+            if raytracing::random_f64() * majorant < self.density(point) {
+                let (dpdu, dpdv) = math::orthonormal_basis(Vec3::new(1.0, 0.0, 0.0));
+                return Some(HitRecord {
+                    point,
+                    normal: Vec3::new(1.0, 0.0, 0.0), // Arbitrary
+                    u: 0.0,
+                    v: 0.0,
+                    dpdu,
+                    dpdv,
+                    material: self.phase_function.clone(),
+                    time,
+                    front_face: true, // Also arbitrary
+                });
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounds
+    }
+}
@@ -1,5 +1,6 @@
 use std::rc::Rc;
 
+use raytracing::math::aabb::Aabb;
 use raytracing::math::interval::Interval;
 use raytracing::math::ray::Ray;
 use raytracing::math::{Point3, Vec3};
@@ -17,6 +18,27 @@ pub struct HitRecord {
     /// The normal vector to the surface at the point of intersection.
     pub normal: Vec3,
 
+    /// The first surface coordinate of the point of intersection, which textures are looked up
+    /// by horizontally.
+    pub u: f64,
+
+    /// The second surface coordinate of the point of intersection, which textures are looked up
+    /// by vertically.
+    pub v: f64,
+
+    /// The partial derivative of the surface point with respect to `u`.
+    ///
+    /// Together with `dpdv` and the normal, this gives the tangent frame of the surface at the
+    /// point of intersection. It is not necessarily unit length or perpendicular to `dpdv`.
+    ///
+    /// Normal maps and anisotropic materials follow this tangent. Shapes without surface
+    /// coordinates have no meaningful one, and fill it from
+    /// [`math::orthonormal_basis`](raytracing::math::orthonormal_basis) instead.
+    pub dpdu: Vec3,
+
+    /// The partial derivative of the surface point with respect to `v`.
+    pub dpdv: Vec3,
+
     /// The material of the hit.
     pub material: Rc<dyn Material>,
 
@@ -52,4 +74,25 @@ pub trait Hittable {
     /// information about a potential hit. If there was no intersection, this method returns
     /// [`None`]. If there was, a [`Some`] variant is returned holding information about the hit.
     fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord>;
+
+    /// Find every place the ray crosses the surface of the shape.
+    ///
+    /// The crossings within the given interval are returned in the order the ray meets them. For
+    /// closed shapes, the [`HitRecord::front_face`] of each crossing tells whether the ray is
+    /// entering or leaving the shape there. By default, this repeatedly calls [`Hittable::hit`],
+    /// starting each search just past the previous crossing.
+    fn crossings(&self, ray: Ray, interval: Interval) -> Vec<HitRecord> {
+        let mut crossings = Vec::new();
+        let mut remaining = interval;
+        while let Some(hit) = self.hit(ray, remaining) {
+            remaining.min = hit.time;
+            crossings.push(hit);
+        }
+        crossings
+    }
+
+    /// Get a box which fully contains the shape.
+    ///
+    /// Shapes which extend infinitely far should return [`Aabb::UNIVERSE`].
+    fn bounding_box(&self) -> Aabb;
 }
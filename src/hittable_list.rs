@@ -1,5 +1,6 @@
 use std::rc::Rc;
 
+use raytracing::math::aabb::Aabb;
 use raytracing::math::interval::Interval;
 use raytracing::math::ray::Ray;
 
@@ -8,6 +9,7 @@ use crate::hittable::{HitRecord, Hittable};
 /// A simple wrapper structure for a list of hittable objects.
 pub struct HittableList {
     objects: Vec<Rc<dyn Hittable>>,
+    bounding_box: Aabb,
 }
 
 impl HittableList {
@@ -15,12 +17,14 @@ impl HittableList {
     pub fn new() -> Self {
         Self {
             objects: Vec::new(),
+            bounding_box: Aabb::EMPTY,
         }
     }
 
     /// Create a hittable list from a single object.
     pub fn _from_hittable(object: Rc<dyn Hittable>) -> Self {
         Self {
+            bounding_box: object.bounding_box(),
             objects: vec![object],
         }
     }
@@ -28,10 +32,12 @@ impl HittableList {
     /// Clear the list.
     pub fn _clear(&mut self) {
         self.objects.clear();
+        self.bounding_box = Aabb::EMPTY;
     }
 
     /// Push a new hittable into the list.
     pub fn add(&mut self, object: Rc<dyn Hittable>) {
+        self.bounding_box = self.bounding_box.union(object.bounding_box());
         self.objects.push(object);
     }
 }
@@ -46,14 +52,25 @@ impl Hittable for HittableList {
                 .as_ref()
                 .map(|rec| rec.time)
                 .unwrap_or(interval.max);
-            let smallest_interval = Interval::new(interval.min, closest_t);
+            let mut smallest_interval = Interval::new(interval.min, closest_t);
 
             // There is no need to trace the ray further than this value of t, since it already hit
             // something there. We need only check to see if it hit something closer.
-            if let Some(hit_info) = object.hit(ray, smallest_interval) {
-                closest_hit = Some(hit_info);
+            while let Some(hit_info) = object.hit(ray, smallest_interval) {
+                if !hit_info.material.is_cut_out(&hit_info) {
+                    closest_hit = Some(hit_info);
+                    break;
+                }
+
+                // The ray passes straight through holes in a surface, so the object may still be
+                // hit somewhere behind this point.
+                smallest_interval.min = hit_info.time;
             }
         }
         closest_hit
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounding_box
+    }
 }
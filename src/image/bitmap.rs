@@ -16,13 +16,13 @@ impl BitmapImage {
     ///
     /// # Examples
     ///
-    /// ```
-    /// use raytracing::bitmap::BitmapImage;
+    /// ```ignore
+    /// use raytracing::image::bitmap::BitmapImage;
     ///
     /// assert_eq!(BitmapImage::bytes_per_row(0), 0);
     /// assert_eq!(BitmapImage::bytes_per_row(1), 4);
-    /// assert_eq!(BitmapImage::bytes_per_row(2), 4);
-    /// assert_eq!(BitmapImage::bytes_per_row(3), 4);
+    /// assert_eq!(BitmapImage::bytes_per_row(2), 8);
+    /// assert_eq!(BitmapImage::bytes_per_row(3), 12);
     /// ```
     fn bytes_per_row(width: u16) -> usize {
         let unpadded = width as isize * 3;
@@ -102,3 +102,17 @@ impl Image for BitmapImage {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_are_padded_to_four_bytes() {
+        assert_eq!(BitmapImage::bytes_per_row(0), 0);
+        assert_eq!(BitmapImage::bytes_per_row(1), 4);
+        assert_eq!(BitmapImage::bytes_per_row(2), 8);
+        assert_eq!(BitmapImage::bytes_per_row(3), 12);
+        assert_eq!(BitmapImage::bytes_per_row(4), 12);
+    }
+}
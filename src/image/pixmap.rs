@@ -1,4 +1,4 @@
-use std::io::Write;
+use std::io::{Error, ErrorKind, Write};
 use std::path::Path;
 
 use crate::color::{self, Color};
@@ -10,6 +10,123 @@ pub struct PixmapImage {
     pixel_data: Box<[u8]>,
 }
 
+impl PixmapImage {
+    /// Load an image from a PPM file.
+    ///
+    /// Both the plain (`P3`) and raw (`P6`) variants of the format are accepted. Components are
+    /// rescaled so that the maximum value declared in the header maps to 255.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    /// Parse an image from the contents of a PPM file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::image::pixmap::PixmapImage;
+    ///
+    /// let image = PixmapImage::parse(b"P3\n# comment\n2 1\n255\n255 0 0\n0 0 255\n").unwrap();
+    ///
+    /// assert_eq!(image.width(), 2);
+    /// assert_eq!(image.pixel(1, 0), (0, 0, 255));
+    /// ```
+    pub fn parse(bytes: &[u8]) -> std::io::Result<Self> {
+        let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+
+        let magic = &bytes[..bytes.len().min(2)];
+        if magic != b"P3" && magic != b"P6" {
+            return Err(invalid("missing PPM magic number"));
+        }
+
+        let mut cursor = 2;
+        let mut next_number =
+            || Self::next_number(bytes, &mut cursor).ok_or_else(|| invalid("malformed PPM data"));
+
+        let width = next_number()?;
+        let height = next_number()?;
+        let max_value = next_number()?;
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(invalid("PPM image is too large"));
+        }
+        if max_value == 0 || max_value > u16::MAX as usize {
+            return Err(invalid("PPM maximum value is out of range"));
+        }
+
+        let component_count = width * height * 3;
+        let rescale = |value: usize| (value.min(max_value) * 255 / max_value) as u8;
+        let components: Vec<u8> = if magic == b"P3" {
+            (0..component_count)
+                .map(|_| next_number().map(rescale))
+                .collect::<std::io::Result<_>>()?
+        } else {
+            // A single whitespace character separates the header from the binary data. Values
+            // larger than 255 take two bytes and are stored big endian.
+            let data = bytes.get(cursor + 1..).unwrap_or_default();
+            let bytes_per_value = if max_value > 255 { 2 } else { 1 };
+            if data.len() < component_count * bytes_per_value {
+                return Err(invalid("PPM pixel data is truncated"));
+            }
+            data.chunks_exact(bytes_per_value)
+                .take(component_count)
+                .map(|chunk| rescale(chunk.iter().fold(0, |acc, &b| acc << 8 | b as usize)))
+                .collect()
+        };
+
+        Ok(Self {
+            width: width as u16,
+            height: height as u16,
+            pixel_data: components.into_boxed_slice(),
+        })
+    }
+
+    /// Read the next whitespace separated number from plain PPM data, skipping comments.
+    fn next_number(bytes: &[u8], cursor: &mut usize) -> Option<usize> {
+        // Comments run from a '#' to the end of the line and may appear anywhere whitespace could.
+        loop {
+            match bytes.get(*cursor)? {
+                b'#' => {
+                    while bytes.get(*cursor).is_some_and(|&b| b != b'\n') {
+                        *cursor += 1;
+                    }
+                }
+                b if b.is_ascii_whitespace() => *cursor += 1,
+                _ => break,
+            }
+        }
+
+        let start = *cursor;
+        while bytes.get(*cursor).is_some_and(|b| b.is_ascii_digit()) {
+            *cursor += 1;
+        }
+        std::str::from_utf8(&bytes[start..*cursor])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Get the stored components of the pixel at the specified location.
+    pub fn pixel(&self, x: u16, y: u16) -> (u8, u8, u8) {
+        let index = (y as usize * self.width as usize + x as usize) * 3;
+
+        (
+            self.pixel_data[index],
+            self.pixel_data[index + 1],
+            self.pixel_data[index + 2],
+        )
+    }
+}
+
 impl Image for PixmapImage {
     fn blank(width: u16, height: u16) -> Self {
         let data_size = width as usize * height as usize * 3;
@@ -0,0 +1,165 @@
+use crate::math::aabb::Aabb;
+use crate::math::interval::Interval;
+use crate::math::{Point3, Vec3};
+
+/// A surface given implicitly as the set of points where a function is zero.
+///
+/// The function should be negative inside the surface and positive outside. Unlike a signed
+/// distance function, its value does not need to mean anything beyond its sign, but it does need
+/// to be smooth for the gradient to make a good normal.
+///
+/// Any closure taking a point and returning a number is an implicit function, with its gradient
+/// estimated by finite differences.
+pub trait Implicit {
+    /// Evaluate the function at a point.
+    fn value(&self, point: Point3) -> f64;
+
+    /// Get the gradient of the function at a point.
+    ///
+    /// On the surface, the gradient points outward, so it is the direction of the normal. By
+    /// default, it is estimated with central differences.
+    fn gradient(&self, point: Point3) -> Vec3 {
+        const H: f64 = 1e-5;
+
+        let difference =
+            |offset: Vec3| (self.value(point + offset) - self.value(point - offset)) / (2.0 * H);
+        Vec3::new(
+            difference(Vec3::new(H, 0.0, 0.0)),
+            difference(Vec3::new(0.0, H, 0.0)),
+            difference(Vec3::new(0.0, 0.0, H)),
+        )
+    }
+
+    /// Bound the values the function takes over a box.
+    ///
+    /// If the returned interval does not contain zero, the surface cannot pass through the box,
+    /// which lets root finders skip over it. Returning [`None`] means no bound is known.
+    fn range(&self, _region: Aabb) -> Option<Interval> {
+        None
+    }
+}
+
+impl<F: Fn(Point3) -> f64> Implicit for F {
+    fn value(&self, point: Point3) -> f64 {
+        self(point)
+    }
+}
+
+/// A single blob of a [`Metaballs`] surface.
+#[derive(Clone, Copy)]
+pub struct Metaball {
+    pub center: Point3,
+
+    /// The distance beyond which the blob has no influence at all.
+    pub radius: f64,
+    pub strength: f64,
+}
+
+impl Metaball {
+    /// Evaluate the field of the blob at the given squared distance from its center.
+    ///
+    /// This is the polynomial falloff of Wyvill et al., which drops smoothly to zero at the radius
+    /// and stays there.
+    fn field(&self, distance_squared: f64) -> f64 {
+        let x = 1.0 - distance_squared / (self.radius * self.radius);
+        if x <= 0.0 {
+            0.0
+        } else {
+            self.strength * x * x * x
+        }
+    }
+}
+
+/// A blobby surface made of blobs which melt into each other.
+///
+/// Each blob contributes a field which is strongest at its center and falls off with distance.
+/// The surface is where the total field equals the threshold, so nearby blobs bulge toward each
+/// other and merge.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::implicit::{Implicit, Metaball, Metaballs};
+/// use raytracing::math::Point3;
+///
+/// let blob = Metaballs {
+///     balls: vec![Metaball {
+///         center: Point3::ZERO,
+///         radius: 1.0,
+///         strength: 1.0,
+///     }],
+///     threshold: 0.125,
+/// };
+///
+/// assert!(blob.value(Point3::ZERO) < 0.0);
+/// assert!(blob.value(Point3::new(0.9, 0.0, 0.0)) > 0.0);
+/// ```
+pub struct Metaballs {
+    pub balls: Vec<Metaball>,
+    pub threshold: f64,
+}
+
+impl Metaballs {
+    /// Get a box containing every point where the field is nonzero.
+    pub fn bounding_box(&self) -> Aabb {
+        self.balls.iter().fold(Aabb::EMPTY, |bounds, ball| {
+            let extent = Vec3::new(ball.radius, ball.radius, ball.radius);
+            bounds.union(Aabb::from_points(
+                ball.center - extent,
+                ball.center + extent,
+            ))
+        })
+    }
+}
+
+impl Implicit for Metaballs {
+    fn value(&self, point: Point3) -> f64 {
+        let field: f64 = self
+            .balls
+            .iter()
+            .map(|ball| ball.field((point - ball.center).length_squared()))
+            .sum();
+        self.threshold - field
+    }
+
+    fn gradient(&self, point: Point3) -> Vec3 {
+        let mut gradient = Vec3::ZERO;
+        for ball in &self.balls {
+            let offset = point - ball.center;
+            let r2 = ball.radius * ball.radius;
+            let x = 1.0 - offset.length_squared() / r2;
+            if x > 0.0 {
+                gradient += offset * (6.0 * ball.strength * x * x / r2);
+            }
+        }
+        gradient
+    }
+
+    fn range(&self, region: Aabb) -> Option<Interval> {
+        // The field of each blob only depends on the distance to its center and shrinks as that
+        // grows, so it is bounded by its values at the nearest and farthest points of the box.
+        let mut low = 0.0;
+        let mut high = 0.0;
+        for ball in &self.balls {
+            let mut nearest = 0.0;
+            let mut farthest = 0.0;
+            for axis in 0..3 {
+                let extent = region.axis(axis);
+                let c = [ball.center.x, ball.center.y, ball.center.z][axis];
+                let near = if c < extent.min {
+                    extent.min - c
+                } else if c > extent.max {
+                    c - extent.max
+                } else {
+                    0.0
+                };
+                let far = (c - extent.min).abs().max((c - extent.max).abs());
+                nearest += near * near;
+                farthest += far * far;
+            }
+            low += ball.field(farthest);
+            high += ball.field(nearest);
+        }
+        Some(Interval::new(self.threshold - high, self.threshold - low))
+    }
+}
@@ -0,0 +1,219 @@
+use std::rc::Rc;
+
+use raytracing::implicit::Implicit;
+use raytracing::math;
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// Represents a surface where an implicit function is zero, such as a metaball surface.
+///
+/// The part of the ray inside the bounds is cut into segments, which are searched front to back
+/// for a change of sign. Where the function can bound its own values, segments which cannot
+/// contain the surface are skipped and doubtful ones are split further, so that thin features
+/// between two samples are not missed. A bracketed root is then refined with Newton's method,
+/// falling back to bisection whenever a Newton step would leave the bracket. Normals come from the
+/// gradient of the function.
+pub struct ImplicitSurface {
+    pub function: Rc<dyn Implicit>,
+    pub bounds: Aabb,
+    pub material: Rc<dyn Material>,
+
+    /// The number of segments the ray is initially cut into.
+    pub segments: u32,
+}
+
+impl ImplicitSurface {
+    /// How many times a doubtful segment may be split in half.
+    const MAX_SUBDIVISIONS: u32 = 8;
+
+    /// Create a new implicit surface with a reasonable number of segments.
+    pub fn new(function: Rc<dyn Implicit>, bounds: Aabb, material: Rc<dyn Material>) -> Self {
+        Self {
+            function,
+            bounds,
+            material,
+            segments: 64,
+        }
+    }
+
+    /// Find the first root of the function along the ray between the two times which lies within
+    /// the interval.
+    fn isolate(
+        &self,
+        ray: Ray,
+        interval: Interval,
+        start: f64,
+        end: f64,
+        depth: u32,
+    ) -> Option<f64> {
+        let value_at_start = self.function.value(ray.at(start));
+        let value_at_end = self.function.value(ray.at(end));
+        if value_at_start == 0.0 {
+            if interval.surrounds(start) {
+                return Some(start);
+            }
+            return self.isolate_past(ray, interval, start, end, depth);
+        }
+        if value_at_start.signum() != value_at_end.signum() {
+            let root = self.refine(ray, start, end, value_at_start);
+            if interval.surrounds(root) {
+                return Some(root);
+            }
+            return self.isolate_past(ray, interval, root, end, depth);
+        }
+
+        // Without a sign change, there could still be an even number of roots in the segment.
+        // That is only worth looking into when the function says it might be zero somewhere here.
+        let region = Aabb::from_points(ray.at(start), ray.at(end));
+        let range = self.function.range(region)?;
+        if !range.contains(0.0) || depth >= Self::MAX_SUBDIVISIONS {
+            return None;
+        }
+
+        let middle = (start + end) / 2.0;
+        self.isolate(ray, interval, start, middle, depth + 1)
+            .or_else(|| self.isolate(ray, interval, middle, end, depth + 1))
+    }
+
+    /// Find the first root within the interval past a root at `start` which is not, like the
+    /// point a secondary ray leaves the surface from.
+    ///
+    /// The sign of the function at a root says nothing about the rest of the segment, so the
+    /// segment is split in half until the part next to the root is too small to search.
+    fn isolate_past(
+        &self,
+        ray: Ray,
+        interval: Interval,
+        start: f64,
+        end: f64,
+        depth: u32,
+    ) -> Option<f64> {
+        if depth >= Self::MAX_SUBDIVISIONS {
+            return None;
+        }
+        let middle = (start + end) / 2.0;
+        self.isolate(ray, interval, start, middle, depth + 1)
+            .or_else(|| self.isolate(ray, interval, middle, end, depth + 1))
+    }
+
+    /// Refine a root bracketed between the two times.
+    fn refine(&self, ray: Ray, mut start: f64, mut end: f64, value_at_start: f64) -> f64 {
+        let start_sign = value_at_start.signum();
+        let mut time = (start + end) / 2.0;
+
+        for _ in 0..64 {
+            let point = ray.at(time);
+            let value = self.function.value(point);
+            if value == 0.0 || end - start < 1e-10 {
+                break;
+            }
+
+            if value.signum() == start_sign {
+                start = time;
+            } else {
+                end = time;
+            }
+
+            // The derivative along the ray is the gradient in the direction of the ray.
+            let slope = self.function.gradient(point).dot(ray.direction);
+            let newton = time - value / slope;
+            time = if slope != 0.0 && start < newton && newton < end {
+                newton
+            } else {
+                (start + end) / 2.0
+            };
+        }
+        time
+    }
+}
+
+impl Hittable for ImplicitSurface {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let clipped = self.bounds.clip(ray, interval)?;
+        let step = clipped.size() / self.segments as f64;
+
+        let root = (0..self.segments).find_map(|segment| {
+            let start = clipped.min + step * segment as f64;
+            self.isolate(ray, interval, start, start + step, 0)
+        })?;
+
+        let point = ray.at(root);
+        let gradient = self.function.gradient(point);
+        let outward_normal = if gradient.is_near_zero() {
+            -ray.direction.unit_vector()
+        } else {
+            gradient.unit_vector()
+        };
+        let (dpdu, dpdv) = math::orthonormal_basis(outward_normal);
+
+        let mut record = HitRecord {
+            point,
+            normal: outward_normal,
+            u: 0.0,
+            v: 0.0,
+            dpdu,
+            dpdv,
+            material: self.material.clone(),
+            time: root,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, outward_normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raytracing::color::Color;
+    use raytracing::math::{Point3, Vec3};
+
+    use super::*;
+    use crate::renderer::material::Lambertian;
+
+    #[test]
+    fn hit_looks_past_a_root_at_the_start_of_the_interval() {
+        let material = Rc::new(Lambertian {
+            albedo: Color::ZERO,
+        });
+        let sphere = ImplicitSurface::new(
+            Rc::new(|point: Point3| point.length_squared() - 1.0),
+            Aabb::from_points(Point3::new(-2.0, -2.0, -2.0), Point3::new(2.0, 2.0, 2.0)),
+            material,
+        );
+
+        // The ray leaves from the surface, so the function is exactly zero where it starts.
+        let ray = Ray::new(Point3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = sphere.hit(ray, Interval::new(0.0, f64::INFINITY));
+        assert!(hit.is_some_and(|hit| (hit.time - 2.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn hit_finds_a_second_root_in_the_same_segment() {
+        let material = Rc::new(Lambertian {
+            albedo: Color::ZERO,
+        });
+        let sphere = ImplicitSurface {
+            segments: 1,
+            ..ImplicitSurface::new(
+                Rc::new(|point: Point3| point.length_squared() - 1.0),
+                Aabb::from_points(Point3::new(-2.0, -2.0, -2.0), Point3::new(2.0, 2.0, 2.0)),
+                material,
+            )
+        };
+
+        // Both sides of the sphere are in the one segment, and the near one is where the ray
+        // leaves from.
+        let ray = Ray::new(Point3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = sphere.hit(ray, Interval::new(0.0, f64::INFINITY));
+        assert!(hit.is_some_and(|hit| (hit.time - 2.0).abs() < 1e-9));
+    }
+}
@@ -1,7 +1,20 @@
+pub mod atmosphere;
+pub mod bvh;
 pub mod camera;
 pub mod color;
+pub mod density_grid;
+pub mod gltf;
 pub mod image;
+pub mod implicit;
 pub mod math;
+pub mod mesh;
+pub mod microfacet;
+pub mod mtl;
+pub mod patches;
+pub mod point_cloud;
+pub mod sdf;
+pub mod spectrum;
+pub mod texture;
 pub mod ui;
 
 pub const TARGET_ASPECT_RATIO: f64 = 16.0 / 9.0;
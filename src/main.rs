@@ -1,66 +1,86 @@
-use std::rc::Rc;
+use std::io::{Error, ErrorKind};
 
-use raytracing::camera::Camera;
-use raytracing::color::Color;
 use raytracing::image::Image;
 use raytracing::image::bitmap::BitmapImage;
-use raytracing::math::{Point3, Vec3};
-
-use crate::hittable_list::HittableList;
-use crate::renderer::material::{Lambertian, Metal};
-use crate::sphere::Sphere;
 
+mod bezier_patch;
+mod cone;
+mod constant_medium;
+mod csg;
+mod cuboid;
+mod curve;
+mod cylinder;
+mod disk;
+mod heightfield;
+mod heterogeneous_medium;
 mod hittable;
 mod hittable_list;
+mod implicit_surface;
+mod plane;
+mod points;
+mod quad;
+mod quadric;
+mod ray_marched;
 mod renderer;
+mod scenes;
 mod sphere;
+mod torus;
+mod translucent;
+mod triangle;
+mod triangle_mesh;
 
 fn main() -> std::io::Result<()> {
-    let camera = Camera {
-        position: Vec3::ZERO,
-        focal_length: 1.0,
-        viewport_height: 2.0,
-        viewport_width: 32.0 / 9.0,
-    };
-
-    let material_ground = Rc::new(Lambertian {
-        albedo: Color::new(0.8, 0.8, 0.0),
-    });
-    let material_center = Rc::new(Lambertian {
-        albedo: Color::new(0.1, 0.2, 0.5),
-    });
-    let material_left = Rc::new(Metal {
-        albedo: Color::new(0.8, 0.8, 0.8),
-    });
-    let material_right = Rc::new(Metal {
-        albedo: Color::new(0.8, 0.6, 0.2),
-    });
-
-    let world = {
-        let mut world = HittableList::new();
-        world.add(Rc::new(Sphere::new(
-            Point3::new(0.0, -100.5, -1.0),
-            100.0,
-            material_ground,
-        )));
-        world.add(Rc::new(Sphere::new(
-            Point3::new(0.0, 0.0, -1.2),
-            0.5,
-            material_center,
-        )));
-        world.add(Rc::new(Sphere::new(
-            Point3::new(-1.0, 0.0, -1.0),
-            0.5,
-            material_left,
-        )));
-        world.add(Rc::new(Sphere::new(
-            Point3::new(1.0, 0.0, -1.0),
-            0.5,
-            material_right,
-        )));
-        world
+    // The scene to render can be picked by name on the command line, and any scene can be
+    // rendered spectrally with `--spectral`.
+    let (flags, names): (Vec<String>, Vec<String>) = std::env::args()
+        .skip(1)
+        .partition(|arg| arg.starts_with("--"));
+    if let Some(flag) = flags.iter().find(|flag| *flag != "--spectral") {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("unknown option `{flag}`"),
+        ));
+    }
+    let spectral = !flags.is_empty();
+    let scene = match names.first().map(String::as_str) {
+        None | Some("spheres") => scenes::spheres(),
+        Some("surface-detail") => scenes::surface_detail()?,
+        Some("cutout") => scenes::cutout(),
+        Some("shapes") => scenes::shapes(),
+        Some("pipes") => scenes::pipes(),
+        Some("carved") => scenes::carved(),
+        Some("distance-fields") => scenes::distance_fields(),
+        Some("smoke") => scenes::smoke(),
+        Some("cloud") => scenes::cloud(),
+        Some("landscape") => scenes::landscape(),
+        Some("terrain") => scenes::terrain()?,
+        Some("blobs") => scenes::blobs(),
+        Some("fur") => scenes::fur(),
+        Some("scan") => scenes::scan()?,
+        Some("quadrics") => scenes::quadrics()?,
+        Some("subdivision") => scenes::subdivision()?,
+        Some("microfacets") => scenes::microfacets(),
+        Some("principled") => scenes::principled(),
+        Some("clay") => scenes::clay(),
+        Some("coated") => scenes::coated(),
+        Some("iridescence") => scenes::iridescence(),
+        Some("dispersion") => scenes::dispersion(),
+        Some("subsurface") => scenes::subsurface(),
+        Some("layers") => scenes::layers(),
+        Some("brushed") => scenes::brushed(),
+        Some(name) => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unknown scene `{name}`"),
+            ));
+        }
     };
 
-    let image: BitmapImage = renderer::render_scene(camera, &world);
+    let image: BitmapImage = renderer::render_scene(
+        scene.camera,
+        &scene.world,
+        scene.atmosphere.as_ref(),
+        scene.spectral || spectral,
+    );
     image.export("image.ppm")
 }
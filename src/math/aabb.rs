@@ -0,0 +1,149 @@
+use crate::math::Point3;
+use crate::math::interval::Interval;
+use crate::math::ray::Ray;
+
+/// An axis-aligned bounding box.
+///
+/// The box is stored as the product of one interval per axis. Bounding boxes are cheap to test a
+/// ray against, so they are used to avoid expensive intersection tests with whatever is inside.
+#[derive(Clone, Copy)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Aabb {
+    /// The empty box, which contains no points.
+    pub const EMPTY: Aabb = Aabb::new(Interval::EMPTY, Interval::EMPTY, Interval::EMPTY);
+
+    /// The box containing all of space.
+    pub const UNIVERSE: Aabb =
+        Aabb::new(Interval::UNIVERSE, Interval::UNIVERSE, Interval::UNIVERSE);
+
+    /// The smallest extent a box is allowed to have along any axis.
+    ///
+    /// Flat shapes like quads would otherwise have boxes of zero thickness, which rays can slip
+    /// past due to rounding.
+    const MINIMUM_EXTENT: f64 = 1e-4;
+
+    /// Create a box from its extent along each axis.
+    pub const fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Create the smallest box containing both of the given points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::math::Point3;
+    /// use raytracing::math::aabb::Aabb;
+    ///
+    /// let aabb = Aabb::from_points(Point3::new(1.0, 0.0, 2.0), Point3::new(0.0, 1.0, 0.0));
+    ///
+    /// assert_eq!(aabb.x.min, 0.0);
+    /// assert_eq!(aabb.z.max, 2.0);
+    /// ```
+    pub fn from_points(a: Point3, b: Point3) -> Self {
+        let span = |a: f64, b: f64| Interval::new(a.min(b), a.max(b));
+        Self::new(span(a.x, b.x), span(a.y, b.y), span(a.z, b.z)).padded()
+    }
+
+    /// Create the smallest box containing both of the given boxes.
+    pub fn union(self, other: Aabb) -> Self {
+        Self::new(
+            self.x.union(other.x),
+            self.y.union(other.y),
+            self.z.union(other.z),
+        )
+    }
+
+    /// Get the extent of the box along the axis with the given index.
+    pub fn axis(&self, index: usize) -> Interval {
+        match index {
+            0 => self.x,
+            1 => self.y,
+            _ => self.z,
+        }
+    }
+
+    /// Get the index of the axis along which the box is largest.
+    pub fn longest_axis(&self) -> usize {
+        if self.x.size() > self.y.size() {
+            if self.x.size() > self.z.size() { 0 } else { 2 }
+        } else if self.y.size() > self.z.size() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Get the center of the box.
+    pub fn centroid(&self) -> Point3 {
+        Point3::new(
+            (self.x.min + self.x.max) / 2.0,
+            (self.y.min + self.y.max) / 2.0,
+            (self.z.min + self.z.max) / 2.0,
+        )
+    }
+
+    /// Determine whether the two boxes share any points.
+    pub fn overlaps(&self, other: Aabb) -> bool {
+        (0..3).all(|axis| {
+            let (a, b) = (self.axis(axis), other.axis(axis));
+            a.min <= b.max && b.min <= a.max
+        })
+    }
+
+    /// Determine whether the ray passes through the box within the given interval.
+    ///
+    /// This is the slab method. The ray is clipped against the pair of planes bounding each axis
+    /// in turn, and if what remains of the interval ever becomes empty, the ray missed.
+    pub fn hit(&self, ray: Ray, interval: Interval) -> bool {
+        self.clip(ray, interval).is_some()
+    }
+
+    /// Clip the interval to the part of the ray inside the box.
+    ///
+    /// Returns [`None`] if the ray does not pass through the box within the interval.
+    pub fn clip(&self, ray: Ray, mut interval: Interval) -> Option<Interval> {
+        let origin = [ray.origin.x, ray.origin.y, ray.origin.z];
+        let direction = [ray.direction.x, ray.direction.y, ray.direction.z];
+
+        for (axis, (origin, direction)) in origin.into_iter().zip(direction).enumerate() {
+            let slab = self.axis(axis);
+            let inverse_direction = 1.0 / direction;
+
+            let t0 = (slab.min - origin) * inverse_direction;
+            let t1 = (slab.max - origin) * inverse_direction;
+            let (near, far) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+
+            // Comparing this way round keeps the interval unchanged when a bound is NaN, which
+            // happens when the ray lies exactly in the plane of a slab.
+            if near > interval.min {
+                interval.min = near;
+            }
+            if far < interval.max {
+                interval.max = far;
+            }
+            if interval.max <= interval.min {
+                return None;
+            }
+        }
+
+        Some(interval)
+    }
+
+    /// Make sure the box has some thickness along every axis.
+    fn padded(self) -> Self {
+        let pad = |interval: Interval| {
+            if interval.size() < Self::MINIMUM_EXTENT {
+                interval.expand(Self::MINIMUM_EXTENT)
+            } else {
+                interval
+            }
+        };
+        Self::new(pad(self.x), pad(self.y), pad(self.z))
+    }
+}
@@ -0,0 +1,77 @@
+use crate::math::{Point3, Vec3};
+
+/// Evaluate the cubic Bézier curve with the given control points.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::math::{Point3, bezier};
+///
+/// let points = [
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(2.0, 0.0, 0.0),
+///     Point3::new(3.0, 0.0, 0.0),
+/// ];
+///
+/// assert_eq!(bezier::evaluate(points, 0.5).x, 1.5);
+/// ```
+pub fn evaluate(points: [Point3; 4], u: f64) -> Point3 {
+    // De Casteljau's algorithm is slower than expanding the Bernstein polynomials, but much
+    // better behaved numerically.
+    let lerp = |a: Point3, b: Point3| a * (1.0 - u) + b * u;
+    let [p0, p1, p2, p3] = points;
+    let (a, b, c) = (lerp(p0, p1), lerp(p1, p2), lerp(p2, p3));
+    let (d, e) = (lerp(a, b), lerp(b, c));
+    lerp(d, e)
+}
+
+/// Get the derivative of the cubic Bézier curve with the given control points.
+pub fn derivative(points: [Point3; 4], u: f64) -> Vec3 {
+    let lerp = |a: Vec3, b: Vec3| a * (1.0 - u) + b * u;
+    let [p0, p1, p2, p3] = points;
+    let (a, b, c) = (p1 - p0, p2 - p1, p3 - p2);
+    lerp(lerp(a, b), lerp(b, c)) * 3.0
+}
+
+/// Split a cubic Bézier curve in half.
+///
+/// The control points of the halves from `u = 0` to `u = 0.5` and from `u = 0.5` to `u = 1` are
+/// returned, in that order.
+pub fn split(points: [Point3; 4]) -> ([Point3; 4], [Point3; 4]) {
+    let middle = |a: Point3, b: Point3| (a + b) / 2.0;
+    let [p0, p1, p2, p3] = points;
+    let (a, b, c) = (middle(p0, p1), middle(p1, p2), middle(p2, p3));
+    let (d, e) = (middle(a, b), middle(b, c));
+    let center = middle(d, e);
+    ([p0, a, d, center], [center, e, c, p3])
+}
+
+/// The control points of a bicubic Bézier patch.
+///
+/// The `u` coordinate runs along each row of control points, and `v` from one row to the next.
+pub type Patch = [[Point3; 4]; 4];
+
+/// Evaluate a bicubic Bézier patch.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::math::{Point3, bezier};
+///
+/// let patch = std::array::from_fn(|v| {
+///     std::array::from_fn(|u| Point3::new(u as f64, v as f64, 0.0))
+/// });
+///
+/// assert_eq!(bezier::evaluate_patch(&patch, 0.5, 1.0).y, 3.0);
+/// ```
+pub fn evaluate_patch(patch: &Patch, u: f64, v: f64) -> Point3 {
+    evaluate(patch.map(|row| evaluate(row, u)), v)
+}
+
+/// Get the partial derivatives of a bicubic Bézier patch with respect to `u` and `v`.
+pub fn patch_derivatives(patch: &Patch, u: f64, v: f64) -> (Vec3, Vec3) {
+    let column = patch.map(|row| evaluate(row, u));
+    let tangents = patch.map(|row| derivative(row, u));
+    (evaluate(tangents, v), derivative(column, v))
+}
@@ -0,0 +1,73 @@
+use crate::math::aabb::Aabb;
+use crate::math::ray::Ray;
+use crate::math::{self, Point3, Vec3};
+
+/// An orthonormal coordinate system positioned somewhere in space.
+///
+/// Shapes which are easiest to intersect in a canonical position, like a cylinder standing on the
+/// xy plane, can use a frame to move rays into their own coordinates and results back out.
+#[derive(Clone, Copy)]
+pub struct Frame {
+    pub origin: Point3,
+    pub x: Vec3,
+    pub y: Vec3,
+    pub z: Vec3,
+}
+
+impl Frame {
+    /// Create a frame at the given origin whose z axis points along `axis`.
+    pub fn from_axis(origin: Point3, axis: Vec3) -> Self {
+        let z = axis.unit_vector();
+        let (x, y) = math::orthonormal_basis(z);
+        Self { origin, x, y, z }
+    }
+
+    /// Express a direction given in world coordinates in this frame.
+    pub fn vector_to_local(&self, vector: Vec3) -> Vec3 {
+        Vec3::new(vector.dot(self.x), vector.dot(self.y), vector.dot(self.z))
+    }
+
+    /// Express a direction given in this frame in world coordinates.
+    pub fn vector_to_world(&self, vector: Vec3) -> Vec3 {
+        self.x * vector.x + self.y * vector.y + self.z * vector.z
+    }
+
+    /// Express a point given in world coordinates in this frame.
+    pub fn point_to_local(&self, point: Point3) -> Point3 {
+        self.vector_to_local(point - self.origin)
+    }
+
+    /// Express a point given in this frame in world coordinates.
+    pub fn point_to_world(&self, point: Point3) -> Point3 {
+        self.origin + self.vector_to_world(point)
+    }
+
+    /// Express a ray given in world coordinates in this frame.
+    ///
+    /// Since the frame is orthonormal, distances along the ray are the same in both coordinate
+    /// systems.
+    pub fn ray_to_local(&self, ray: Ray) -> Ray {
+        Ray::new(
+            self.point_to_local(ray.origin),
+            self.vector_to_local(ray.direction),
+        )
+    }
+
+    /// Find a box in world coordinates which contains a box given in this frame.
+    pub fn box_to_world(&self, local: Aabb) -> Aabb {
+        let mut bounds = Aabb::EMPTY;
+        for corner in 0..8 {
+            let pick = |axis: usize| {
+                let extent = local.axis(axis);
+                if corner & (1 << axis) == 0 {
+                    extent.min
+                } else {
+                    extent.max
+                }
+            };
+            let point = self.point_to_world(Point3::new(pick(0), pick(1), pick(2)));
+            bounds = bounds.union(Aabb::from_points(point, point));
+        }
+        bounds
+    }
+}
@@ -33,6 +33,17 @@ impl Interval {
         Self { min, max }
     }
 
+    /// Create the smallest interval containing both of the given intervals.
+    pub fn union(self, other: Interval) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Grow the interval by the given amount, split evenly between both ends.
+    pub fn expand(self, delta: f64) -> Self {
+        let padding = delta / 2.0;
+        Self::new(self.min - padding, self.max + padding)
+    }
+
     /// Get the size of the interval.
     ///
     /// This method retrieves the size of this interval. The size is positive if `self.min <
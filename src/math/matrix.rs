@@ -0,0 +1,145 @@
+use crate::math::{Point3, Vec3};
+
+/// A 4x4 matrix, stored as an array of rows.
+///
+/// Points are treated as the column `(x, y, z, 1)` and directions as `(x, y, z, 0)`, so that
+/// points are affected by translations and directions are not.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat4 {
+    pub rows: [[f64; 4]; 4],
+}
+
+impl Mat4 {
+    /// The identity matrix.
+    pub const IDENTITY: Mat4 = Mat4::new([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    /// Create a matrix from its rows.
+    pub const fn new(rows: [[f64; 4]; 4]) -> Self {
+        Self { rows }
+    }
+
+    /// Create a matrix which translates points by the given offset.
+    pub fn translation(offset: Vec3) -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, offset.x],
+            [0.0, 1.0, 0.0, offset.y],
+            [0.0, 0.0, 1.0, offset.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Create a matrix which scales each axis by the given factor.
+    pub fn scaling(factors: Vec3) -> Self {
+        Self::new([
+            [factors.x, 0.0, 0.0, 0.0],
+            [0.0, factors.y, 0.0, 0.0],
+            [0.0, 0.0, factors.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Swap the rows and columns of the matrix.
+    pub fn transpose(&self) -> Self {
+        let mut rows = [[0.0; 4]; 4];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, entry) in row.iter_mut().enumerate() {
+                *entry = self.rows[j][i];
+            }
+        }
+        Self::new(rows)
+    }
+
+    /// Multiply the matrix by a column vector.
+    pub fn apply(&self, vector: [f64; 4]) -> [f64; 4] {
+        self.rows
+            .map(|row| row.iter().zip(vector).map(|(a, b)| a * b).sum())
+    }
+
+    /// Transform a point by the matrix, dividing through by the resulting `w` coordinate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::math::Vec3;
+    /// use raytracing::math::matrix::Mat4;
+    ///
+    /// let matrix = Mat4::translation(Vec3::new(1.0, 2.0, 3.0));
+    ///
+    /// assert_eq!(matrix.transform_point(Vec3::ZERO).z, 3.0);
+    /// assert_eq!(matrix.transform_vector(Vec3::ZERO).z, 0.0);
+    /// ```
+    pub fn transform_point(&self, point: Point3) -> Point3 {
+        let [x, y, z, w] = self.apply([point.x, point.y, point.z, 1.0]);
+        Point3::new(x, y, z) / w
+    }
+
+    /// Transform a direction by the matrix, ignoring any translation.
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        let [x, y, z, _] = self.apply([vector.x, vector.y, vector.z, 0.0]);
+        Vec3::new(x, y, z)
+    }
+
+    /// Evaluate the bilinear form `a^T M b`.
+    ///
+    /// When `a` and `b` are the same, this is the quadratic form of the matrix.
+    pub fn bilinear(&self, a: [f64; 4], b: [f64; 4]) -> f64 {
+        a.iter().zip(self.apply(b)).map(|(a, b)| a * b).sum()
+    }
+
+    /// Find the inverse of the matrix, if it has one.
+    ///
+    /// This is Gauss-Jordan elimination with partial pivoting.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut left = self.rows;
+        let mut right = Self::IDENTITY.rows;
+
+        for column in 0..4 {
+            let pivot = (column..4)
+                .max_by(|&a, &b| left[a][column].abs().total_cmp(&left[b][column].abs()))
+                .unwrap_or(column);
+            if left[pivot][column].abs() < 1e-12 {
+                return None;
+            }
+            left.swap(column, pivot);
+            right.swap(column, pivot);
+
+            let scale = 1.0 / left[column][column];
+            for j in 0..4 {
+                left[column][j] *= scale;
+                right[column][j] *= scale;
+            }
+
+            for row in 0..4 {
+                if row == column {
+                    continue;
+                }
+                let factor = left[row][column];
+                for j in 0..4 {
+                    left[row][j] -= factor * left[column][j];
+                    right[row][j] -= factor * right[column][j];
+                }
+            }
+        }
+
+        Some(Self::new(right))
+    }
+}
+
+impl std::ops::Mul for Mat4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut rows = [[0.0; 4]; 4];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, entry) in row.iter_mut().enumerate() {
+                *entry = (0..4).map(|k| self.rows[i][k] * rhs.rows[k][j]).sum();
+            }
+        }
+        Self::new(rows)
+    }
+}
@@ -1,4 +1,9 @@
+pub mod aabb;
+pub mod bezier;
+pub mod frame;
 pub mod interval;
+pub mod matrix;
+pub mod polynomial;
 pub mod ray;
 
 /// A 3D vector with three floating-point components.
@@ -236,3 +241,35 @@ pub type Point3 = Vec3;
 pub fn reflect(vector: Vec3, normal: Vec3) -> Vec3 {
     vector - normal * 2.0 * vector.dot(normal)
 }
+
+/// Build two unit vectors which, together with the given unit normal, form an orthonormal basis.
+///
+/// The returned vectors `(tangent, bitangent)` satisfy `tangent.cross(bitangent) == normal`. This
+/// is the branchless construction of Duff et al., which is continuous everywhere except where the
+/// normal is close to `-z`.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::math::{self, Vec3};
+///
+/// let normal = Vec3::new(1.0, 2.0, 3.0).unit_vector();
+/// let (tangent, bitangent) = math::orthonormal_basis(normal);
+///
+/// assert!(tangent.dot(normal).abs() < 1e-12);
+/// assert!((tangent.cross(bitangent) - normal).is_near_zero());
+/// ```
+pub fn orthonormal_basis(normal: Vec3) -> (Vec3, Vec3) {
+    let sign = 1.0_f64.copysign(normal.z);
+    let a = -1.0 / (sign + normal.z);
+    let b = normal.x * normal.y * a;
+
+    (
+        Vec3::new(
+            1.0 + sign * normal.x * normal.x * a,
+            sign * b,
+            -sign * normal.x,
+        ),
+        Vec3::new(b, sign + normal.y * normal.y * a, -normal.y),
+    )
+}
@@ -0,0 +1,146 @@
+/// Coefficients smaller than this, relative to the others, are treated as zero.
+const EPSILON: f64 = 1e-12;
+
+/// Find the real roots of `a x^2 + b x + c`.
+///
+/// Like the other solvers in this module, the roots are returned in ascending order. Repeated
+/// roots may be reported once or several times, so callers should not depend on the multiplicity.
+///
+/// The roots are computed in a way that avoids the catastrophic cancellation of the schoolbook
+/// formula when `b^2` is much larger than `4 a c`. If `a` is zero, the polynomial is solved as a
+/// linear one.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::math::polynomial;
+///
+/// assert_eq!(polynomial::solve_quadratic(1.0, -3.0, 2.0), vec![1.0, 2.0]);
+/// assert!(polynomial::solve_quadratic(1.0, 0.0, 1.0).is_empty());
+/// ```
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        return if b.abs() < EPSILON {
+            Vec::new()
+        } else {
+            vec![-c / b]
+        };
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+
+    let q = -0.5 * (b + discriminant.sqrt().copysign(b));
+    let (x0, x1) = if q == 0.0 { (0.0, 0.0) } else { (q / a, c / q) };
+    if x0 < x1 { vec![x0, x1] } else { vec![x1, x0] }
+}
+
+/// Find the real roots of `a x^3 + b x^2 + c x + d`.
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        return solve_quadratic(b, c, d);
+    }
+
+    // Substituting x = y - b / 3 gives the depressed cubic y^3 + p y + q.
+    let (b, c, d) = (b / a, c / a, d / a);
+    let shift = b / 3.0;
+    let p = c - b * b / 3.0;
+    let q = 2.0 * b * b * b / 27.0 - b * c / 3.0 + d;
+
+    let mut roots = if p.abs() < EPSILON {
+        vec![(-q).cbrt()]
+    } else {
+        let discriminant = q * q / 4.0 + p * p * p / 27.0;
+        if discriminant > 0.0 {
+            // One real root, from Cardano's formula.
+            let sqrt_discriminant = discriminant.sqrt();
+            vec![(-q / 2.0 + sqrt_discriminant).cbrt() + (-q / 2.0 - sqrt_discriminant).cbrt()]
+        } else {
+            // Three real roots, which are most stably found with the trigonometric method.
+            let radius = 2.0 * (-p / 3.0).sqrt();
+            let angle = ((3.0 * q) / (p * radius)).clamp(-1.0, 1.0).acos() / 3.0;
+            (0..3)
+                .map(|k| radius * (angle - 2.0 * std::f64::consts::PI * k as f64 / 3.0).cos())
+                .collect()
+        }
+    };
+
+    for root in &mut roots {
+        *root -= shift;
+    }
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Find the real roots of `a x^4 + b x^3 + c x^2 + d x + e`.
+///
+/// This uses Ferrari's method, followed by a few steps of Newton's method on the original
+/// polynomial, since the closed form loses a fair bit of precision along the way.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::math::polynomial;
+///
+/// // (x - 1)(x - 2)(x - 3)(x - 4)
+/// let roots = polynomial::solve_quartic(1.0, -10.0, 35.0, -50.0, 24.0);
+///
+/// assert_eq!(roots.len(), 4);
+/// for (root, expected) in roots.into_iter().zip([1.0, 2.0, 3.0, 4.0]) {
+///     assert!((root - expected).abs() < 1e-9);
+/// }
+/// ```
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        return solve_cubic(b, c, d, e);
+    }
+
+    // Substituting x = y - b / 4 gives the depressed quartic y^4 + p y^2 + q y + r.
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+    let shift = b / 4.0;
+    let p = c - 3.0 * b * b / 8.0;
+    let q = d - b * c / 2.0 + b * b * b / 8.0;
+    let r = e - b * d / 4.0 + b * b * c / 16.0 - 3.0 * b * b * b * b / 256.0;
+
+    let mut roots = Vec::with_capacity(4);
+    if q.abs() < EPSILON {
+        // The quartic is a quadratic in y^2.
+        for z in solve_quadratic(1.0, p, r) {
+            if z >= 0.0 {
+                roots.push(z.sqrt());
+                roots.push(-z.sqrt());
+            }
+        }
+    } else {
+        // Any positive root m of the resolvent cubic splits the quartic into two quadratics.
+        let Some(m) = solve_cubic(1.0, p, p * p / 4.0 - r, -q * q / 8.0)
+            .into_iter()
+            .rfind(|&m| m > 0.0)
+        else {
+            return Vec::new();
+        };
+        let s = (2.0 * m).sqrt();
+        roots.extend(solve_quadratic(1.0, s, p / 2.0 + m - q / (2.0 * s)));
+        roots.extend(solve_quadratic(1.0, -s, p / 2.0 + m + q / (2.0 * s)));
+    }
+
+    let evaluate = |x: f64| {
+        let value = (((x + b) * x + c) * x + d) * x + e;
+        let derivative = ((4.0 * x + 3.0 * b) * x + 2.0 * c) * x + d;
+        (value, derivative)
+    };
+    for root in &mut roots {
+        *root -= shift;
+        for _ in 0..3 {
+            let (value, derivative) = evaluate(*root);
+            if derivative.abs() < EPSILON {
+                break;
+            }
+            *root -= value / derivative;
+        }
+    }
+    roots.sort_by(f64::total_cmp);
+    roots
+}
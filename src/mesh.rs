@@ -0,0 +1,632 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use crate::math::{Point3, Vec3};
+
+/// The subdivision schemes a [`Mesh`] can be refined with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Subdivision {
+    /// The Catmull-Clark scheme, which turns any mesh into quads and suits quad dominant cages.
+    CatmullClark,
+
+    /// Loop's scheme, which works on triangles. Other faces are split into triangles first.
+    Loop,
+}
+
+/// A polygon mesh, like the control cage of a subdivision surface.
+///
+/// Faces list the indices of their vertices counterclockwise as seen from the front. Edges can be
+/// given a crease sharpness, which keeps subdivision from rounding them off. An edge with a
+/// sharpness of `s` stays sharp for the first `s` levels of subdivision before it starts to be
+/// smoothed, so infinitely sharp edges stay sharp forever. Boundary edges are always treated as
+/// infinitely sharp.
+///
+/// Every corner of every face can also be given texture coordinates. These are carried through
+/// subdivision by interpolating them linearly across each face, so they do not move with the
+/// smoothed surface, but textures stay put on it.
+#[derive(Clone, Default)]
+pub struct Mesh {
+    pub positions: Vec<Point3>,
+    pub faces: Vec<Vec<usize>>,
+    creases: HashMap<(usize, usize), f64>,
+    texture_coordinates: Option<Vec<Vec<[f64; 2]>>>,
+}
+
+/// The connectivity of a mesh, which subdivision needs to find the neighbors of each element.
+struct Topology {
+    edges: Vec<(usize, usize)>,
+    edge_faces: Vec<Vec<usize>>,
+    edge_index: HashMap<(usize, usize), usize>,
+    vertex_edges: Vec<Vec<usize>>,
+    vertex_faces: Vec<Vec<usize>>,
+}
+
+/// Get the key of the edge between two vertices, which does not depend on their order.
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+fn lerp(a: Point3, b: Point3, t: f64) -> Point3 {
+    a * (1.0 - t) + b * t
+}
+
+/// Get the texture coordinates halfway between two others.
+fn halfway(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0]
+}
+
+impl Mesh {
+    /// Create a mesh without any creases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a face has fewer than three vertices or refers to a vertex which does not exist.
+    pub fn new(positions: Vec<Point3>, faces: Vec<Vec<usize>>) -> Self {
+        for face in &faces {
+            assert!(face.len() >= 3, "a face needs at least three vertices");
+            assert!(
+                face.iter().all(|&v| v < positions.len()),
+                "a face refers to a missing vertex"
+            );
+        }
+
+        Self {
+            positions,
+            faces,
+            creases: HashMap::new(),
+            texture_coordinates: None,
+        }
+    }
+
+    /// Give every corner of every face texture coordinates, listed in the same order as the
+    /// faces and their vertices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of faces or of corners of any face does not match the mesh.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::math::Point3;
+    /// use raytracing::mesh::{Mesh, Subdivision};
+    ///
+    /// let square = Mesh::new(
+    ///     vec![
+    ///         Point3::new(0.0, 0.0, 0.0),
+    ///         Point3::new(1.0, 0.0, 0.0),
+    ///         Point3::new(1.0, 1.0, 0.0),
+    ///         Point3::new(0.0, 1.0, 0.0),
+    ///     ],
+    ///     vec![vec![0, 1, 2, 3]],
+    /// )
+    /// .with_texture_coordinates(vec![vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]]);
+    ///
+    /// let refined = square.subdivide(Subdivision::CatmullClark, 1);
+    ///
+    /// assert_eq!(refined.texture_coordinates().unwrap()[0][2], [0.5, 0.5]);
+    /// ```
+    pub fn with_texture_coordinates(mut self, coordinates: Vec<Vec<[f64; 2]>>) -> Self {
+        assert!(
+            coordinates.len() == self.faces.len()
+                && coordinates
+                    .iter()
+                    .zip(&self.faces)
+                    .all(|(corners, face)| corners.len() == face.len()),
+            "the texture coordinates do not match the faces"
+        );
+        self.texture_coordinates = Some(coordinates);
+        self
+    }
+
+    /// Get the texture coordinates of every corner of every face, if the mesh has any.
+    pub fn texture_coordinates(&self) -> Option<&[Vec<[f64; 2]>]> {
+        self.texture_coordinates.as_deref()
+    }
+
+    /// Load a mesh from an OBJ file.
+    ///
+    /// See [`Mesh::parse_obj`] for what is supported.
+    pub fn load_obj<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Self::parse_obj(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse a mesh from the contents of an OBJ file.
+    ///
+    /// Vertex positions, texture coordinates and faces are read, and normals are ignored. The
+    /// texture coordinates are only kept if every corner of every face has them. Indices may be
+    /// negative, counting back from the latest vertex. Creases are read from tags in the form
+    /// OpenSubdiv uses, `t crease 2/1/0 a b sharpness`, where the vertex indices start from zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::mesh::Mesh;
+    ///
+    /// let mesh = Mesh::parse_obj(
+    ///     "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvt 1 1\n\
+    ///      f 1/1 2/1 3/2 -1/-1\nt crease 2/1/0 0 1 2.5\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(mesh.faces, vec![vec![0, 1, 2, 3]]);
+    /// assert_eq!(mesh.crease(1, 0), 2.5);
+    /// assert_eq!(mesh.texture_coordinates().unwrap()[0][2], [1.0, 1.0]);
+    /// ```
+    pub fn parse_obj(text: &str) -> std::io::Result<Self> {
+        let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+        let number = |token: &str| {
+            token
+                .parse::<f64>()
+                .map_err(|_| invalid("malformed OBJ number"))
+        };
+
+        // Turn a one based or negative index into a list of the given length into a zero based
+        // one.
+        let resolve = |token: &str, count: usize, missing: &str| {
+            let index: i64 = token.parse().map_err(|_| invalid("malformed OBJ face"))?;
+            let count = count as i64;
+            let index = if index < 0 { count + index } else { index - 1 };
+            if !(0..count).contains(&index) {
+                return Err(invalid(missing));
+            }
+            Ok(index as usize)
+        };
+
+        let mut mesh = Self::default();
+        let mut texture_coordinates = Vec::new();
+        let mut corner_coordinates = Vec::new();
+        for line in text.lines() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["v", x, y, z, ..] => {
+                    mesh.positions
+                        .push(Point3::new(number(x)?, number(y)?, number(z)?));
+                }
+                ["vt", u, v, ..] => {
+                    texture_coordinates.push([number(u)?, number(v)?]);
+                }
+                ["f", corners @ ..] => {
+                    let mut face = Vec::with_capacity(corners.len());
+                    let mut coordinates = Some(Vec::with_capacity(corners.len()));
+                    for corner in corners {
+                        let mut indices = corner.split('/');
+                        face.push(resolve(
+                            indices.next().unwrap_or_default(),
+                            mesh.positions.len(),
+                            "OBJ face refers to a missing vertex",
+                        )?);
+                        match indices.next().filter(|index| !index.is_empty()) {
+                            Some(index) => {
+                                let index = resolve(
+                                    index,
+                                    texture_coordinates.len(),
+                                    "OBJ face refers to missing texture coordinates",
+                                )?;
+                                if let Some(coordinates) = &mut coordinates {
+                                    coordinates.push(texture_coordinates[index]);
+                                }
+                            }
+                            None => coordinates = None,
+                        }
+                    }
+                    if face.len() < 3 {
+                        return Err(invalid("OBJ face has fewer than three vertices"));
+                    }
+                    mesh.faces.push(face);
+                    corner_coordinates.push(coordinates);
+                }
+                ["t", "crease", _, a, b, sharpness, ..] => {
+                    let index = |token: &str| {
+                        token
+                            .parse::<usize>()
+                            .map_err(|_| invalid("malformed OBJ crease"))
+                    };
+                    mesh.set_crease(index(a)?, index(b)?, number(sharpness)?);
+                }
+                _ => {}
+            }
+        }
+
+        if mesh.creases.keys().any(|&(_, b)| b >= mesh.positions.len()) {
+            return Err(invalid("OBJ crease refers to a missing vertex"));
+        }
+        mesh.texture_coordinates = corner_coordinates.into_iter().collect();
+        Ok(mesh)
+    }
+
+    /// Set the crease sharpness of the edge between two vertices.
+    pub fn set_crease(&mut self, a: usize, b: usize, sharpness: f64) {
+        if sharpness > 0.0 {
+            self.creases.insert(edge_key(a, b), sharpness);
+        } else {
+            self.creases.remove(&edge_key(a, b));
+        }
+    }
+
+    /// Get the crease sharpness of the edge between two vertices.
+    pub fn crease(&self, a: usize, b: usize) -> f64 {
+        self.creases.get(&edge_key(a, b)).copied().unwrap_or(0.0)
+    }
+
+    fn topology(&self) -> Topology {
+        let mut topology = Topology {
+            edges: Vec::new(),
+            edge_faces: Vec::new(),
+            edge_index: HashMap::new(),
+            vertex_edges: vec![Vec::new(); self.positions.len()],
+            vertex_faces: vec![Vec::new(); self.positions.len()],
+        };
+
+        for (f, face) in self.faces.iter().enumerate() {
+            for (i, &a) in face.iter().enumerate() {
+                let b = face[(i + 1) % face.len()];
+                let key = edge_key(a, b);
+                let edge = *topology.edge_index.entry(key).or_insert_with(|| {
+                    topology.edges.push(key);
+                    topology.edge_faces.push(Vec::new());
+                    topology.vertex_edges[a].push(topology.edges.len() - 1);
+                    topology.vertex_edges[b].push(topology.edges.len() - 1);
+                    topology.edges.len() - 1
+                });
+                topology.edge_faces[edge].push(f);
+                topology.vertex_faces[a].push(f);
+            }
+        }
+        topology
+    }
+
+    /// Get the sharpness of an edge, counting boundary edges as infinitely sharp.
+    fn edge_sharpness(&self, topology: &Topology, edge: usize) -> f64 {
+        if topology.edge_faces[edge].len() != 2 {
+            f64::INFINITY
+        } else {
+            let (a, b) = topology.edges[edge];
+            self.crease(a, b)
+        }
+    }
+
+    /// Move a vertex according to the creases around it.
+    ///
+    /// Vertices on fewer than two sharp edges follow the smooth rule of the scheme. Vertices on
+    /// exactly two only move along the crease they form, and vertices on more are corners, which
+    /// stay where they are. Creases with a sharpness below one blend the sharp rule with the
+    /// smooth one.
+    fn crease_vertex<F>(&self, topology: &Topology, vertex: usize, smooth: F) -> Point3
+    where
+        F: FnOnce() -> Point3,
+    {
+        let position = self.positions[vertex];
+        let sharp: Vec<(usize, f64)> = topology.vertex_edges[vertex]
+            .iter()
+            .map(|&edge| (edge, self.edge_sharpness(topology, edge)))
+            .filter(|&(_, sharpness)| sharpness > 0.0)
+            .collect();
+        if sharp.len() < 2 {
+            return smooth();
+        }
+
+        let sharp_position = if sharp.len() == 2 {
+            let other = |edge: usize| {
+                let (a, b) = topology.edges[edge];
+                self.positions[if a == vertex { b } else { a }]
+            };
+            (other(sharp[0].0) + position * 6.0 + other(sharp[1].0)) / 8.0
+        } else {
+            position
+        };
+
+        let sharpness = sharp.iter().map(|&(_, s)| s).sum::<f64>() / sharp.len() as f64;
+        if sharpness >= 1.0 {
+            sharp_position
+        } else {
+            lerp(smooth(), sharp_position, sharpness)
+        }
+    }
+
+    /// Give the two halves of every split edge the sharpness of the original, less one level.
+    fn split_creases(&self, topology: &Topology, midpoint: impl Fn(usize) -> usize) -> Self {
+        let mut mesh = Self::default();
+        for (edge, &(a, b)) in topology.edges.iter().enumerate() {
+            let sharpness = self.crease(a, b) - 1.0;
+            mesh.set_crease(a, midpoint(edge), sharpness);
+            mesh.set_crease(midpoint(edge), b, sharpness);
+        }
+        mesh
+    }
+
+    /// Refine the mesh by one level of Catmull-Clark subdivision.
+    ///
+    /// Every face with `n` sides is split into `n` quads, meeting at a new vertex in the middle of
+    /// the face.
+    pub fn catmull_clark(&self) -> Self {
+        let topology = self.topology();
+        let vertex_count = self.positions.len();
+        let edge_count = topology.edges.len();
+
+        let face_points: Vec<Point3> = self
+            .faces
+            .iter()
+            .map(|face| {
+                let sum = face
+                    .iter()
+                    .fold(Vec3::ZERO, |sum, &v| sum + self.positions[v]);
+                sum / face.len() as f64
+            })
+            .collect();
+
+        let edge_points = (0..edge_count).map(|edge| {
+            let (a, b) = topology.edges[edge];
+            let midpoint = (self.positions[a] + self.positions[b]) / 2.0;
+            let sharpness = self.edge_sharpness(&topology, edge);
+            if sharpness >= 1.0 {
+                return midpoint;
+            }
+            let faces = &topology.edge_faces[edge];
+            let smooth = (midpoint * 2.0 + face_points[faces[0]] + face_points[faces[1]]) / 4.0;
+            lerp(smooth, midpoint, sharpness)
+        });
+
+        let vertex_points = (0..vertex_count).map(|vertex| {
+            self.crease_vertex(&topology, vertex, || {
+                let position = self.positions[vertex];
+                let edges = &topology.vertex_edges[vertex];
+                let faces = &topology.vertex_faces[vertex];
+                if edges.is_empty() || faces.is_empty() {
+                    return position;
+                }
+
+                let n = edges.len() as f64;
+                let q = faces
+                    .iter()
+                    .fold(Vec3::ZERO, |sum, &f| sum + face_points[f])
+                    / faces.len() as f64;
+                let r = edges.iter().fold(Vec3::ZERO, |sum, &edge| {
+                    let (a, b) = topology.edges[edge];
+                    sum + (self.positions[a] + self.positions[b]) / 2.0
+                }) / n;
+                (q + r * 2.0 + position * (n - 3.0)) / n
+            })
+        });
+
+        let midpoint = |edge: usize| vertex_count + edge;
+        let mut mesh = self.split_creases(&topology, midpoint);
+        mesh.positions = vertex_points
+            .chain(edge_points)
+            .chain(face_points.iter().copied())
+            .collect();
+
+        let edge = |a: usize, b: usize| midpoint(topology.edge_index[&edge_key(a, b)]);
+        for (f, face) in self.faces.iter().enumerate() {
+            let center = vertex_count + edge_count + f;
+            let n = face.len();
+            for i in 0..n {
+                let (previous, current, next) = (face[(i + n - 1) % n], face[i], face[(i + 1) % n]);
+                mesh.faces.push(vec![
+                    current,
+                    edge(current, next),
+                    center,
+                    edge(previous, current),
+                ]);
+            }
+        }
+
+        mesh.texture_coordinates = self.texture_coordinates.as_ref().map(|coordinates| {
+            coordinates
+                .iter()
+                .flat_map(|corners| {
+                    let n = corners.len();
+                    let center = corners.iter().fold([0.0; 2], |sum, uv| {
+                        [sum[0] + uv[0] / n as f64, sum[1] + uv[1] / n as f64]
+                    });
+                    (0..n).map(move |i| {
+                        let (previous, current, next) =
+                            (corners[(i + n - 1) % n], corners[i], corners[(i + 1) % n]);
+                        vec![
+                            current,
+                            halfway(current, next),
+                            center,
+                            halfway(previous, current),
+                        ]
+                    })
+                })
+                .collect()
+        });
+        mesh
+    }
+
+    /// Refine the mesh by one level of Loop subdivision.
+    ///
+    /// Every triangle is split into four. Faces with more sides are split into a fan of triangles
+    /// first.
+    pub fn loop_subdivision(&self) -> Self {
+        fn fan<T: Copy>(polygons: &[Vec<T>]) -> Vec<Vec<T>> {
+            polygons
+                .iter()
+                .flat_map(|p| (1..p.len().saturating_sub(1)).map(|i| vec![p[0], p[i], p[i + 1]]))
+                .collect()
+        }
+        let mut triangulated = self.clone();
+        triangulated.faces = fan(&self.faces);
+        triangulated.texture_coordinates = self.texture_coordinates.as_deref().map(fan);
+        let this = &triangulated;
+
+        let topology = this.topology();
+        let vertex_count = this.positions.len();
+
+        let edge_points = (0..topology.edges.len()).map(|edge| {
+            let (a, b) = topology.edges[edge];
+            let (pa, pb) = (this.positions[a], this.positions[b]);
+            let midpoint = (pa + pb) / 2.0;
+            let sharpness = this.edge_sharpness(&topology, edge);
+            if sharpness >= 1.0 {
+                return midpoint;
+            }
+
+            // Weigh in the vertices opposite the edge in the two triangles sharing it.
+            let opposite = |f: usize| {
+                let face = &this.faces[f];
+                let v = face.iter().copied().find(|&v| v != a && v != b);
+                this.positions[v.unwrap_or(a)]
+            };
+            let faces = &topology.edge_faces[edge];
+            let smooth = (pa + pb) * 0.375 + (opposite(faces[0]) + opposite(faces[1])) * 0.125;
+            lerp(smooth, midpoint, sharpness)
+        });
+
+        let vertex_points = (0..vertex_count).map(|vertex| {
+            this.crease_vertex(&topology, vertex, || {
+                let position = this.positions[vertex];
+                let edges = &topology.vertex_edges[vertex];
+                if edges.is_empty() {
+                    return position;
+                }
+
+                let n = edges.len() as f64;
+                let beta = (0.625 - (0.375 + 0.25 * (2.0 * PI / n).cos()).powi(2)) / n;
+                let neighbors = edges.iter().fold(Vec3::ZERO, |sum, &edge| {
+                    let (a, b) = topology.edges[edge];
+                    sum + this.positions[if a == vertex { b } else { a }]
+                });
+                position * (1.0 - n * beta) + neighbors * beta
+            })
+        });
+
+        let midpoint = |edge: usize| vertex_count + edge;
+        let mut mesh = this.split_creases(&topology, midpoint);
+        mesh.positions = vertex_points.chain(edge_points).collect();
+
+        let edge = |a: usize, b: usize| midpoint(topology.edge_index[&edge_key(a, b)]);
+        for face in &this.faces {
+            let [a, b, c] = [face[0], face[1], face[2]];
+            let (ab, bc, ca) = (edge(a, b), edge(b, c), edge(c, a));
+            mesh.faces.extend([
+                vec![a, ab, ca],
+                vec![ab, b, bc],
+                vec![ca, bc, c],
+                vec![ab, bc, ca],
+            ]);
+        }
+
+        mesh.texture_coordinates = this.texture_coordinates.as_ref().map(|coordinates| {
+            coordinates
+                .iter()
+                .flat_map(|corners| {
+                    let [a, b, c] = [corners[0], corners[1], corners[2]];
+                    let (ab, bc, ca) = (halfway(a, b), halfway(b, c), halfway(c, a));
+                    [
+                        vec![a, ab, ca],
+                        vec![ab, b, bc],
+                        vec![ca, bc, c],
+                        vec![ab, bc, ca],
+                    ]
+                })
+                .collect()
+        });
+        mesh
+    }
+
+    /// Refine the mesh by the given number of levels of subdivision.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::math::Point3;
+    /// use raytracing::mesh::{Mesh, Subdivision};
+    ///
+    /// let square = Mesh::new(
+    ///     vec![
+    ///         Point3::new(0.0, 0.0, 0.0),
+    ///         Point3::new(1.0, 0.0, 0.0),
+    ///         Point3::new(1.0, 1.0, 0.0),
+    ///         Point3::new(0.0, 1.0, 0.0),
+    ///     ],
+    ///     vec![vec![0, 1, 2, 3]],
+    /// );
+    ///
+    /// let refined = square.subdivide(Subdivision::CatmullClark, 2);
+    ///
+    /// assert_eq!(refined.faces.len(), 16);
+    /// assert!(refined.positions.iter().all(|p| p.z == 0.0));
+    /// ```
+    pub fn subdivide(&self, scheme: Subdivision, levels: u32) -> Self {
+        let mut mesh = self.clone();
+        for _ in 0..levels {
+            mesh = match scheme {
+                Subdivision::CatmullClark => mesh.catmull_clark(),
+                Subdivision::Loop => mesh.loop_subdivision(),
+            };
+        }
+        mesh
+    }
+
+    /// Compute a normal for every corner of every face.
+    ///
+    /// Normals are averaged over the faces around each vertex, weighted by their area, except
+    /// across edges with any crease sharpness left, so that creases and boundaries stay crisp.
+    pub fn corner_normals(&self) -> Vec<Vec<Vec3>> {
+        // The normal of a polygon is half the sum of the cross products of its consecutive
+        // vertices, which is proportional to its area.
+        let face_normals: Vec<Vec3> = self
+            .faces
+            .iter()
+            .map(|face| {
+                let n = face.len();
+                (0..n).fold(Vec3::ZERO, |sum, i| {
+                    sum + self.positions[face[i]].cross(self.positions[face[(i + 1) % n]])
+                })
+            })
+            .collect();
+
+        // Group the corners around each vertex into the fans between sharp edges, joining the
+        // corners of neighboring faces across every smooth edge.
+        let mut corners = Vec::new();
+        let mut corner_index = HashMap::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            for &v in face {
+                corner_index.insert((f, v), corners.len());
+                corners.push(corners.len());
+            }
+        }
+        fn root(parents: &mut [usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                parents[i] = parents[parents[i]];
+                i = parents[i];
+            }
+            i
+        }
+
+        let topology = self.topology();
+        for (edge, faces) in topology.edge_faces.iter().enumerate() {
+            if faces.len() != 2 || self.edge_sharpness(&topology, edge) > 0.0 {
+                continue;
+            }
+            let (a, b) = topology.edges[edge];
+            for v in [a, b] {
+                let first = root(&mut corners, corner_index[&(faces[0], v)]);
+                let second = root(&mut corners, corner_index[&(faces[1], v)]);
+                corners[first] = second;
+            }
+        }
+
+        let mut sums = vec![Vec3::ZERO; corners.len()];
+        for (f, face) in self.faces.iter().enumerate() {
+            for &v in face {
+                let group = root(&mut corners, corner_index[&(f, v)]);
+                sums[group] += face_normals[f];
+            }
+        }
+
+        self.faces
+            .iter()
+            .enumerate()
+            .map(|(f, face)| {
+                face.iter()
+                    .map(|&v| sums[root(&mut corners, corner_index[&(f, v)])].unit_vector())
+                    .collect()
+            })
+            .collect()
+    }
+}
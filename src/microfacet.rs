@@ -0,0 +1,364 @@
+use std::f64::consts::PI;
+
+use crate::color::Color;
+use crate::math::Vec3;
+
+/// The Trowbridge-Reitz distribution of microfacet normals, better known as GGX.
+///
+/// Rough surfaces are modelled as a great many tiny mirrors, the microfacets, whose normals are
+/// spread around the normal of the surface. Directions are given in a local frame in which the
+/// surface normal is the z axis. The widths of the distribution along x and y are `alpha_x` and
+/// `alpha_y`, which are equal for isotropic surfaces.
+#[derive(Clone, Copy)]
+pub struct TrowbridgeReitz {
+    pub alpha_x: f64,
+    pub alpha_y: f64,
+}
+
+impl TrowbridgeReitz {
+    /// Widths below this are treated as perfectly smooth.
+    const SMOOTH: f64 = 1e-3;
+
+    /// Create an isotropic distribution from a perceptual roughness between 0 and 1.
+    ///
+    /// The width is the square of the roughness, which makes roughness values look evenly spaced.
+    pub fn isotropic(roughness: f64) -> Self {
+        let alpha = roughness.clamp(0.0, 1.0).powi(2);
+        Self {
+            alpha_x: alpha,
+            alpha_y: alpha,
+        }
+    }
+
+    /// Create an anisotropic distribution from a perceptual roughness along each axis.
+    ///
+    /// Surfaces orient the x axis along their tangent, so a brushed metal is rougher across the
+    /// grooves than along them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::math::Vec3;
+    /// use raytracing::microfacet::TrowbridgeReitz;
+    ///
+    /// let brushed = TrowbridgeReitz::anisotropic(0.2, 0.6);
+    ///
+    /// // Microfacets tilted across the grooves are more common than ones tilted along them.
+    /// let along = Vec3::new(0.3, 0.0, 1.0).unit_vector();
+    /// let across = Vec3::new(0.0, 0.3, 1.0).unit_vector();
+    /// assert!(brushed.d(across) > brushed.d(along));
+    /// ```
+    pub fn anisotropic(roughness_x: f64, roughness_y: f64) -> Self {
+        Self {
+            alpha_x: roughness_x.clamp(0.0, 1.0).powi(2),
+            alpha_y: roughness_y.clamp(0.0, 1.0).powi(2),
+        }
+    }
+
+    /// Determine whether the distribution is narrow enough to be treated as a perfect mirror.
+    pub fn is_smooth(&self) -> bool {
+        self.alpha_x.max(self.alpha_y) < Self::SMOOTH
+    }
+
+    /// Evaluate the density of microfacets with the given normal.
+    pub fn d(&self, m: Vec3) -> f64 {
+        if m.z <= 0.0 {
+            return 0.0;
+        }
+        let (ax, ay) = (
+            self.alpha_x.max(Self::SMOOTH),
+            self.alpha_y.max(Self::SMOOTH),
+        );
+        let e = (m.x / ax).powi(2) + (m.y / ay).powi(2) + m.z * m.z;
+        1.0 / (PI * ax * ay * e * e)
+    }
+
+    /// Evaluate Smith's auxiliary function, which measures how much of the microsurface the
+    /// direction sees shadowed.
+    pub fn lambda(&self, w: Vec3) -> f64 {
+        if w.z == 0.0 {
+            return f64::INFINITY;
+        }
+        let tan2 = ((self.alpha_x * w.x).powi(2) + (self.alpha_y * w.y).powi(2)) / (w.z * w.z);
+        ((1.0 + tan2).sqrt() - 1.0) / 2.0
+    }
+
+    /// Get the fraction of the microsurface visible from the direction, by Smith's model.
+    pub fn g1(&self, w: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// Get the fraction of the microfacets visible from both directions at once.
+    pub fn g(&self, wo: Vec3, wi: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Sample a microfacet normal in proportion to how much of it is visible from `wo`.
+    ///
+    /// This is the method of Heitz, "Sampling the GGX Distribution of Visible Normals", which
+    /// stretches the distribution into a hemisphere, samples the projection of that, and
+    /// stretches the result back. It never produces normals facing away from `wo`, which makes it
+    /// much less noisy than sampling the distribution itself.
+    pub fn sample_visible_normal(&self, wo: Vec3, u1: f64, u2: f64) -> Vec3 {
+        if self.is_smooth() {
+            return Vec3::new(0.0, 0.0, 1.0);
+        }
+
+        let vh = Vec3::new(self.alpha_x * wo.x, self.alpha_y * wo.y, wo.z).unit_vector();
+        let length_squared = vh.x * vh.x + vh.y * vh.y;
+        let t1 = if length_squared > 0.0 {
+            Vec3::new(-vh.y, vh.x, 0.0) / length_squared.sqrt()
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let t2 = vh.cross(t1);
+
+        let radius = u1.sqrt();
+        let (sin_phi, cos_phi) = (2.0 * PI * u2).sin_cos();
+        let p1 = radius * cos_phi;
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).max(0.0).sqrt() + s * radius * sin_phi;
+        let nh = t1 * p1 + t2 * p2 + vh * (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+
+        Vec3::new(self.alpha_x * nh.x, self.alpha_y * nh.y, nh.z.max(1e-6)).unit_vector()
+    }
+
+    /// Get the density of [`TrowbridgeReitz::sample_visible_normal`] producing the normal `m`.
+    pub fn visible_normal_pdf(&self, wo: Vec3, m: Vec3) -> f64 {
+        if wo.z <= 0.0 {
+            return 0.0;
+        }
+        self.g1(wo) * wo.dot(m).max(0.0) * self.d(m) / wo.z
+    }
+}
+
+/// Get the fraction of light a dielectric interface reflects.
+///
+/// The cosine is that of the angle between the incoming light and the normal, on the side the
+/// light comes from, and `eta` is the refractive index of the other side relative to this one.
+/// Beyond the critical angle, everything is reflected.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::microfacet;
+///
+/// // Glass reflects about 4% of light head on.
+/// assert!((microfacet::fresnel_dielectric(1.0, 1.5) - 0.04).abs() < 1e-9);
+/// // Light inside glass is totally reflected at grazing angles.
+/// assert_eq!(microfacet::fresnel_dielectric(0.1, 1.0 / 1.5), 1.0);
+/// ```
+pub fn fresnel_dielectric(cos_i: f64, eta: f64) -> f64 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+
+    let parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    (parallel * parallel + perpendicular * perpendicular) / 2.0
+}
+
+/// Get the fraction of light a conductor reflects, for each color channel.
+///
+/// Metals have a complex refractive index `eta + i k`, where the imaginary part describes how
+/// quickly light is absorbed inside them. Both parts are given relative to the outside medium.
+pub fn fresnel_conductor(cos_i: f64, eta: Color, k: Color) -> Color {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let channel = |eta: f64, k: f64| {
+        let cos2 = cos_i * cos_i;
+        let sin2 = 1.0 - cos2;
+        let (eta2, k2) = (eta * eta, k * k);
+
+        let t0 = eta2 - k2 - sin2;
+        let a2b2 = (t0 * t0 + 4.0 * eta2 * k2).sqrt();
+        let t1 = a2b2 + cos2;
+        let a = (0.5 * (a2b2 + t0)).max(0.0).sqrt();
+        let t2 = 2.0 * cos_i * a;
+        let perpendicular = (t1 - t2) / (t1 + t2);
+
+        let t3 = cos2 * a2b2 + sin2 * sin2;
+        let t4 = t2 * sin2;
+        let parallel = perpendicular * (t3 - t4) / (t3 + t4);
+        (parallel + perpendicular) / 2.0
+    };
+
+    Color::new(
+        channel(eta.x, k.x),
+        channel(eta.y, k.y),
+        channel(eta.z, k.z),
+    )
+}
+
+/// A complex number, for the handful of optics formulas which need them.
+#[derive(Clone, Copy)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    fn real(re: f64) -> Self {
+        Self::new(re, 0.0)
+    }
+
+    fn norm_squared(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    fn sqrt(self) -> Self {
+        let radius = self.norm_squared().sqrt();
+        let re = ((radius + self.re) / 2.0).max(0.0).sqrt();
+        let im = ((radius - self.re) / 2.0).max(0.0).sqrt();
+        Self::new(re, if self.im < 0.0 { -im } else { im })
+    }
+
+    /// Get `e^(i self)`.
+    fn exp_i(self) -> Self {
+        let scale = (-self.im).exp();
+        Self::new(scale * self.re.cos(), scale * self.re.sin())
+    }
+}
+
+impl std::ops::Add for Complex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl std::ops::Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl std::ops::Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl std::ops::Div for Complex {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let denominator = other.norm_squared();
+        Self::new(
+            (self.re * other.re + self.im * other.im) / denominator,
+            (self.im * other.re - self.re * other.im) / denominator,
+        )
+    }
+}
+
+/// A thin transparent film on top of a surface, like oil on water or the oxide on anodized metal.
+///
+/// Light reflecting off the top of the film interferes with light reflecting off the surface
+/// below, which cancels out some wavelengths and reinforces others depending on the thickness of
+/// the film and the angle it is seen at. This is what gives soap bubbles and oil slicks their
+/// shifting rainbow colors. The thickness is in nanometers, comparable to the wavelength of light
+/// for the effect to show.
+#[derive(Clone, Copy)]
+pub struct ThinFilm {
+    pub thickness: f64,
+    pub refractive_index: f64,
+}
+
+impl ThinFilm {
+    /// The wavelengths in nanometers averaged over for each color channel.
+    const WAVELENGTHS: [[f64; 3]; 3] = [
+        [600.0, 630.0, 660.0],
+        [510.0, 540.0, 570.0],
+        [430.0, 460.0, 490.0],
+    ];
+
+    /// Get the fraction of light of a single wavelength the film and the surface below reflect
+    /// together.
+    ///
+    /// Light arrives through a medium with the refractive index `outside`, and the surface below
+    /// has the complex refractive index `eta + i k`. Unlike elsewhere, every index here is
+    /// absolute rather than relative, since the interference depends on the wavelength inside
+    /// the film. The wavelength is in nanometers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::microfacet::ThinFilm;
+    ///
+    /// // A quarter wave coating cancels the reflection off glass almost entirely.
+    /// let coating = ThinFilm {
+    ///     thickness: 550.0 / (4.0 * 1.22),
+    ///     refractive_index: 1.22,
+    /// };
+    ///
+    /// assert!(coating.reflectance(1.0, 550.0, 1.0, 1.5, 0.0) < 1e-4);
+    /// ```
+    pub fn reflectance(&self, cos_i: f64, wavelength: f64, outside: f64, eta: f64, k: f64) -> f64 {
+        let cos_i = cos_i.clamp(0.0, 1.0);
+        let sin2 = Complex::real((1.0 - cos_i * cos_i) * outside * outside);
+        let n1 = Complex::real(outside);
+        let n2 = Complex::real(self.refractive_index);
+        let n3 = Complex::new(eta, k);
+
+        // Snell's law gives the cosine of the angle in each layer, which is complex where the
+        // light is totally reflected or absorbed.
+        let cosine = |n: Complex| (Complex::real(1.0) - sin2 / (n * n)).sqrt();
+        let (cos1, cos2, cos3) = (Complex::real(cos_i), cosine(n2), cosine(n3));
+
+        // The light going back and forth inside the film picks up this much phase each round.
+        let phase =
+            Complex::real(4.0 * std::f64::consts::PI * self.thickness / wavelength) * n2 * cos2;
+        let round_trip = phase.exp_i();
+
+        let airy = |r12: Complex, r23: Complex| {
+            ((r12 + r23 * round_trip) / (Complex::real(1.0) + r12 * r23 * round_trip))
+                .norm_squared()
+        };
+        let perpendicular = |a: Complex, ca: Complex, b: Complex, cb: Complex| {
+            (a * ca - b * cb) / (a * ca + b * cb)
+        };
+        let parallel = |a: Complex, ca: Complex, b: Complex, cb: Complex| {
+            (b * ca - a * cb) / (b * ca + a * cb)
+        };
+
+        let s = airy(
+            perpendicular(n1, cos1, n2, cos2),
+            perpendicular(n2, cos2, n3, cos3),
+        );
+        let p = airy(parallel(n1, cos1, n2, cos2), parallel(n2, cos2, n3, cos3));
+        ((s + p) / 2.0).clamp(0.0, 1.0)
+    }
+
+    /// Get the fraction of light the film and the surface below reflect, for each color channel.
+    ///
+    /// Each channel averages a few wavelengths around its primary, using the refractive index of
+    /// the surface for that channel. See [`ThinFilm::reflectance`] for the meaning of the indices.
+    pub fn fresnel(&self, cos_i: f64, outside: f64, eta: Color, k: Color) -> Color {
+        let channel = |index: usize, eta: f64, k: f64| {
+            Self::WAVELENGTHS[index]
+                .iter()
+                .map(|&wavelength| self.reflectance(cos_i, wavelength, outside, eta, k))
+                .sum::<f64>()
+                / 3.0
+        };
+        Color::new(
+            channel(0, eta.x, k.x),
+            channel(1, eta.y, k.y),
+            channel(2, eta.z, k.z),
+        )
+    }
+}
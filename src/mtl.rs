@@ -0,0 +1,121 @@
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use crate::color::Color;
+
+/// A material read from a Wavefront MTL file.
+///
+/// Besides the classic Phong style parameters, this includes the physically based extension most
+/// exporters understand, with roughness (`Pr`), metalness (`Pm`), sheen (`Ps`), clearcoat (`Pc`)
+/// and clearcoat roughness (`Pcr`). Texture maps are kept as the paths written in the file. Only
+/// the maps listed here are read, and the others, like specular and bump maps, are skipped.
+#[derive(Clone, Default)]
+pub struct MtlMaterial {
+    pub name: String,
+    pub diffuse: Option<Color>,
+    pub specular: Option<Color>,
+    pub emission: Option<Color>,
+    pub shininess: Option<f64>,
+    pub dissolve: Option<f64>,
+    pub refractive_index: Option<f64>,
+    pub transmission_filter: Option<Color>,
+    pub roughness: Option<f64>,
+    pub metallic: Option<f64>,
+    pub sheen: Option<f64>,
+    pub clearcoat: Option<f64>,
+    pub clearcoat_roughness: Option<f64>,
+    pub diffuse_map: Option<String>,
+    pub roughness_map: Option<String>,
+    pub metallic_map: Option<String>,
+    pub sheen_map: Option<String>,
+}
+
+/// Load every material from an MTL file.
+///
+/// See [`parse`] for what is supported.
+pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<MtlMaterial>> {
+    parse(&std::fs::read_to_string(path)?)
+}
+
+/// Parse every material from the contents of an MTL file.
+///
+/// Unknown statements are skipped, as are the options of texture maps, so only the path at the
+/// end of a map statement is kept.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::mtl;
+///
+/// let materials = mtl::parse("newmtl brass\nKd 0.8 0.6 0.2\nPm 1\nmap_Pr -bm 1 rough.ppm\n").unwrap();
+///
+/// assert_eq!(materials[0].name, "brass");
+/// assert_eq!(materials[0].metallic, Some(1.0));
+/// assert_eq!(materials[0].roughness_map.as_deref(), Some("rough.ppm"));
+/// ```
+pub fn parse(text: &str) -> std::io::Result<Vec<MtlMaterial>> {
+    let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+    let number = |token: &str| {
+        token
+            .parse::<f64>()
+            .map_err(|_| invalid("malformed MTL number"))
+    };
+    let color = |values: &[&str]| -> std::io::Result<Color> {
+        match values {
+            [r, g, b, ..] => Ok(Color::new(number(r)?, number(g)?, number(b)?)),
+            [gray] => {
+                let gray = number(gray)?;
+                Ok(Color::new(gray, gray, gray))
+            }
+            _ => Err(invalid("malformed MTL color")),
+        }
+    };
+
+    let mut materials: Vec<MtlMaterial> = Vec::new();
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&keyword, values)) = words.split_first() else {
+            continue;
+        };
+
+        if keyword == "newmtl" {
+            materials.push(MtlMaterial {
+                name: values.join(" "),
+                ..MtlMaterial::default()
+            });
+            continue;
+        }
+        let material = materials
+            .last_mut()
+            .ok_or_else(|| invalid("MTL statement outside of a material"))?;
+        let scalar = || {
+            values
+                .first()
+                .map_or(Err(invalid("missing MTL value")), |v| number(v))
+        };
+        let path = || values.last().map(|path| path.to_string());
+
+        match keyword {
+            "Kd" => material.diffuse = Some(color(values)?),
+            "Ks" => material.specular = Some(color(values)?),
+            "Ke" => material.emission = Some(color(values)?),
+            "Tf" => material.transmission_filter = Some(color(values)?),
+            "Ns" => material.shininess = Some(scalar()?),
+            "d" => material.dissolve = Some(scalar()?),
+            "Tr" => material.dissolve = Some(1.0 - scalar()?),
+            "Ni" => material.refractive_index = Some(scalar()?),
+            "Pr" => material.roughness = Some(scalar()?),
+            "Pm" => material.metallic = Some(scalar()?),
+            "Ps" => material.sheen = Some(scalar()?),
+            "Pc" => material.clearcoat = Some(scalar()?),
+            "Pcr" => material.clearcoat_roughness = Some(scalar()?),
+            "map_Kd" => material.diffuse_map = path(),
+            "map_Pr" => material.roughness_map = path(),
+            "map_Pm" => material.metallic_map = path(),
+            "map_Ps" => material.sheen_map = path(),
+            _ => {}
+        }
+    }
+    Ok(materials)
+}
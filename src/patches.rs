@@ -0,0 +1,75 @@
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use crate::math::Point3;
+use crate::math::bezier::Patch;
+
+/// Load a set of bicubic Bézier patches from a file.
+///
+/// See [`parse_bpt`] for the format.
+pub fn load_bpt<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<Patch>> {
+    parse_bpt(&std::fs::read_to_string(path)?)
+}
+
+/// Parse a set of Bézier patches in the BPT format.
+///
+/// This is the format the Utah teapot is usually distributed in. The text starts with the number
+/// of patches, and each patch is its degree in `u` and `v` followed by its control points, one
+/// row at a time. Only bicubic patches, of degree 3 both ways, are supported.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::patches;
+///
+/// let mut text = String::from("1\n3 3\n");
+/// for v in 0..4 {
+///     for u in 0..4 {
+///         text += &format!("{u} {v} 0\n");
+///     }
+/// }
+///
+/// let patches = patches::parse_bpt(&text).unwrap();
+///
+/// assert_eq!(patches.len(), 1);
+/// assert_eq!(patches[0][2][1].y, 2.0);
+///
+/// // The count has to be a whole number, and the patches have to be there.
+/// assert!(patches::parse_bpt("1e30 3 3").is_err());
+/// assert!(patches::parse_bpt("1000000000000 3 3").is_err());
+/// ```
+pub fn parse_bpt(text: &str) -> std::io::Result<Vec<Patch>> {
+    let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+
+    let mut tokens = text.split_whitespace();
+    let count: usize = tokens
+        .next()
+        .ok_or_else(|| invalid("BPT file ends too early"))?
+        .parse()
+        .map_err(|_| invalid("malformed BPT patch count"))?;
+
+    let mut next_number = || -> std::io::Result<f64> {
+        tokens
+            .next()
+            .ok_or_else(|| invalid("BPT file ends too early"))?
+            .parse()
+            .map_err(|_| invalid("malformed BPT number"))
+    };
+
+    // The count is not trusted for reserving space, as it may be far more than the text holds.
+    let mut patches = Vec::new();
+    for _ in 0..count {
+        if next_number()? != 3.0 || next_number()? != 3.0 {
+            return Err(invalid("only bicubic BPT patches are supported"));
+        }
+
+        let mut patch = [[Point3::ZERO; 4]; 4];
+        for row in &mut patch {
+            for point in row {
+                *point = Point3::new(next_number()?, next_number()?, next_number()?);
+            }
+        }
+        patches.push(patch);
+    }
+    Ok(patches)
+}
@@ -0,0 +1,73 @@
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{self, Point3, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// Represents an infinite ray traceable plane.
+///
+/// The plane passes through `point` and faces in the direction of `normal`. Its surface
+/// coordinates repeat every unit of distance, so image textures tile across it.
+#[derive(Clone)]
+pub struct Plane {
+    pub point: Point3,
+    pub normal: Vec3,
+    pub material: Rc<dyn Material>,
+    tangent: Vec3,
+    bitangent: Vec3,
+}
+
+impl Plane {
+    /// Create a new plane through a point with the given normal.
+    pub fn new(point: Point3, normal: Vec3, material: Rc<dyn Material>) -> Self {
+        let normal = normal.unit_vector();
+        let (tangent, bitangent) = math::orthonormal_basis(normal);
+
+        Self {
+            point,
+            normal,
+            material,
+            tangent,
+            bitangent,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let denominator = self.normal.dot(ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let root = self.normal.dot(self.point - ray.origin) / denominator;
+        if !interval.surrounds(root) {
+            return None;
+        }
+
+        let hit_point = ray.at(root);
+        let planar = hit_point - self.point;
+        let mut record = HitRecord {
+            point: hit_point,
+            normal: self.normal,
+            u: planar.dot(self.tangent).rem_euclid(1.0),
+            v: planar.dot(self.bitangent).rem_euclid(1.0),
+            dpdu: self.tangent,
+            dpdv: self.bitangent,
+            material: self.material.clone(),
+            time: root,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, self.normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::UNIVERSE
+    }
+}
@@ -0,0 +1,363 @@
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use crate::color::{self, Color};
+use crate::math::{Point3, Vec3};
+
+/// A set of points in space, like the output of a laser scanner.
+///
+/// Points may come with a color and a normal each. Colors are stored in linear space, having been
+/// decoded from the gamma encoding files use.
+pub struct PointCloud {
+    pub positions: Vec<Point3>,
+    pub colors: Option<Vec<Color>>,
+    pub normals: Option<Vec<Vec3>>,
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+/// Decode a gamma encoded color whose components go up to the given full scale, like 255 for 8
+/// bit integers or 1 for fractions.
+fn decode_color(red: f64, green: f64, blue: f64, full_scale: f64) -> Color {
+    let scale = 1.0 / full_scale;
+    Color::new(
+        color::gamma_to_linear(red * scale),
+        color::gamma_to_linear(green * scale),
+        color::gamma_to_linear(blue * scale),
+    )
+}
+
+impl PointCloud {
+    /// Load a point cloud from a file.
+    ///
+    /// Files ending in `.ply` are read as PLY files, and anything else as XYZ text. See
+    /// [`PointCloud::parse_ply`] and [`PointCloud::parse_xyz`] for what is supported.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let is_ply = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("ply"));
+
+        if is_ply {
+            Self::parse_ply(&std::fs::read(path)?)
+        } else {
+            Self::parse_xyz(&std::fs::read_to_string(path)?)
+        }
+    }
+
+    /// Parse a point cloud from XYZ text.
+    ///
+    /// Every line holds one point as three coordinates, optionally followed by a red, green and
+    /// blue component, and then optionally by a normal. Colors are read as 8 bit integers if any
+    /// component in the file is above 1, and as fractions otherwise. Blank lines and lines
+    /// starting with `#` are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::point_cloud::PointCloud;
+    ///
+    /// let cloud = PointCloud::parse_xyz("# x y z r g b\n0 0 0 255 0 0\n1 2 3 0 0 255\n").unwrap();
+    ///
+    /// assert_eq!(cloud.positions[1].z, 3.0);
+    /// assert_eq!(cloud.colors.unwrap()[0].x, 1.0);
+    /// ```
+    pub fn parse_xyz(text: &str) -> std::io::Result<Self> {
+        let mut rows = Vec::new();
+        let mut columns = None;
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let row = line
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|token| !token.is_empty())
+                .map(str::parse)
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| invalid("expected a number"))?;
+            if !matches!(row.len(), 3 | 6 | 9) {
+                return Err(invalid("expected 3, 6 or 9 numbers per point"));
+            }
+            if *columns.get_or_insert(row.len()) != row.len() {
+                return Err(invalid("every point must have the same number of values"));
+            }
+            rows.push(row);
+        }
+
+        let columns = columns.unwrap_or(3);
+        let integers = rows
+            .iter()
+            .any(|row| row[3..columns.min(6)].iter().any(|&c| c > 1.0));
+        let full_scale = if integers { 255.0 } else { 1.0 };
+
+        Ok(Self {
+            positions: rows
+                .iter()
+                .map(|row| Point3::new(row[0], row[1], row[2]))
+                .collect(),
+            colors: (columns >= 6).then(|| {
+                rows.iter()
+                    .map(|row| decode_color(row[3], row[4], row[5], full_scale))
+                    .collect()
+            }),
+            normals: (columns == 9).then(|| {
+                rows.iter()
+                    .map(|row| Vec3::new(row[6], row[7], row[8]))
+                    .collect()
+            }),
+        })
+    }
+
+    /// Parse a point cloud from the contents of a PLY file.
+    ///
+    /// Both the ASCII and the binary little endian encodings are accepted. Only the vertex
+    /// element is read, so any faces are ignored, and it has to come first in the file. The
+    /// `x`, `y` and `z` properties are required, while `red`, `green` and `blue`, and `nx`, `ny`
+    /// and `nz`, are picked up when present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::point_cloud::PointCloud;
+    ///
+    /// let header = "ply\nformat binary_little_endian 1.0\nelement vertex 1\n\
+    ///     property float x\nproperty float y\nproperty float z\nend_header\n";
+    /// let mut bytes = header.as_bytes().to_vec();
+    /// for value in [1.0f32, 2.0, 3.0] {
+    ///     bytes.extend(value.to_le_bytes());
+    /// }
+    ///
+    /// let cloud = PointCloud::parse_ply(&bytes).unwrap();
+    ///
+    /// assert_eq!(cloud.positions[0].y, 2.0);
+    /// assert!(cloud.colors.is_none());
+    ///
+    /// // Integer colors are scaled by the largest value of their type.
+    /// let ascii = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nproperty float y\n\
+    ///     property float z\nproperty ushort red\nproperty ushort green\nproperty ushort blue\n\
+    ///     end_header\n0 0 0 65535 0 0\n";
+    /// let cloud = PointCloud::parse_ply(ascii.as_bytes()).unwrap();
+    /// assert_eq!(cloud.colors.unwrap()[0].x, 1.0);
+    ///
+    /// // Windows line endings are fine too.
+    /// let crlf = header.replace('\n', "\r\n");
+    /// assert!(PointCloud::parse_ply(&[crlf.as_bytes(), &bytes[header.len()..]].concat()).is_ok());
+    ///
+    /// // Headers promising more vertices than the file holds are rejected.
+    /// let header = header.replace("vertex 1", "vertex 99999999999999999");
+    /// assert!(PointCloud::parse_ply(header.as_bytes()).is_err());
+    /// ```
+    pub fn parse_ply(bytes: &[u8]) -> std::io::Result<Self> {
+        const END_OF_HEADER: &[u8] = b"end_header";
+
+        // The header ends with a line break of either kind, and the body starts right after it.
+        let header_length = (0..bytes.len())
+            .find_map(|start| {
+                let rest = bytes[start..].strip_prefix(END_OF_HEADER)?;
+                let line_break = [&b"\n"[..], b"\r\n"]
+                    .into_iter()
+                    .find(|line_break| rest.starts_with(line_break))?;
+                Some(start + END_OF_HEADER.len() + line_break.len())
+            })
+            .ok_or_else(|| invalid("missing end of PLY header"))?;
+        let header = std::str::from_utf8(&bytes[..header_length])
+            .map_err(|_| invalid("PLY header is not text"))?;
+        let body = &bytes[header_length..];
+
+        let mut lines = header.lines().map(str::trim);
+        if lines.next() != Some("ply") {
+            return Err(invalid("missing PLY magic number"));
+        }
+
+        let mut binary = None;
+        let mut vertex_count = None;
+        let mut properties: Vec<(&str, PropertyType)> = Vec::new();
+        for line in lines {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["format", "ascii", _] => binary = Some(false),
+                ["format", "binary_little_endian", _] => binary = Some(true),
+                ["format", ..] => return Err(invalid("unsupported PLY format")),
+                ["element", "vertex", count] if vertex_count.is_none() => {
+                    let count: usize = count
+                        .parse()
+                        .map_err(|_| invalid("malformed PLY vertex count"))?;
+                    vertex_count = Some(count);
+                }
+                ["element", ..] if vertex_count.is_none() => {
+                    return Err(invalid("PLY vertices must come first"));
+                }
+                // Properties of later elements do not matter, since only vertices are read.
+                ["element", ..] => break,
+                ["property", "list", ..] => return Err(invalid("unsupported PLY vertex list")),
+                ["property", kind, name] => properties.push((
+                    name,
+                    PropertyType::parse(kind)
+                        .ok_or_else(|| invalid("unknown PLY property type"))?,
+                )),
+                _ => {}
+            }
+        }
+        let binary = binary.ok_or_else(|| invalid("missing PLY format"))?;
+        let vertex_count = vertex_count.ok_or_else(|| invalid("missing PLY vertices"))?;
+
+        let find = |name: &str| properties.iter().position(|&(n, _)| n == name);
+        let position = [find("x"), find("y"), find("z")];
+        let color = [find("red"), find("green"), find("blue")];
+        let normal = [find("nx"), find("ny"), find("nz")];
+        let [Some(x), Some(y), Some(z)] = position else {
+            return Err(invalid("PLY vertices have no position"));
+        };
+
+        // The count in the header cannot be trusted, so check the body could hold that many
+        // vertices before reserving space for them. ASCII values take at least a digit and a
+        // separator each.
+        let values_per_vertex = properties.len();
+        let vertex_size: usize = properties.iter().map(|&(_, kind)| kind.size()).sum();
+        let value_count = vertex_count
+            .checked_mul(values_per_vertex)
+            .ok_or_else(|| invalid("too many PLY vertices"))?;
+        let body_size = if binary {
+            vertex_count.checked_mul(vertex_size)
+        } else {
+            value_count
+                .checked_mul(2)
+                .map(|size| size.saturating_sub(1))
+        };
+        if body_size.is_none_or(|size| size > body.len()) {
+            return Err(invalid("PLY file ends too early"));
+        }
+
+        // Read every property of every vertex as a float, whatever its type in the file.
+        let mut values = Vec::with_capacity(value_count);
+        if binary {
+            let mut cursor = 0;
+            for _ in 0..vertex_count {
+                for &(_, kind) in &properties {
+                    let size = kind.size();
+                    let field = body
+                        .get(cursor..cursor + size)
+                        .ok_or_else(|| invalid("PLY file ends too early"))?;
+                    values.push(kind.decode(field));
+                    cursor += size;
+                }
+            }
+        } else {
+            let text = std::str::from_utf8(body).map_err(|_| invalid("PLY data is not text"))?;
+            let mut tokens = text.split_whitespace();
+            for _ in 0..value_count {
+                let value = tokens
+                    .next()
+                    .ok_or_else(|| invalid("PLY file ends too early"))?
+                    .parse()
+                    .map_err(|_| invalid("malformed PLY value"))?;
+                values.push(value);
+            }
+        }
+
+        let vertices = || values.chunks_exact(values_per_vertex);
+        let colors = match color {
+            [Some(r), Some(g), Some(b)] => {
+                let full_scale = properties[r].1.full_scale();
+                Some(
+                    vertices()
+                        .map(|v| decode_color(v[r], v[g], v[b], full_scale))
+                        .collect(),
+                )
+            }
+            _ => None,
+        };
+        let normals = match normal {
+            [Some(nx), Some(ny), Some(nz)] => {
+                Some(vertices().map(|v| Vec3::new(v[nx], v[ny], v[nz])).collect())
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            positions: vertices().map(|v| Point3::new(v[x], v[y], v[z])).collect(),
+            colors,
+            normals,
+        })
+    }
+
+    /// Get the number of points in the cloud.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Determine whether the cloud has no points at all.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+/// The scalar types a PLY property can have.
+#[derive(Clone, Copy)]
+enum PropertyType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl PropertyType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => Self::Int8,
+            "uchar" | "uint8" => Self::UInt8,
+            "short" | "int16" => Self::Int16,
+            "ushort" | "uint16" => Self::UInt16,
+            "int" | "int32" => Self::Int32,
+            "uint" | "uint32" => Self::UInt32,
+            "float" | "float32" => Self::Float32,
+            "double" | "float64" => Self::Float64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::Int8 | Self::UInt8 => 1,
+            Self::Int16 | Self::UInt16 => 2,
+            Self::Int32 | Self::UInt32 | Self::Float32 => 4,
+            Self::Float64 => 8,
+        }
+    }
+
+    /// Get the value standing for full intensity in a color of this type, which is the largest
+    /// value of integer types and 1 for floats.
+    fn full_scale(self) -> f64 {
+        match self {
+            Self::Int8 => i8::MAX as f64,
+            Self::UInt8 => u8::MAX as f64,
+            Self::Int16 => i16::MAX as f64,
+            Self::UInt16 => u16::MAX as f64,
+            Self::Int32 => i32::MAX as f64,
+            Self::UInt32 => u32::MAX as f64,
+            Self::Float32 | Self::Float64 => 1.0,
+        }
+    }
+
+    /// Decode a little endian value of this type.
+    fn decode(self, bytes: &[u8]) -> f64 {
+        match self {
+            Self::Int8 => bytes[0] as i8 as f64,
+            Self::UInt8 => bytes[0] as f64,
+            Self::Int16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            Self::UInt16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            Self::Int32 => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            Self::UInt32 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            Self::Float32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            Self::Float64 => f64::from_le_bytes(bytes.try_into().unwrap_or_default()),
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use std::rc::Rc;
+
+use raytracing::bvh::Bvh;
+use raytracing::color::Color;
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{self, Point3, Vec3};
+use raytracing::point_cloud::PointCloud;
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::{Material, Sample, ShadingContext};
+use crate::sphere::Sphere;
+
+/// The shape each point of a [`Points`] is drawn as.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PointShape {
+    Sphere,
+
+    /// A flat disk, or splat, lying across the normal of the point. Points without normals get
+    /// disks turned to face the incoming ray.
+    Disk,
+}
+
+/// Represents a ray traceable point cloud, with every point drawn as a small shape.
+///
+/// Clouds can have millions of points, far too many for a [`HittableList`], so the points are kept
+/// in a bounding volume hierarchy of their own. All of the points share the given material, and if
+/// the cloud has colors, each point is tinted by its own, so a white material shows them as they
+/// are. The points, their radius and their material are fixed once the hierarchy has been built
+/// over them.
+///
+/// Surface coordinates are those of each point's own shape, so textures repeat on every point.
+/// Spheres have the coordinates of a [`Sphere`], while disks have coordinates running straight
+/// across them, unlike a [`Disk`], since they are turned arbitrarily around their normals.
+///
+/// [`HittableList`]: crate::hittable_list::HittableList
+/// [`Sphere`]: crate::sphere::Sphere
+/// [`Disk`]: crate::disk::Disk
+pub struct Points {
+    cloud: Rc<Cloud>,
+    pub shape: PointShape,
+    material: Rc<dyn Material>,
+}
+
+/// The points of a [`Points`] with the hierarchy built over them, shared with the material tinting
+/// them by their colors.
+struct Cloud {
+    points: PointCloud,
+    radius: f64,
+    bvh: Bvh,
+}
+
+impl Cloud {
+    /// Find the point whose shape a spot on the surface of the cloud belongs to.
+    ///
+    /// Where the shapes of several points overlap, the one centered nearest to the spot is picked.
+    fn point_at(&self, spot: Point3) -> Option<usize> {
+        let mut nearest = None;
+        let mut nearest_distance = f64::INFINITY;
+        self.bvh
+            .overlapping(Aabb::from_points(spot, spot), |index| {
+                let distance = (self.points.positions[index] - spot).length_squared();
+                if distance < nearest_distance {
+                    nearest = Some(index);
+                    nearest_distance = distance;
+                }
+            });
+        nearest
+    }
+}
+
+/// The material of a [`Points`] with colors, which filters everything the material of the cloud
+/// scatters by the color of the point hit.
+struct Tinted {
+    base: Rc<dyn Material>,
+    cloud: Rc<Cloud>,
+}
+
+impl Tinted {
+    /// Filter a value of the base by the color of the point hit.
+    fn filter(&self, hit: &ShadingContext, value: Color) -> Color {
+        let color = self.cloud.point_at(hit.point).and_then(|index| {
+            let colors = self.cloud.points.colors.as_ref()?;
+            Some(hit.color(colors[index]))
+        });
+        match color {
+            Some(color) => Color::new(value.x * color.x, value.y * color.y, value.z * color.z),
+            None => value,
+        }
+    }
+}
+
+impl Material for Tinted {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        self.filter(hit, self.base.eval(hit, wo, wi))
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let sample = self.base.sample(hit, wo)?;
+        Some(Sample {
+            value: self.filter(hit, sample.value),
+            ..sample
+        })
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        self.base.pdf(hit, wo, wi)
+    }
+
+    fn is_delta(&self, hit: &HitRecord) -> bool {
+        self.base.is_delta(hit)
+    }
+
+    fn is_dispersive(&self, hit: &HitRecord) -> bool {
+        self.base.is_dispersive(hit)
+    }
+
+    fn is_cut_out(&self, hit: &HitRecord) -> bool {
+        self.base.is_cut_out(hit)
+    }
+}
+
+impl Points {
+    /// Create a new point cloud, drawing each point with the given radius.
+    pub fn new(
+        cloud: PointCloud,
+        radius: f64,
+        shape: PointShape,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        let radius = radius.max(0.0);
+        let extent = Vec3::new(radius, radius, radius);
+        let bounds: Vec<Aabb> = cloud
+            .positions
+            .iter()
+            .map(|&position| Aabb::from_points(position - extent, position + extent))
+            .collect();
+        let cloud = Rc::new(Cloud {
+            bvh: Bvh::new(&bounds),
+            points: cloud,
+            radius,
+        });
+
+        // The colors are looked up when shading rather than kept with each hit, so a single
+        // material covers every point.
+        let material: Rc<dyn Material> = match cloud.points.colors {
+            Some(_) => Rc::new(Tinted {
+                base: material,
+                cloud: cloud.clone(),
+            }),
+            None => material,
+        };
+
+        Self {
+            cloud,
+            shape,
+            material,
+        }
+    }
+
+    /// Intersect the ray with a single point, returning the time and outward normal of the hit.
+    fn hit_point(&self, index: usize, ray: Ray, interval: Interval) -> Option<(f64, Vec3)> {
+        let center = self.cloud.points.positions[index];
+        match self.shape {
+            PointShape::Sphere => {
+                let oc = center - ray.origin;
+                let a = ray.direction.length_squared();
+                let h = ray.direction.dot(oc);
+                let c = oc.length_squared() - self.cloud.radius * self.cloud.radius;
+                let discriminant = h * h - a * c;
+                if discriminant < 0.0 {
+                    return None;
+                }
+                let root = discriminant.sqrt();
+                let time = [(h - root) / a, (h + root) / a]
+                    .into_iter()
+                    .find(|&t| interval.surrounds(t))?;
+                Some((time, (ray.at(time) - center) / self.cloud.radius))
+            }
+            PointShape::Disk => {
+                let normal = match &self.cloud.points.normals {
+                    Some(normals) => normals[index].unit_vector(),
+                    None => -ray.direction.unit_vector(),
+                };
+                let denominator = normal.dot(ray.direction);
+                if denominator.abs() < 1e-12 {
+                    return None;
+                }
+                let time = normal.dot(center - ray.origin) / denominator;
+                if !interval.surrounds(time)
+                    || (ray.at(time) - center).length_squared()
+                        > self.cloud.radius * self.cloud.radius
+                {
+                    return None;
+                }
+                Some((time, normal))
+            }
+        }
+    }
+}
+
+impl Hittable for Points {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let mut normal = Vec3::ZERO;
+        let (point, time) = self
+            .cloud
+            .bvh
+            .closest_hit(ray, interval, |index, interval| {
+                let (time, outward_normal) = self.hit_point(index, ray, interval)?;
+                normal = outward_normal;
+                Some(time)
+            })?;
+        let outward_normal = normal;
+        let hit_point = ray.at(time);
+
+        let (u, v, dpdu, dpdv) = match self.shape {
+            PointShape::Sphere => {
+                let (u, v, dpdu, dpdv) = Sphere::surface_coordinates(outward_normal);
+                (u, v, dpdu * self.cloud.radius, dpdv * self.cloud.radius)
+            }
+            PointShape::Disk => {
+                // Straight across the splat, as the direction it is turned around its normal is
+                // arbitrary anyway.
+                let (tangent, bitangent) = math::orthonormal_basis(outward_normal);
+                let planar = (hit_point - self.cloud.points.positions[point]) / self.cloud.radius;
+                (
+                    (planar.dot(tangent) + 1.0) / 2.0,
+                    (planar.dot(bitangent) + 1.0) / 2.0,
+                    tangent * (2.0 * self.cloud.radius),
+                    bitangent * (2.0 * self.cloud.radius),
+                )
+            }
+        };
+
+        let mut record = HitRecord {
+            point: hit_point,
+            normal: outward_normal,
+            u,
+            v,
+            dpdu,
+            dpdv,
+            material: self.material.clone(),
+            time,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, outward_normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.cloud.bvh.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::material::Lambertian;
+
+    #[test]
+    fn points_are_tinted_by_their_own_colors() {
+        let cloud = || PointCloud {
+            positions: vec![Point3::new(-1.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0)],
+            colors: Some(vec![Color::new(1.0, 0.0, 0.0), Color::new(0.0, 0.0, 1.0)]),
+            normals: None,
+        };
+        let white = Rc::new(Lambertian {
+            albedo: Color::new(1.0, 1.0, 1.0),
+        });
+
+        for shape in [PointShape::Sphere, PointShape::Disk] {
+            let points = Points::new(cloud(), 0.5, shape, white.clone());
+            for (x, expected) in [
+                (-1.1, Color::new(1.0, 0.0, 0.0)),
+                (0.9, Color::new(0.0, 0.0, 1.0)),
+            ] {
+                let ray = Ray::new(Point3::new(x, 0.2, 5.0), Vec3::new(0.0, 0.0, -1.0));
+                let hit = points
+                    .hit(ray, Interval::new(0.001, f64::INFINITY))
+                    .unwrap();
+                let shading = ShadingContext {
+                    hit: &hit,
+                    wavelengths: None,
+                };
+                let sample = hit.material.sample(&shading, -ray.direction).unwrap();
+                assert!((sample.value / sample.pdf - expected).length() < 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{Point3, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// Represents a ray traceable parallelogram.
+///
+/// The quad is spanned by the edges `u` and `v` starting from the corner `corner`. The surface
+/// coordinates run from 0 to 1 along each edge, and the front face is the one for which `u` and `v`
+/// appear in counterclockwise order.
+#[derive(Clone)]
+pub struct Quad {
+    pub corner: Point3,
+    pub u: Vec3,
+    pub v: Vec3,
+    pub material: Rc<dyn Material>,
+    normal: Vec3,
+    offset: f64,
+    w: Vec3,
+}
+
+impl Quad {
+    /// Create a new quad from a corner and two edges.
+    pub fn new(corner: Point3, u: Vec3, v: Vec3, material: Rc<dyn Material>) -> Self {
+        let n = u.cross(v);
+        let normal = n.unit_vector();
+
+        Self {
+            corner,
+            u,
+            v,
+            material,
+            normal,
+            offset: normal.dot(corner),
+            w: n / n.length_squared(),
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        // A ray parallel to the plane of the quad never hits it.
+        let denominator = self.normal.dot(ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let root = (self.offset - self.normal.dot(ray.origin)) / denominator;
+        if !interval.surrounds(root) {
+            return None;
+        }
+
+        // Express the point in the plane in terms of the two edges. It is inside the quad exactly
+        // when both coefficients lie between 0 and 1.
+        let hit_point = ray.at(root);
+        let planar = hit_point - self.corner;
+        let alpha = self.w.dot(planar.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        let mut record = HitRecord {
+            point: hit_point,
+            normal: self.normal,
+            u: alpha,
+            v: beta,
+            dpdu: self.u,
+            dpdv: self.v,
+            material: self.material.clone(),
+            time: root,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, self.normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let diagonal = Aabb::from_points(self.corner, self.corner + self.u + self.v);
+        let other_diagonal = Aabb::from_points(self.corner + self.u, self.corner + self.v);
+        diagonal.union(other_diagonal)
+    }
+}
@@ -0,0 +1,182 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::matrix::Mat4;
+use raytracing::math::polynomial;
+use raytracing::math::ray::Ray;
+use raytracing::math::{self, Point3, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// Represents a ray traceable quadric surface, clipped to a box.
+///
+/// A quadric is the set of points `p` where `p^T Q p = 0`, with `p` written as `(x, y, z, 1)` and
+/// `Q` a symmetric 4x4 matrix. Spheres, ellipsoids, cylinders, cones, paraboloids and
+/// hyperboloids are all quadrics. Many of them extend infinitely far, so only the part of the
+/// surface inside `bounds` is kept, which leaves the surface open where the box cuts it.
+///
+/// The `u` coordinate goes once around the vertical line through the center of the box, and the
+/// `v` coordinate runs from its bottom to its top.
+#[derive(Clone)]
+pub struct Quadric {
+    pub matrix: Mat4,
+    pub bounds: Aabb,
+    pub material: Rc<dyn Material>,
+}
+
+impl Quadric {
+    /// Create a new quadric from its matrix and the box it is clipped to.
+    ///
+    /// The matrix does not need to be symmetric, as only its symmetric part matters.
+    pub fn new(matrix: Mat4, bounds: Aabb, material: Rc<dyn Material>) -> Self {
+        Self {
+            matrix: Mat4::new(std::array::from_fn(|i| {
+                std::array::from_fn(|j| (matrix.rows[i][j] + matrix.rows[j][i]) / 2.0)
+            })),
+            bounds,
+            material,
+        }
+    }
+
+    /// Get the matrix of a quadric after moving it by an invertible transformation.
+    ///
+    /// If `p` is on the original surface, then `transform p` is on the new one. Returns [`None`]
+    /// if the transformation cannot be inverted, as it flattens space and there is no quadric
+    /// left to describe.
+    pub fn transform_matrix(matrix: Mat4, transform: Mat4) -> Option<Mat4> {
+        let inverse = transform.inverse()?;
+        Some(inverse.transpose() * matrix * inverse)
+    }
+
+    /// Create an ellipsoid with the given radii along each axis.
+    pub fn ellipsoid(center: Point3, radii: Vec3, material: Rc<dyn Material>) -> Self {
+        let matrix = Mat4::new([
+            [1.0 / (radii.x * radii.x), 0.0, 0.0, 0.0],
+            [0.0, 1.0 / (radii.y * radii.y), 0.0, 0.0],
+            [0.0, 0.0, 1.0 / (radii.z * radii.z), 0.0],
+            [0.0, 0.0, 0.0, -1.0],
+        ]);
+        Self::new(
+            Self::transform_matrix(matrix, Mat4::translation(center))
+                .expect("translations are invertible"),
+            Aabb::from_points(center - radii, center + radii),
+            material,
+        )
+    }
+
+    /// Create a paraboloid opening upward from its lowest point.
+    ///
+    /// The paraboloid is cut off at the given height, where its radius has grown to `radius`.
+    pub fn paraboloid(
+        vertex: Point3,
+        radius: f64,
+        height: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        // x^2 + z^2 = r^2 y / h, with the linear term split between the two off-diagonal entries.
+        let r2 = radius * radius;
+        let matrix = Mat4::new([
+            [1.0 / r2, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, -0.5 / height],
+            [0.0, 0.0, 1.0 / r2, 0.0],
+            [0.0, -0.5 / height, 0.0, 0.0],
+        ]);
+        Self::new(
+            Self::transform_matrix(matrix, Mat4::translation(vertex))
+                .expect("translations are invertible"),
+            Aabb::from_points(
+                vertex + Vec3::new(-radius, 0.0, -radius),
+                vertex + Vec3::new(radius, height, radius),
+            ),
+            material,
+        )
+    }
+
+    /// Create a hyperboloid of one sheet around the vertical axis.
+    ///
+    /// The hyperboloid has a radius of `waist` at its narrowest, and away from there its walls
+    /// approach a cone whose radius grows by `waist` for every `flare` units of height. It is cut
+    /// off at half the given height above and below its center.
+    pub fn hyperboloid(
+        center: Point3,
+        waist: f64,
+        flare: f64,
+        height: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        let matrix = Mat4::new([
+            [1.0 / (waist * waist), 0.0, 0.0, 0.0],
+            [0.0, -1.0 / (flare * flare), 0.0, 0.0],
+            [0.0, 0.0, 1.0 / (waist * waist), 0.0],
+            [0.0, 0.0, 0.0, -1.0],
+        ]);
+        let half_height = height / 2.0;
+        let radius = waist * (1.0 + half_height * half_height / (flare * flare)).sqrt();
+        Self::new(
+            Self::transform_matrix(matrix, Mat4::translation(center))
+                .expect("translations are invertible"),
+            Aabb::from_points(
+                center - Vec3::new(radius, half_height, radius),
+                center + Vec3::new(radius, half_height, radius),
+            ),
+            material,
+        )
+    }
+}
+
+impl Hittable for Quadric {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let interval = self.bounds.clip(ray, interval)?;
+
+        // Substituting the ray into the quadratic form gives a quadratic in t, since the matrix is
+        // symmetric.
+        let o = [ray.origin.x, ray.origin.y, ray.origin.z, 1.0];
+        let d = [ray.direction.x, ray.direction.y, ray.direction.z, 0.0];
+        let a = self.matrix.bilinear(d, d);
+        let b = 2.0 * self.matrix.bilinear(d, o);
+        let c = self.matrix.bilinear(o, o);
+
+        // The box test is repeated for each root, because the surface may leave the box and come
+        // back in between them.
+        let inside = |p: Point3| {
+            self.bounds.x.contains(p.x)
+                && self.bounds.y.contains(p.y)
+                && self.bounds.z.contains(p.z)
+        };
+        let root = polynomial::solve_quadratic(a, b, c)
+            .into_iter()
+            .find(|&t| interval.surrounds(t) && inside(ray.at(t)))?;
+
+        // The gradient of the quadratic form is twice the matrix times the point.
+        let point = ray.at(root);
+        let [x, y, z, _] = self.matrix.apply([point.x, point.y, point.z, 1.0]);
+        let outward_normal = Vec3::new(x, y, z).unit_vector();
+        let (dpdu, dpdv) = math::orthonormal_basis(outward_normal);
+
+        let center = self.bounds.centroid();
+        let phi = (point.z - center.z).atan2(point.x - center.x);
+        let height = self.bounds.y.size().max(1e-12);
+
+        let mut record = HitRecord {
+            point,
+            normal: outward_normal,
+            u: phi / (2.0 * PI) + 0.5,
+            v: (point.y - self.bounds.y.min) / height,
+            dpdu,
+            dpdv,
+            material: self.material.clone(),
+            time: root,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, outward_normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounds
+    }
+}
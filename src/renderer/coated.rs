@@ -0,0 +1,274 @@
+use std::rc::Rc;
+
+use raytracing::color::Color;
+use raytracing::math::Vec3;
+use raytracing::microfacet::{self, TrowbridgeReitz};
+use raytracing::texture::Texture;
+
+use crate::hittable::HitRecord;
+use crate::renderer::material::{Material, Sample, ShadingContext};
+use crate::renderer::microfacet::{ShadingFrame, glossy_reflection, reflect};
+
+/// A material covered by a thin layer of varnish, like car paint or lacquered wood.
+///
+/// Light either reflects off the top of the coat, which is a rough dielectric interface, or
+/// refracts into it, scatters off the base material, and refracts back out. On the way through,
+/// the coat absorbs some of it. The `tint` is the color the coat lets through going straight down
+/// and back up again, and it filters more strongly at grazing angles, where light travels
+/// further through the coat. White gives a clear coat.
+///
+/// Light reflected back down by the underside of the coat is not followed any further, so even a
+/// clear coat over a white base loses around a tenth of the light.
+///
+/// Like for a [`Conductor`](crate::renderer::microfacet::Conductor), an anisotropic distribution
+/// follows the surface tangent, turned by the `tangent_rotation` texture.
+pub struct Coated {
+    pub base: Rc<dyn Material>,
+    pub refractive_index: f64,
+    pub distribution: TrowbridgeReitz,
+    pub tint: Color,
+    pub tangent_rotation: Option<Rc<dyn Texture>>,
+}
+
+impl Coated {
+    /// Cover a material with a clear coat of the given roughness and the refractive index of
+    /// common varnish.
+    pub fn new(base: Rc<dyn Material>, roughness: f64) -> Self {
+        Self {
+            base,
+            refractive_index: 1.5,
+            distribution: TrowbridgeReitz::isotropic(roughness),
+            tint: Color::new(1.0, 1.0, 1.0),
+            tangent_rotation: None,
+        }
+    }
+
+    /// Get the direction inside the coat corresponding to one outside of it.
+    ///
+    /// Both directions point away from the surface.
+    fn refract_in(&self, w: Vec3, normal: Vec3) -> Vec3 {
+        let cos = w.dot(normal);
+        let across = (w - normal * cos) / self.refractive_index;
+        across + normal * (1.0 - across.length_squared()).max(0.0).sqrt()
+    }
+
+    /// Get the direction outside the coat corresponding to one inside of it, unless it is totally
+    /// reflected back in.
+    fn refract_out(&self, w: Vec3, normal: Vec3) -> Option<Vec3> {
+        let cos = w.dot(normal);
+        let across = (w - normal * cos) * self.refractive_index;
+        let sin2 = across.length_squared();
+        (cos > 0.0 && sin2 < 1.0).then(|| across + normal * (1.0 - sin2).sqrt())
+    }
+
+    /// Get the fraction of light the top of the coat reflects, for light hitting it head on to
+    /// the surface with the given cosine.
+    fn fresnel(&self, cos: f64) -> f64 {
+        microfacet::fresnel_dielectric(cos, self.refractive_index)
+    }
+
+    /// Get the fraction of light which makes it through the coat down and back up, given the
+    /// cosines of both directions inside it.
+    fn transmittance(&self, hit: &ShadingContext, cos_o: f64, cos_i: f64) -> Color {
+        let passes = (1.0 / cos_o + 1.0 / cos_i) / 2.0;
+        let tint = hit.color(self.tint);
+        Color::new(
+            tint.x.powf(passes),
+            tint.y.powf(passes),
+            tint.z.powf(passes),
+        )
+    }
+
+    /// Evaluate the light which goes through the coat, or get the weight of a direction picked by
+    /// a delta lobe of the base.
+    ///
+    /// The value is for the outside measure, so the change in solid angle from refraction is
+    /// included.
+    fn through_coat(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3, base: Color) -> Color {
+        let normal = hit.normal;
+        let (inner_o, inner_i) = (self.refract_in(wo, normal), self.refract_in(wi, normal));
+        let (cos_o, cos_i) = (inner_o.dot(normal), inner_i.dot(normal));
+        let outer = (1.0 - self.fresnel(wo.dot(normal))) * (1.0 - self.fresnel(wi.dot(normal)));
+        let transmittance = self.transmittance(hit, cos_o, cos_i);
+
+        Color::new(
+            base.x * transmittance.x,
+            base.y * transmittance.y,
+            base.z * transmittance.z,
+        ) * outer
+    }
+}
+
+impl Material for Coated {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        let normal = hit.normal;
+        if wo.dot(normal) <= 0.0 || wi.dot(normal) <= 0.0 {
+            return Color::ZERO;
+        }
+
+        let mut value = Color::ZERO;
+        if !self.distribution.is_smooth() {
+            let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+            let (local_o, local_i) = (frame.to_local(wo), frame.to_local(wi));
+            if let Some(lobe) = glossy_reflection(&self.distribution, local_o, local_i) {
+                let fresnel = self.fresnel(local_o.dot(lobe.m));
+                value += Color::new(1.0, 1.0, 1.0) * (fresnel * lobe.value);
+            }
+        }
+
+        let (inner_o, inner_i) = (self.refract_in(wo, normal), self.refract_in(wi, normal));
+        let jacobian = wi.dot(normal) / inner_i.dot(normal);
+        let base = self.base.eval(hit, inner_o, inner_i) * jacobian;
+        value + self.through_coat(hit, wo, wi, base)
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let normal = hit.normal;
+        let cos_o = wo.dot(normal);
+        if cos_o <= 0.0 {
+            return None;
+        }
+
+        // Pick the coat in proportion to how much it reflects head on to the surface.
+        if raytracing::random_f64() < self.fresnel(cos_o) {
+            let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+            let local = frame.to_local(wo);
+            if self.distribution.is_smooth() {
+                return Some(Sample {
+                    direction: frame.to_world(Vec3::new(-local.x, -local.y, local.z)),
+                    value: Color::new(1.0, 1.0, 1.0),
+                    pdf: 1.0,
+                    is_delta: true,
+                    exit: None,
+                });
+            }
+
+            let m = self.distribution.sample_visible_normal(
+                local,
+                raytracing::random_f64(),
+                raytracing::random_f64(),
+            );
+            let wi = reflect(local, m);
+            if wi.z <= 0.0 {
+                return None;
+            }
+            let direction = frame.to_world(wi);
+            return Some(Sample {
+                direction,
+                value: self.eval(hit, wo, direction),
+                pdf: self.pdf(hit, wo, direction),
+                is_delta: false,
+                exit: None,
+            });
+        }
+
+        let inner_o = self.refract_in(wo, normal);
+        let sample = self.base.sample(hit, inner_o)?;
+        let direction = self.refract_out(sample.direction, normal)?;
+        if sample.is_delta {
+            // The probability of picking the base cancels the Fresnel term on the way in.
+            let inner = sample.value / (1.0 - self.fresnel(cos_o));
+            return Some(Sample {
+                direction,
+                value: self.through_coat(hit, wo, direction, inner),
+                pdf: 1.0,
+                is_delta: true,
+                exit: sample.exit,
+            });
+        }
+
+        Some(Sample {
+            direction,
+            value: self.eval(hit, wo, direction),
+            pdf: self.pdf(hit, wo, direction),
+            is_delta: false,
+            exit: None,
+        })
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        let normal = hit.normal;
+        let cos_o = wo.dot(normal);
+        if cos_o <= 0.0 || wi.dot(normal) <= 0.0 {
+            return 0.0;
+        }
+        let coat = self.fresnel(cos_o);
+
+        let mut pdf = 0.0;
+        if !self.distribution.is_smooth() {
+            let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+            if let Some(lobe) =
+                glossy_reflection(&self.distribution, frame.to_local(wo), frame.to_local(wi))
+            {
+                pdf += coat * lobe.pdf;
+            }
+        }
+
+        // Refraction squeezes the directions inside the coat together, which spreads their
+        // density out over a wider range of directions outside of it.
+        let (inner_o, inner_i) = (self.refract_in(wo, normal), self.refract_in(wi, normal));
+        let eta2 = self.refractive_index * self.refractive_index;
+        let jacobian = wi.dot(normal) / (eta2 * inner_i.dot(normal));
+        pdf + (1.0 - coat) * self.base.pdf(hit, inner_o, inner_i) * jacobian
+    }
+
+    fn is_delta(&self, hit: &HitRecord) -> bool {
+        self.distribution.is_smooth() && self.base.is_delta(hit)
+    }
+
+    fn is_dispersive(&self, hit: &HitRecord) -> bool {
+        self.base.is_dispersive(hit)
+    }
+
+    fn is_cut_out(&self, hit: &HitRecord) -> bool {
+        self.base.is_cut_out(hit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raytracing::texture::SolidColor;
+
+    use super::*;
+    use crate::renderer::material::Lambertian;
+    use crate::renderer::material::tests::{albedo, assert_albedo, assert_consistent, direction};
+    use crate::renderer::microfacet::Conductor;
+
+    fn white() -> Rc<dyn Material> {
+        Rc::new(Lambertian {
+            albedo: Color::new(1.0, 1.0, 1.0),
+        })
+    }
+
+    #[test]
+    fn samples_match_eval_and_pdf() {
+        let materials = [
+            Coated::new(white(), 0.0),
+            Coated::new(white(), 0.2),
+            Coated::new(Rc::new(Conductor::gold(0.3)), 0.1),
+            Coated {
+                distribution: TrowbridgeReitz::anisotropic(0.1, 0.4),
+                tangent_rotation: Some(Rc::new(SolidColor::new(Color::new(0.1, 0.1, 0.1)))),
+                ..Coated::new(white(), 0.0)
+            },
+        ];
+        for material in materials {
+            let material = Rc::new(material);
+            for angle in [0.0, 45.0, 80.0] {
+                assert_consistent(material.clone(), direction(angle));
+            }
+        }
+    }
+
+    #[test]
+    fn clear_coat_loses_little_energy() {
+        // Light the coat reflects back down on its way out is lost, so some energy goes missing,
+        // but never more than that.
+        for roughness in [0.0, 0.2] {
+            let coated = Rc::new(Coated::new(white(), roughness));
+            for angle in [0.0, 45.0, 80.0] {
+                assert_albedo(albedo(coated.clone(), direction(angle)), 0.85, 1.01);
+            }
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use std::f64::consts::PI;
+
+use raytracing::color::Color;
+use raytracing::math::Vec3;
+
+use crate::renderer::material::{Material, Sample, ShadingContext};
+
+/// An approximation of the way light scatters off a hair fiber.
+///
+/// This follows the three lobes of the Marschner model. Light is either reflected straight off
+/// the surface (R), which gives a white highlight, transmitted through the fiber (TT), or
+/// reflected once off the inside of it (TRT), which gives a colored highlight. In every case, the
+/// light leaves on a cone around the fiber with about the same angle to it that it came in at.
+///
+/// The lobes are shifted along the fiber by the tilt of the scales on the surface of the hair,
+/// and spread out by `roughness`, both in radians. The `color` is the fraction of light which
+/// survives passing through the fiber once.
+///
+/// Each lobe is a Gaussian in both angles, cut off where the angles would wrap around, so the
+/// density of a direction is known exactly. The lobes are not renormalized over what is left, so
+/// the light they would have sent past the cut is lost. That is next to nothing for smooth fibers,
+/// but rough ones, and lobes near the ends of the fiber, come out a little dark.
+///
+/// The fiber runs along `dpdu` of the hit, so this only makes sense on curves and other shapes
+/// with a meaningful tangent.
+pub struct Hair {
+    pub color: Color,
+    pub roughness: f64,
+    pub tilt: f64,
+}
+
+/// The refractive index of keratin, which hair is made of.
+const KERATIN_INDEX: f64 = 1.55;
+
+impl Hair {
+    /// Create a hair material with typical roughness and scale tilt.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            roughness: 0.15,
+            tilt: 0.05,
+        }
+    }
+}
+
+/// Draw a sample from the standard normal distribution with the Box-Muller transform.
+fn random_normal() -> f64 {
+    let radius = (-2.0 * (1.0 - raytracing::random_f64()).ln()).sqrt();
+    radius * (2.0 * PI * raytracing::random_f64()).cos()
+}
+
+/// Evaluate the density of a normal distribution with a mean of zero.
+fn gaussian(x: f64, deviation: f64) -> f64 {
+    (-x * x / (2.0 * deviation * deviation)).exp() / (deviation * (2.0 * PI).sqrt())
+}
+
+/// One of the three ways light leaves a fiber.
+#[derive(Clone, Copy)]
+struct Lobe {
+    /// The fraction of light carried by the lobe.
+    attenuation: Color,
+
+    /// The angle to the fiber the lobe is centered on.
+    theta: f64,
+
+    /// The direction around the fiber the lobe is centered on.
+    around: Vec3,
+
+    /// The standard deviation of the lobe in both angles.
+    deviation: f64,
+}
+
+/// The average of the channels of a color, used to pick lobes.
+fn weight(color: Color) -> f64 {
+    (color.x + color.y + color.z) / 3.0
+}
+
+impl Hair {
+    /// Get the direction of the fiber and the lobes light leaves along toward `wo`.
+    fn lobes(&self, hit: &ShadingContext, wo: Vec3) -> Option<(Vec3, [Lobe; 3])> {
+        let tangent = hit.dpdu.unit_vector();
+        let incoming = -wo;
+
+        // Split the incoming direction into parts along the fiber and around it.
+        let sin_theta = incoming.dot(tangent).clamp(-1.0, 1.0);
+        let across = incoming - tangent * sin_theta;
+        if across.is_near_zero() {
+            return None;
+        }
+        let across = across.unit_vector();
+
+        // Schlick's approximation of how much light the surface reflects, looking only at the
+        // angle around the fiber, since that is what the cross section of the fiber sees.
+        let cos_gamma = across.dot(hit.normal).abs();
+        let f0 = ((KERATIN_INDEX - 1.0) / (KERATIN_INDEX + 1.0)).powi(2);
+        let fresnel = f0 + (1.0 - f0) * (1.0 - cos_gamma).powi(5);
+
+        let reflected_across = across - hit.normal * (2.0 * across.dot(hit.normal));
+        let color = hit.color(self.color);
+        let absorbed = |passes: i32| {
+            Color::new(
+                color.x.powi(passes),
+                color.y.powi(passes),
+                color.z.powi(passes),
+            )
+        };
+
+        // Light leaves at the same angle to the fiber that it came in at, give or take the tilt
+        // of the scales and the roughness of the surface. The lobes are blurred around the fiber
+        // by the roughness too, the transmitted one least and the internal reflection most.
+        let theta = sin_theta.asin();
+        let lobe = |attenuation, shift: f64, spread: f64, around| Lobe {
+            attenuation,
+            theta: theta + shift * self.tilt,
+            around,
+            deviation: (spread * self.roughness).max(1e-3),
+        };
+        Some((
+            tangent,
+            [
+                lobe(
+                    Color::new(fresnel, fresnel, fresnel),
+                    2.0,
+                    1.0,
+                    reflected_across,
+                ),
+                lobe(absorbed(2) * (1.0 - fresnel).powi(2), -1.0, 0.5, across),
+                lobe(
+                    absorbed(4) * ((1.0 - fresnel).powi(2) * fresnel),
+                    -4.0,
+                    2.0,
+                    reflected_across,
+                ),
+            ],
+        ))
+    }
+
+    /// Get the density of a lobe sending light out along the direction, over solid angle.
+    fn lobe_pdf(tangent: Vec3, lobe: &Lobe, direction: Vec3) -> f64 {
+        let sin_theta = direction.dot(tangent).clamp(-1.0, 1.0);
+        let cos_theta = (1.0 - sin_theta * sin_theta).sqrt();
+        if cos_theta < 1e-6 {
+            return 0.0;
+        }
+        let across = direction - tangent * sin_theta;
+        let phi = across
+            .dot(tangent.cross(lobe.around))
+            .atan2(across.dot(lobe.around));
+
+        gaussian(sin_theta.asin() - lobe.theta, lobe.deviation) * gaussian(phi, lobe.deviation)
+            / cos_theta
+    }
+}
+
+impl Material for Hair {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        let Some((tangent, lobes)) = self.lobes(hit, wo) else {
+            return Color::ZERO;
+        };
+        lobes.iter().fold(Color::ZERO, |total, lobe| {
+            total + lobe.attenuation * Self::lobe_pdf(tangent, lobe, wi)
+        })
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let (tangent, lobes) = self.lobes(hit, wo)?;
+
+        // Pick a lobe in proportion to how much light it carries.
+        let total: f64 = lobes.iter().map(|lobe| weight(lobe.attenuation)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut choice = raytracing::random_f64() * total;
+        let lobe = lobes
+            .iter()
+            .copied()
+            .find(|lobe| {
+                choice -= weight(lobe.attenuation);
+                choice <= 0.0
+            })
+            .unwrap_or(lobes[0]);
+
+        // Angles past the ends of the fiber or all the way around it would land on directions
+        // whose density is that of a different angle, so they are dropped instead.
+        let theta = lobe.theta + random_normal() * lobe.deviation;
+        let twist = random_normal() * lobe.deviation;
+        if theta.abs() >= PI / 2.0 || twist.abs() > PI {
+            return None;
+        }
+        let (sin_out, cos_out) = theta.sin_cos();
+        let around =
+            (lobe.around * twist.cos() + tangent.cross(lobe.around) * twist.sin()).unit_vector();
+        let direction = tangent * sin_out + around * cos_out;
+
+        Some(Sample {
+            direction,
+            value: self.eval(hit, wo, direction),
+            pdf: self.pdf(hit, wo, direction),
+            is_delta: false,
+            exit: None,
+        })
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        let Some((tangent, lobes)) = self.lobes(hit, wo) else {
+            return 0.0;
+        };
+        let total: f64 = lobes.iter().map(|lobe| weight(lobe.attenuation)).sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        lobes
+            .iter()
+            .map(|lobe| weight(lobe.attenuation) / total * Self::lobe_pdf(tangent, lobe, wi))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::renderer::material::tests::{albedo, assert_albedo, assert_consistent, direction};
+
+    #[test]
+    fn samples_match_eval_and_pdf() {
+        for roughness in [0.15, 1.0] {
+            let hair = Rc::new(Hair {
+                roughness,
+                ..Hair::new(Color::new(0.8, 0.5, 0.3))
+            });
+            for angle in [0.0, 45.0, 85.0] {
+                assert_consistent(hair.clone(), direction(angle));
+            }
+        }
+    }
+
+    #[test]
+    fn never_creates_energy() {
+        for roughness in [0.15, 1.0, 2.0] {
+            let hair = Rc::new(Hair {
+                roughness,
+                ..Hair::new(Color::new(1.0, 1.0, 1.0))
+            });
+            for angle in [0.0, 45.0, 85.0] {
+                assert_albedo(albedo(hair.clone(), direction(angle)), 0.0, 1.01);
+            }
+        }
+    }
+}
@@ -1,16 +1,142 @@
-use raytracing::color::Color;
-use raytracing::math::ray::Ray;
-use raytracing::math::{self, Vec3};
+use std::f64::consts::PI;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use raytracing::color::{self, Color};
+use raytracing::math::{self, Point3, Vec3};
+use raytracing::spectrum::Wavelengths;
+use raytracing::texture::{SolidColor, Texture};
 
 use crate::hittable::HitRecord;
 
-pub struct Reflection {
-    pub ray: Ray,
-    pub attenuation: Color,
+/// A direction picked by [`Material::sample`], along with what the material does to light
+/// arriving from it.
+pub struct Sample {
+    /// The direction light arrives from, pointing away from the surface.
+    pub direction: Vec3,
+
+    /// The scattering function for this direction, times the cosine of its angle to the normal.
+    pub value: Color,
+
+    /// The probability density of picking this direction, over solid angle.
+    pub pdf: f64,
+
+    /// Whether the direction comes from a delta lobe, like that of a perfect mirror.
+    ///
+    /// Delta lobes scatter into single directions, which [`Material::eval`] and
+    /// [`Material::pdf`] can never hit. For these, the value already has the probability of
+    /// picking the lobe divided out, and the pdf is 1.
+    pub is_delta: bool,
+
+    /// Where the light leaves the surface, if it travels beneath it and comes out somewhere other
+    /// than the point of the hit.
+    pub exit: Option<Point3>,
 }
 
+/// A hit being shaded, along with the wavelengths light is traced at along the path.
+///
+/// This dereferences to the hit itself, so materials can read its geometry directly.
+#[derive(Clone, Copy)]
+pub struct ShadingContext<'a> {
+    pub hit: &'a HitRecord,
+
+    /// The wavelengths the path carries when rendering spectrally, or nothing when light is
+    /// traced in red, green and blue.
+    pub wavelengths: Option<Wavelengths>,
+}
+
+impl ShadingContext<'_> {
+    /// Shade a different hit with the same wavelengths, like one whose normal has been perturbed.
+    pub fn with_hit<'b>(&self, hit: &'b HitRecord) -> ShadingContext<'b> {
+        ShadingContext {
+            hit,
+            wavelengths: self.wavelengths,
+        }
+    }
+
+    /// Get the hero wavelength, which properties with a single value for all channels are
+    /// evaluated at, when rendering spectrally.
+    pub fn wavelength(&self) -> Option<f64> {
+        self.wavelengths.map(|wavelengths| wavelengths.hero())
+    }
+
+    /// Turn a color into the values the path carries, which is the color itself, or its spectrum
+    /// at each of the wavelengths when rendering spectrally.
+    pub fn color(&self, color: Color) -> Color {
+        match self.wavelengths {
+            Some(wavelengths) => wavelengths.upsample(color),
+            None => color,
+        }
+    }
+}
+
+impl Deref for ShadingContext<'_> {
+    type Target = HitRecord;
+
+    fn deref(&self) -> &HitRecord {
+        self.hit
+    }
+}
+
+/// How light scatters at a surface, or inside a participating medium.
+///
+/// Directions are all unit vectors pointing away from the point of the hit. The outgoing
+/// direction `wo` points back the way the ray came, toward the camera, while the incoming
+/// direction `wi` points toward where light arrives from. Surfaces include the cosine of the
+/// angle between `wi` and the normal in the values they return, which phase functions of media
+/// do not have.
+///
+/// The values returned are those the path carries, so materials turn their colors into spectra
+/// with [`ShadingContext::color`] when rendering spectrally.
 pub trait Material {
-    fn scatter(&self, ray: Ray, hit: HitRecord) -> Option<Reflection>;
+    /// Evaluate the scattering function from `wi` to `wo`, times the cosine of `wi`.
+    ///
+    /// Delta lobes contribute nothing here.
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color;
+
+    /// Pick a direction light could arrive from, in proportion to how much of it would be
+    /// scattered toward `wo`.
+    ///
+    /// Returns nothing if the light is absorbed.
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample>;
+
+    /// Get the probability density of [`Material::sample`] picking `wi`, over solid angle.
+    ///
+    /// Delta lobes contribute nothing here.
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64;
+
+    /// Determine whether the material only scatters into delta lobes at the point of the hit.
+    ///
+    /// There is no point in evaluating such a material for any direction other than those it
+    /// samples, so light sampling can skip it.
+    fn is_delta(&self, _hit: &HitRecord) -> bool {
+        false
+    }
+
+    /// Determine whether the material sends light of different wavelengths different ways.
+    ///
+    /// When rendering spectrally, only the hero wavelength is followed past such a material.
+    fn is_dispersive(&self, _hit: &HitRecord) -> bool {
+        false
+    }
+
+    /// Determine whether the surface is cut out at the point of the hit.
+    ///
+    /// Intersections with cut out parts of a surface are ignored entirely, and the ray carries on
+    /// as if the surface was not there. Most materials are opaque everywhere.
+    fn is_cut_out(&self, _hit: &HitRecord) -> bool {
+        false
+    }
+}
+
+/// Pick a direction around the normal with a density proportional to its cosine.
+pub(crate) fn random_cosine_direction(normal: Vec3) -> Vec3 {
+    let direction = normal + Vec3::random_unit_vector();
+    if direction.is_near_zero() {
+        normal
+    } else {
+        direction.unit_vector()
+    }
 }
 
 /// A diffuse (matte) material.
@@ -19,17 +145,92 @@ pub struct Lambertian {
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _ray: Ray, hit: HitRecord) -> Option<Reflection> {
-        let mut scatter_direction = hit.normal + Vec3::random_unit_vector();
-        if scatter_direction.is_near_zero() {
-            scatter_direction = hit.normal;
+    fn eval(&self, hit: &ShadingContext, _wo: Vec3, wi: Vec3) -> Color {
+        hit.color(self.albedo) * (wi.dot(hit.normal).max(0.0) / PI)
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let direction = random_cosine_direction(hit.normal);
+        Some(Sample {
+            direction,
+            value: self.eval(hit, wo, direction),
+            pdf: self.pdf(hit, wo, direction),
+            is_delta: false,
+            exit: None,
+        })
+    }
+
+    fn pdf(&self, hit: &ShadingContext, _wo: Vec3, wi: Vec3) -> f64 {
+        wi.dot(hit.normal).max(0.0) / PI
+    }
+}
+
+/// A rough diffuse material, following the model of Oren and Nayar.
+///
+/// Rough matte surfaces like clay, concrete and cloth are made of tiny facets which are each
+/// diffuse. Facets facing the light are also the ones seen when looking from near the light, so
+/// such surfaces look flatter than a [`Lambertian`] one and get brighter toward grazing angles
+/// instead of darker. The roughness `sigma` is the standard deviation of the angle of the facets,
+/// in radians, and a `sigma` of zero is the same as [`Lambertian`].
+pub struct OrenNayar {
+    pub albedo: Color,
+    pub sigma: f64,
+}
+
+impl OrenNayar {
+    /// Create an Oren-Nayar material with the roughness given in degrees.
+    pub fn new(albedo: Color, sigma_degrees: f64) -> Self {
+        Self {
+            albedo,
+            sigma: sigma_degrees.to_radians(),
+        }
+    }
+}
+
+impl Material for OrenNayar {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        let normal = hit.normal;
+        let (cos_i, cos_o) = (wi.dot(normal), wo.dot(normal));
+        if cos_i <= 0.0 || cos_o <= 0.0 {
+            return Color::ZERO;
         }
 
-        Some(Reflection {
-            ray: Ray::new(hit.point, scatter_direction),
-            attenuation: self.albedo,
+        // The qualitative model, which leaves out the interreflections between facets.
+        let sigma2 = self.sigma * self.sigma;
+        let a = 1.0 - sigma2 / (2.0 * (sigma2 + 0.33));
+        let b = 0.45 * sigma2 / (sigma2 + 0.09);
+
+        let sin_i = (1.0 - cos_i * cos_i).max(0.0).sqrt();
+        let sin_o = (1.0 - cos_o * cos_o).max(0.0).sqrt();
+        let (across_i, across_o) = (wi - normal * cos_i, wo - normal * cos_o);
+        let cos_phi = if sin_i > 1e-4 && sin_o > 1e-4 {
+            (across_i.dot(across_o) / (sin_i * sin_o)).max(0.0)
+        } else {
+            0.0
+        };
+        let (sin_alpha, tan_beta) = if cos_i > cos_o {
+            (sin_o, sin_i / cos_i)
+        } else {
+            (sin_i, sin_o / cos_o)
+        };
+
+        hit.color(self.albedo) * ((a + b * cos_phi * sin_alpha * tan_beta) * cos_i / PI)
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let direction = random_cosine_direction(hit.normal);
+        Some(Sample {
+            direction,
+            value: self.eval(hit, wo, direction),
+            pdf: self.pdf(hit, wo, direction),
+            is_delta: false,
+            exit: None,
         })
     }
+
+    fn pdf(&self, hit: &ShadingContext, _wo: Vec3, wi: Vec3) -> f64 {
+        wi.dot(hit.normal).max(0.0) / PI
+    }
 }
 
 pub struct Metal {
@@ -37,12 +238,532 @@ pub struct Metal {
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray: Ray, hit: HitRecord) -> Option<Reflection> {
-        let reflected = math::reflect(ray.direction, hit.normal);
+    fn eval(&self, _hit: &ShadingContext, _wo: Vec3, _wi: Vec3) -> Color {
+        Color::ZERO
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        Some(Sample {
+            direction: math::reflect(-wo, hit.normal),
+            value: hit.color(self.albedo),
+            pdf: 1.0,
+            is_delta: true,
+            exit: None,
+        })
+    }
+
+    fn pdf(&self, _hit: &ShadingContext, _wo: Vec3, _wi: Vec3) -> f64 {
+        0.0
+    }
+
+    fn is_delta(&self, _hit: &HitRecord) -> bool {
+        true
+    }
+}
+
+/// A material with holes in it.
+///
+/// The luminance of the opacity texture is compared against the threshold, and wherever it falls
+/// below, the surface is cut out. Everywhere else, the base material is used. This is how leaves
+/// and fences are made out of simple quads.
+pub struct AlphaMask {
+    pub base: Rc<dyn Material>,
+    pub opacity: Rc<dyn Texture>,
+    pub threshold: f64,
+}
+
+impl AlphaMask {
+    /// Create an alpha mask which cuts out everything less than half opaque.
+    pub fn new(base: Rc<dyn Material>, opacity: Rc<dyn Texture>) -> Self {
+        Self {
+            base,
+            opacity,
+            threshold: 0.5,
+        }
+    }
+}
+
+impl Material for AlphaMask {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        self.base.eval(hit, wo, wi)
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        self.base.sample(hit, wo)
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        self.base.pdf(hit, wo, wi)
+    }
+
+    fn is_delta(&self, hit: &HitRecord) -> bool {
+        self.base.is_delta(hit)
+    }
+
+    fn is_dispersive(&self, hit: &HitRecord) -> bool {
+        self.base.is_dispersive(hit)
+    }
+
+    fn is_cut_out(&self, hit: &HitRecord) -> bool {
+        let alpha = color::luminance(self.opacity.value(hit.u, hit.v, hit.point));
+        alpha < self.threshold || self.base.is_cut_out(hit)
+    }
+}
+
+/// A blend of two materials, like rust or dirt over metal, or a decal over paint.
+///
+/// The luminance of the weight texture gives how much of the second material there is at each
+/// point, from 0 for only the first to 1 for only the second. Light is scattered by one of the
+/// two, picked at random in those proportions. Where one of them is cut out, like a decal with a
+/// hole in it, the other one gets all of the weight, and the surface is only cut out where every
+/// material it has some of is cut out.
+pub struct Mix {
+    pub first: Rc<dyn Material>,
+    pub second: Rc<dyn Material>,
+    pub weight: Rc<dyn Texture>,
+}
+
+impl Mix {
+    /// Blend two materials by the same weight everywhere.
+    pub fn new(first: Rc<dyn Material>, second: Rc<dyn Material>, weight: f64) -> Self {
+        let weight = Rc::new(SolidColor::new(Color::new(weight, weight, weight)));
+        Self::with_texture(first, second, weight)
+    }
+
+    /// Blend two materials by a weight which varies over the surface.
+    pub fn with_texture(
+        first: Rc<dyn Material>,
+        second: Rc<dyn Material>,
+        weight: Rc<dyn Texture>,
+    ) -> Self {
+        Self {
+            first,
+            second,
+            weight,
+        }
+    }
+
+    /// Get how much of the second material there is at the point of a hit, after handing the
+    /// weight of a material cut out there to the other one.
+    fn weight(&self, hit: &HitRecord) -> f64 {
+        let weight = color::luminance(self.weight.value(hit.u, hit.v, hit.point)).clamp(0.0, 1.0);
+        match (self.first.is_cut_out(hit), self.second.is_cut_out(hit)) {
+            (true, false) if weight > 0.0 => 1.0,
+            (false, true) if weight < 1.0 => 0.0,
+            _ => weight,
+        }
+    }
+}
+
+impl Material for Mix {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        let weight = self.weight(hit);
+        self.first.eval(hit, wo, wi) * (1.0 - weight) + self.second.eval(hit, wo, wi) * weight
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let weight = self.weight(hit);
+        let picked = if raytracing::random_f64() < weight {
+            &self.second
+        } else {
+            &self.first
+        };
+        let sample = picked.sample(hit, wo)?;
+
+        // The probability of picking a delta lobe cancels its weight in the blend, while any
+        // other direction could have come from either material.
+        if sample.is_delta {
+            return Some(sample);
+        }
+        Some(Sample {
+            value: self.eval(hit, wo, sample.direction),
+            pdf: self.pdf(hit, wo, sample.direction),
+            ..sample
+        })
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        let weight = self.weight(hit);
+        self.first.pdf(hit, wo, wi) * (1.0 - weight) + self.second.pdf(hit, wo, wi) * weight
+    }
+
+    fn is_delta(&self, hit: &HitRecord) -> bool {
+        let weight = self.weight(hit);
+        (weight >= 1.0 || self.first.is_delta(hit)) && (weight <= 0.0 || self.second.is_delta(hit))
+    }
+
+    fn is_dispersive(&self, hit: &HitRecord) -> bool {
+        self.first.is_dispersive(hit) || self.second.is_dispersive(hit)
+    }
+
+    fn is_cut_out(&self, hit: &HitRecord) -> bool {
+        let weight = self.weight(hit);
+        (weight >= 1.0 || self.first.is_cut_out(hit))
+            && (weight <= 0.0 || self.second.is_cut_out(hit))
+    }
+}
+
+/// A surface which looks different from either side, like a leaf or a playing card.
+///
+/// The front is the side the shape's normal points out of.
+pub struct TwoSided {
+    pub front: Rc<dyn Material>,
+    pub back: Rc<dyn Material>,
+}
+
+impl TwoSided {
+    /// Get the material for the side of the surface a hit is on.
+    fn side(&self, hit: &HitRecord) -> &dyn Material {
+        if hit.front_face {
+            self.front.as_ref()
+        } else {
+            self.back.as_ref()
+        }
+    }
+}
+
+impl Material for TwoSided {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        self.side(hit).eval(hit, wo, wi)
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        self.side(hit).sample(hit, wo)
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        self.side(hit).pdf(hit, wo, wi)
+    }
+
+    fn is_delta(&self, hit: &HitRecord) -> bool {
+        self.side(hit).is_delta(hit)
+    }
+
+    fn is_dispersive(&self, hit: &HitRecord) -> bool {
+        self.side(hit).is_dispersive(hit)
+    }
+
+    fn is_cut_out(&self, hit: &HitRecord) -> bool {
+        self.side(hit).is_cut_out(hit)
+    }
+}
+
+/// A phase function which scatters light equally in every direction.
+///
+/// This is meant for the particles making up a participating medium rather than for surfaces.
+pub struct Isotropic {
+    pub albedo: Rc<dyn Texture>,
+}
+
+impl Isotropic {
+    /// Create an isotropic phase function with a uniform albedo.
+    pub fn new(albedo: Color) -> Self {
+        Self {
+            albedo: Rc::new(SolidColor::new(albedo)),
+        }
+    }
+}
+
+impl Material for Isotropic {
+    fn eval(&self, hit: &ShadingContext, _wo: Vec3, _wi: Vec3) -> Color {
+        hit.color(self.albedo.value(hit.u, hit.v, hit.point)) / (4.0 * PI)
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let direction = Vec3::random_unit_vector();
+        Some(Sample {
+            direction,
+            value: self.eval(hit, wo, direction),
+            pdf: self.pdf(hit, wo, direction),
+            is_delta: false,
+            exit: None,
+        })
+    }
+
+    fn pdf(&self, _hit: &ShadingContext, _wo: Vec3, _wi: Vec3) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+}
+
+/// The Henyey-Greenstein phase function.
+///
+/// The asymmetry parameter `g`, between -1 and 1, is the average cosine of the angle light is
+/// scattered by. Positive values favor scattering forward, as in clouds and haze, negative values
+/// favor scattering backward, and zero scatters equally in every direction.
+pub struct HenyeyGreenstein {
+    pub albedo: Rc<dyn Texture>,
+    pub g: f64,
+}
+
+impl HenyeyGreenstein {
+    /// Create a Henyey-Greenstein phase function with a uniform albedo.
+    pub fn new(albedo: Color, g: f64) -> Self {
+        Self {
+            albedo: Rc::new(SolidColor::new(albedo)),
+            g: g.clamp(-0.99, 0.99),
+        }
+    }
+
+    /// Evaluate the phase function for light turned by an angle with the given cosine.
+    fn phase(&self, cos_theta: f64) -> f64 {
+        let g = self.g;
+        let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+        (1.0 - g * g) / (4.0 * PI * denominator * denominator.sqrt())
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        hit.color(self.albedo.value(hit.u, hit.v, hit.point)) * self.pdf(hit, wo, wi)
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        // Invert the cumulative distribution of the cosine of the scattering angle, measured from
+        // the direction the ray was already travelling in.
+        let g = self.g;
+        let xi = raytracing::random_f64();
+        let cos_theta = if g.abs() < 1e-3 {
+            1.0 - 2.0 * xi
+        } else {
+            let term = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+            (1.0 + g * g - term * term) / (2.0 * g)
+        };
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let (sin_phi, cos_phi) = (2.0 * PI * raytracing::random_f64()).sin_cos();
 
-        Some(Reflection {
-            ray: Ray::new(hit.point, reflected),
-            attenuation: self.albedo,
+        let forward = -wo;
+        let (tangent, bitangent) = math::orthonormal_basis(forward);
+        let direction = forward * cos_theta + (tangent * cos_phi + bitangent * sin_phi) * sin_theta;
+
+        Some(Sample {
+            direction,
+            value: self.eval(hit, wo, direction),
+            pdf: self.pdf(hit, wo, direction),
+            is_delta: false,
+            exit: None,
         })
     }
+
+    fn pdf(&self, _hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        self.phase(-wo.dot(wi))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::renderer::microfacet::Conductor;
+
+    /// Make a hit from the front of a surface facing up the z axis.
+    pub(crate) fn hit(material: Rc<dyn Material>) -> HitRecord {
+        HitRecord {
+            point: Point3::ZERO,
+            normal: Vec3::new(0.0, 0.0, 1.0),
+            u: 0.5,
+            v: 0.5,
+            dpdu: Vec3::new(1.0, 0.0, 0.0),
+            dpdv: Vec3::new(0.0, 1.0, 0.0),
+            material,
+            time: 1.0,
+            front_face: true,
+        }
+    }
+
+    /// Get the direction above the surface at the given angle in degrees from the normal.
+    pub(crate) fn direction(degrees: f64) -> Vec3 {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Vec3::new(sin, 0.0, cos)
+    }
+
+    /// Check that every direction the material samples outside of a delta lobe comes with the
+    /// same value and density as evaluating it.
+    pub(crate) fn assert_consistent(material: Rc<dyn Material>, wo: Vec3) {
+        let hit = hit(material.clone());
+        let hit = ShadingContext {
+            hit: &hit,
+            wavelengths: None,
+        };
+        for _ in 0..1000 {
+            let Some(sample) = material.sample(&hit, wo) else {
+                continue;
+            };
+            if sample.is_delta {
+                continue;
+            }
+            let value = material.eval(&hit, wo, sample.direction);
+            let pdf = material.pdf(&hit, wo, sample.direction);
+            assert!((sample.value - value).length() <= 1e-6 * value.length().max(1.0));
+            assert!((sample.pdf - pdf).abs() <= 1e-6 * pdf.max(1.0));
+        }
+    }
+
+    /// Estimate how much of the light arriving from every direction the material scatters
+    /// toward `wo`, which is one for a white surface that loses no energy.
+    pub(crate) fn albedo(material: Rc<dyn Material>, wo: Vec3) -> Color {
+        const SAMPLES: usize = 100_000;
+
+        let hit = hit(material.clone());
+        let hit = ShadingContext {
+            hit: &hit,
+            wavelengths: None,
+        };
+        let mut sum = Color::ZERO;
+        for _ in 0..SAMPLES {
+            if let Some(sample) = material.sample(&hit, wo)
+                && sample.pdf > 0.0
+            {
+                sum += sample.value / sample.pdf;
+            }
+        }
+        sum / SAMPLES as f64
+    }
+
+    /// Check that every channel of an albedo lies within the given range.
+    pub(crate) fn assert_albedo(albedo: Color, min: f64, max: f64) {
+        for channel in [albedo.x, albedo.y, albedo.z] {
+            assert!((min..=max).contains(&channel), "albedo {channel}");
+        }
+    }
+
+    #[test]
+    fn lambertian_samples_match_eval_and_pdf() {
+        let white = Rc::new(Lambertian {
+            albedo: Color::new(1.0, 1.0, 1.0),
+        });
+        assert_consistent(white, direction(30.0));
+    }
+
+    #[test]
+    fn white_lambertian_keeps_all_energy() {
+        let white = Rc::new(Lambertian {
+            albedo: Color::new(1.0, 1.0, 1.0),
+        });
+        for angle in [0.0, 45.0, 80.0] {
+            assert_albedo(albedo(white.clone(), direction(angle)), 0.999, 1.001);
+        }
+    }
+
+    #[test]
+    fn oren_nayar_samples_match_eval_and_pdf() {
+        let clay = Rc::new(OrenNayar::new(Color::new(0.8, 0.5, 0.3), 30.0));
+        for angle in [0.0, 45.0, 80.0] {
+            assert_consistent(clay.clone(), direction(angle));
+        }
+    }
+
+    #[test]
+    fn smooth_oren_nayar_is_lambertian() {
+        let white = Rc::new(OrenNayar::new(Color::new(1.0, 1.0, 1.0), 0.0));
+        for angle in [0.0, 45.0, 80.0] {
+            assert_albedo(albedo(white.clone(), direction(angle)), 0.999, 1.001);
+        }
+
+        // Rough surfaces leave out the light bouncing between facets, so they only lose energy.
+        let rough = Rc::new(OrenNayar::new(Color::new(1.0, 1.0, 1.0), 40.0));
+        assert_albedo(albedo(rough, direction(45.0)), 0.5, 1.001);
+    }
+
+    #[test]
+    fn mix_samples_match_eval_and_pdf() {
+        let paint = Rc::new(Lambertian {
+            albedo: Color::new(0.8, 0.2, 0.1),
+        });
+        for weight in [0.0, 0.3, 1.0] {
+            let mix = Rc::new(Mix::new(
+                paint.clone(),
+                Rc::new(Conductor::gold(0.3)),
+                weight,
+            ));
+            for angle in [0.0, 45.0, 80.0] {
+                assert_consistent(mix.clone(), direction(angle));
+            }
+        }
+    }
+
+    #[test]
+    fn mix_of_white_materials_keeps_all_energy() {
+        let white = Rc::new(Lambertian {
+            albedo: Color::new(1.0, 1.0, 1.0),
+        });
+        let mirror = Rc::new(Metal {
+            albedo: Color::new(1.0, 1.0, 1.0),
+        });
+        let mix = Rc::new(Mix::new(white, mirror, 0.3));
+        for angle in [0.0, 45.0, 80.0] {
+            assert_albedo(albedo(mix.clone(), direction(angle)), 0.99, 1.01);
+        }
+    }
+
+    #[test]
+    fn mix_is_cut_out_only_where_every_material_it_has_is() {
+        let opaque: Rc<dyn Material> = Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        });
+        let hole: Rc<dyn Material> = Rc::new(AlphaMask::new(
+            opaque.clone(),
+            Rc::new(SolidColor::new(Color::ZERO)),
+        ));
+        let cases = [
+            (hole.clone(), opaque.clone(), 0.0, true),
+            (hole.clone(), opaque.clone(), 0.5, false),
+            (hole.clone(), opaque.clone(), 1.0, false),
+            (hole.clone(), hole.clone(), 0.5, true),
+            (opaque.clone(), hole, 0.999, false),
+        ];
+        for (first, second, weight, cut_out) in cases {
+            let mix = Rc::new(Mix::new(first, second, weight));
+            let hit = hit(mix.clone());
+            for _ in 0..100 {
+                assert_eq!(mix.is_cut_out(&hit), cut_out, "weight {weight}");
+            }
+        }
+    }
+
+    #[test]
+    fn mix_gives_the_weight_of_a_cut_out_material_to_the_other() {
+        let white: Rc<dyn Material> = Rc::new(Lambertian {
+            albedo: Color::new(1.0, 1.0, 1.0),
+        });
+        let hole: Rc<dyn Material> = Rc::new(AlphaMask::new(
+            Rc::new(Lambertian {
+                albedo: Color::ZERO,
+            }),
+            Rc::new(SolidColor::new(Color::ZERO)),
+        ));
+        let cases = [
+            (white.clone(), hole.clone(), 0.5),
+            (hole.clone(), white.clone(), 0.5),
+            (hole, white, 0.999),
+        ];
+        for (first, second, weight) in cases {
+            let mix = Rc::new(Mix::new(first, second, weight));
+            assert_albedo(albedo(mix, direction(30.0)), 0.999, 1.001);
+        }
+    }
+
+    #[test]
+    fn two_sided_shades_each_side_with_its_own_material() {
+        let front = Rc::new(Lambertian {
+            albedo: Color::new(1.0, 0.0, 0.0),
+        });
+        let back = Rc::new(Lambertian {
+            albedo: Color::new(0.0, 0.0, 1.0),
+        });
+        let card = Rc::new(TwoSided { front, back });
+        assert_consistent(card.clone(), direction(45.0));
+
+        let mut hit = hit(card.clone());
+        for (front_face, expected) in [
+            (true, Color::new(1.0, 0.0, 0.0)),
+            (false, Color::new(0.0, 0.0, 1.0)),
+        ] {
+            hit.front_face = front_face;
+            let hit = ShadingContext {
+                hit: &hit,
+                wavelengths: None,
+            };
+            let sample = card.sample(&hit, direction(45.0)).unwrap();
+            assert!((sample.value / sample.pdf - expected).length() < 1e-6);
+        }
+    }
 }
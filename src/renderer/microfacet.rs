@@ -0,0 +1,616 @@
+use std::f64::consts::TAU;
+use std::rc::Rc;
+
+use raytracing::color::{self, Color};
+use raytracing::math::{self, Vec3};
+use raytracing::microfacet::{self, ThinFilm, TrowbridgeReitz};
+use raytracing::spectrum::{self, Dispersion};
+use raytracing::texture::Texture;
+
+use crate::hittable::HitRecord;
+use crate::renderer::material::{Material, Sample, ShadingContext};
+
+/// An orthonormal frame around the shading normal of a hit.
+///
+/// Microfacet models work in a local frame with the normal along the z axis.
+pub(crate) struct ShadingFrame {
+    pub tangent: Vec3,
+    pub bitangent: Vec3,
+    pub normal: Vec3,
+}
+
+impl ShadingFrame {
+    pub fn new(normal: Vec3) -> Self {
+        let (tangent, bitangent) = math::orthonormal_basis(normal);
+        Self {
+            tangent,
+            bitangent,
+            normal,
+        }
+    }
+
+    /// Create a frame whose tangent follows the direction of increasing `u` on the surface, which
+    /// anisotropic distributions line up with.
+    ///
+    /// The luminance of the rotation texture turns the tangent about the normal, with 1 being a
+    /// full turn. Where the surface has no tangent to follow, any will do.
+    pub fn oriented(hit: &HitRecord, rotation: Option<&dyn Texture>) -> Self {
+        let normal = hit.normal;
+        let along = hit.dpdu - normal * hit.dpdu.dot(normal);
+        if along.length_squared() < 1e-12 {
+            return Self::new(normal);
+        }
+
+        let tangent = along.unit_vector();
+        let bitangent = normal.cross(tangent);
+        let turn = rotation.map_or(0.0, |rotation| {
+            color::luminance(rotation.value(hit.u, hit.v, hit.point))
+        });
+        let (sin, cos) = (turn * TAU).sin_cos();
+        Self {
+            tangent: tangent * cos + bitangent * sin,
+            bitangent: bitangent * cos - tangent * sin,
+            normal,
+        }
+    }
+
+    pub fn to_local(&self, vector: Vec3) -> Vec3 {
+        Vec3::new(
+            vector.dot(self.tangent),
+            vector.dot(self.bitangent),
+            vector.dot(self.normal),
+        )
+    }
+
+    pub fn to_world(&self, vector: Vec3) -> Vec3 {
+        self.tangent * vector.x + self.bitangent * vector.y + self.normal * vector.z
+    }
+}
+
+/// Reflect a direction pointing away from the surface about a microfacet normal.
+pub(crate) fn reflect(wo: Vec3, m: Vec3) -> Vec3 {
+    m * (2.0 * wo.dot(m)) - wo
+}
+
+/// Refract a direction pointing away from the surface through a microfacet normal.
+///
+/// The refractive index is that of the other side relative to the side of `wo`. Returns nothing
+/// if the light is totally reflected instead.
+pub(crate) fn refract(wo: Vec3, m: Vec3, eta: f64) -> Option<Vec3> {
+    let cos_o = wo.dot(m);
+    let sin2_t = (1.0 - cos_o * cos_o) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return None;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(-wo / eta + m * (cos_o / eta - cos_t))
+}
+
+/// A lobe of light scattered by microfacets, evaluated for a pair of directions in the local
+/// frame.
+///
+/// The value is the scattering function times the cosine of `wi`, leaving out the Fresnel term,
+/// and the pdf is that of picking `wi` by sampling a visible normal and then reflecting or
+/// refracting about it, leaving out the probability of choosing one over the other.
+pub(crate) struct Lobe {
+    pub m: Vec3,
+    pub value: f64,
+    pub pdf: f64,
+}
+
+/// Evaluate reflection off the microfacets, if the directions make that possible.
+pub(crate) fn glossy_reflection(
+    distribution: &TrowbridgeReitz,
+    wo: Vec3,
+    wi: Vec3,
+) -> Option<Lobe> {
+    if wo.z <= 0.0 || wi.z <= 0.0 {
+        return None;
+    }
+    let m = (wo + wi).unit_vector();
+    let cos_o = wo.dot(m);
+    if cos_o <= 0.0 {
+        return None;
+    }
+
+    Some(Lobe {
+        m,
+        value: distribution.d(m) * distribution.g(wo, wi) / (4.0 * wo.z),
+        pdf: distribution.visible_normal_pdf(wo, m) / (4.0 * cos_o),
+    })
+}
+
+/// Evaluate refraction through the microfacets, if the directions make that possible.
+///
+/// The refractive index is that of the side of `wi` relative to the side of `wo`. Light is
+/// assumed to carry over unchanged, without the squeezing of radiance into the denser side.
+pub(crate) fn glossy_transmission(
+    distribution: &TrowbridgeReitz,
+    wo: Vec3,
+    wi: Vec3,
+    eta: f64,
+) -> Option<Lobe> {
+    if wo.z <= 0.0 || wi.z >= 0.0 {
+        return None;
+    }
+    let mut m = (wi * eta + wo).unit_vector();
+    if m.z < 0.0 {
+        m = -m;
+    }
+    let (cos_o, cos_i) = (wo.dot(m), wi.dot(m));
+    if cos_o <= 0.0 || cos_i >= 0.0 {
+        return None;
+    }
+
+    // The change of variables from the microfacet normal to the refracted direction.
+    let denominator = (cos_i + cos_o / eta).powi(2);
+    Some(Lobe {
+        m,
+        value: distribution.d(m) * distribution.g(wo, wi) * -cos_i * cos_o / (wo.z * denominator),
+        pdf: distribution.visible_normal_pdf(wo, m) * -cos_i / denominator,
+    })
+}
+
+/// A rough metal, modelled as microfacets following the GGX distribution.
+///
+/// The color of a metal comes entirely from its complex refractive index, which makes the
+/// reflection tinted head on and whiter toward grazing angles. A roughness of zero gives a
+/// perfect mirror. An optional thin film on top, like the oxide layer of anodized metal, adds
+/// colors of its own.
+///
+/// An anisotropic distribution makes brushed metal, with the x axis of the distribution along the
+/// surface tangent. The tangent can be turned by a rotation texture to change the direction of
+/// the grooves over the surface. Not every shape has a tangent worth following, as listed at
+/// [`HitRecord::dpdu`].
+pub struct Conductor {
+    pub eta: Color,
+    pub k: Color,
+    pub distribution: TrowbridgeReitz,
+    pub thin_film: Option<ThinFilm>,
+    pub tangent_rotation: Option<Rc<dyn Texture>>,
+}
+
+impl Conductor {
+    /// Create a conductor from its complex refractive index and its roughness.
+    pub fn new(eta: Color, k: Color, roughness: f64) -> Self {
+        Self {
+            eta,
+            k,
+            distribution: TrowbridgeReitz::isotropic(roughness),
+            thin_film: None,
+            tangent_rotation: None,
+        }
+    }
+
+    /// Get the fraction of light reflected by a microfacet seen at the given cosine.
+    ///
+    /// When rendering spectrally, a thin film is evaluated at each wavelength of the path, with
+    /// the refractive index of the metal taken from the spectra of its red, green and blue values.
+    fn fresnel(&self, hit: &ShadingContext, cos: f64) -> Color {
+        match (self.thin_film, hit.wavelengths) {
+            (Some(film), Some(wavelengths)) => wavelengths.map(|wavelength| {
+                let eta = spectrum::rgb_to_spectrum(self.eta, wavelength);
+                let k = spectrum::rgb_to_spectrum(self.k, wavelength);
+                film.reflectance(cos, wavelength, 1.0, eta, k)
+            }),
+            (Some(film), None) => film.fresnel(cos, 1.0, self.eta, self.k),
+            (None, _) => hit.color(microfacet::fresnel_conductor(cos, self.eta, self.k)),
+        }
+    }
+
+    /// Create a gold conductor.
+    pub fn gold(roughness: f64) -> Self {
+        Self::new(
+            Color::new(0.143, 0.374, 1.442),
+            Color::new(3.983, 2.385, 1.603),
+            roughness,
+        )
+    }
+
+    /// Create a copper conductor.
+    pub fn copper(roughness: f64) -> Self {
+        Self::new(
+            Color::new(0.200, 0.924, 1.102),
+            Color::new(3.912, 2.452, 2.142),
+            roughness,
+        )
+    }
+
+    /// Create an aluminum conductor.
+    pub fn aluminum(roughness: f64) -> Self {
+        Self::new(
+            Color::new(1.657, 0.880, 0.521),
+            Color::new(9.224, 6.270, 4.837),
+            roughness,
+        )
+    }
+}
+
+impl Material for Conductor {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        if self.distribution.is_smooth() {
+            return Color::ZERO;
+        }
+        let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+        let (wo, wi) = (frame.to_local(wo), frame.to_local(wi));
+        match glossy_reflection(&self.distribution, wo, wi) {
+            Some(lobe) => self.fresnel(hit, wo.dot(lobe.m)) * lobe.value,
+            None => Color::ZERO,
+        }
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+        let local = frame.to_local(wo);
+        if local.z <= 0.0 {
+            return None;
+        }
+
+        if self.distribution.is_smooth() {
+            return Some(Sample {
+                direction: frame.to_world(Vec3::new(-local.x, -local.y, local.z)),
+                value: self.fresnel(hit, local.z),
+                pdf: 1.0,
+                is_delta: true,
+                exit: None,
+            });
+        }
+
+        let m = self.distribution.sample_visible_normal(
+            local,
+            raytracing::random_f64(),
+            raytracing::random_f64(),
+        );
+        let wi = reflect(local, m);
+        if wi.z <= 0.0 {
+            return None;
+        }
+        let direction = frame.to_world(wi);
+        Some(Sample {
+            direction,
+            value: self.eval(hit, wo, direction),
+            pdf: self.pdf(hit, wo, direction),
+            is_delta: false,
+            exit: None,
+        })
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        if self.distribution.is_smooth() {
+            return 0.0;
+        }
+        let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+        glossy_reflection(&self.distribution, frame.to_local(wo), frame.to_local(wi))
+            .map_or(0.0, |lobe| lobe.pdf)
+    }
+
+    fn is_delta(&self, _hit: &HitRecord) -> bool {
+        self.distribution.is_smooth()
+    }
+}
+
+/// A rough glass-like material, modelled as microfacets following the GGX distribution.
+///
+/// Light is reflected or refracted by each microfacet according to the Fresnel equations. A
+/// roughness of zero gives clear glass, and higher roughness gives frosted glass. The refractive
+/// index is that of the inside relative to the outside.
+///
+/// An optional thin film on the outside makes the reflection iridescent. A soap bubble is a film
+/// on a dielectric with a refractive index of 1, which does not bend light at all.
+///
+/// With dispersion, the refractive index depends on the wavelength, which splits white light into
+/// its colors when rendering spectrally. Otherwise, the fixed refractive index is used.
+///
+/// Like for a [`Conductor`], an anisotropic distribution follows the rotated surface tangent.
+pub struct Dielectric {
+    pub refractive_index: f64,
+    pub distribution: TrowbridgeReitz,
+    pub thin_film: Option<ThinFilm>,
+    pub dispersion: Option<Dispersion>,
+    pub tangent_rotation: Option<Rc<dyn Texture>>,
+}
+
+impl Dielectric {
+    /// Create a dielectric from its refractive index and its roughness.
+    pub fn new(refractive_index: f64, roughness: f64) -> Self {
+        Self {
+            refractive_index,
+            distribution: TrowbridgeReitz::isotropic(roughness),
+            thin_film: None,
+            dispersion: None,
+            tangent_rotation: None,
+        }
+    }
+
+    /// Create a dispersive dielectric, whose fixed refractive index is the one at the d line.
+    pub fn dispersive(dispersion: Dispersion, roughness: f64) -> Self {
+        Self {
+            dispersion: Some(dispersion),
+            ..Self::new(dispersion.index(Dispersion::D_LINE), roughness)
+        }
+    }
+
+    /// Get the refractive index of the inside relative to the outside, at the hero wavelength
+    /// when rendering spectrally.
+    fn absolute_index(&self, hit: &ShadingContext) -> f64 {
+        self.index_at(hit.wavelength())
+    }
+
+    /// Get the refractive index of the inside relative to the outside at a wavelength, or the
+    /// fixed one without.
+    fn index_at(&self, wavelength: Option<f64>) -> f64 {
+        match (self.dispersion, wavelength) {
+            (Some(dispersion), Some(wavelength)) => dispersion.index(wavelength),
+            _ => self.refractive_index,
+        }
+    }
+
+    /// Get the fraction of light reflected by a microfacet seen at the given cosine, from the
+    /// side of the surface the hit is on.
+    ///
+    /// When rendering spectrally, a thin film is evaluated at each wavelength of the path.
+    fn fresnel(&self, hit: &ShadingContext, cos: f64) -> Color {
+        let sides = |index: f64| {
+            if hit.front_face {
+                (1.0, index)
+            } else {
+                (index, 1.0)
+            }
+        };
+        match (self.thin_film, hit.wavelengths) {
+            (Some(film), Some(wavelengths)) => wavelengths.map(|wavelength| {
+                let (outside, inside) = sides(self.index_at(Some(wavelength)));
+                film.reflectance(cos, wavelength, outside, inside, 0.0)
+            }),
+            (Some(film), None) => {
+                let (outside, inside) = sides(self.refractive_index);
+                film.fresnel(
+                    cos,
+                    outside,
+                    Color::new(inside, inside, inside),
+                    Color::ZERO,
+                )
+            }
+            (None, _) => {
+                let fresnel = microfacet::fresnel_dielectric(cos, self.relative_index(hit));
+                Color::new(fresnel, fresnel, fresnel)
+            }
+        }
+    }
+
+    /// Get the refractive index of the far side of the surface relative to the near one.
+    fn relative_index(&self, hit: &ShadingContext) -> f64 {
+        let index = self.absolute_index(hit);
+        if hit.front_face { index } else { 1.0 / index }
+    }
+}
+
+/// The average of the channels of a color, used as the probability of picking reflection.
+fn average(color: Color) -> f64 {
+    (color.x + color.y + color.z) / 3.0
+}
+
+impl Material for Dielectric {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        if self.distribution.is_smooth() {
+            return Color::ZERO;
+        }
+        let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+        let (wo, wi) = (frame.to_local(wo), frame.to_local(wi));
+        let eta = self.relative_index(hit);
+
+        if let Some(lobe) = glossy_reflection(&self.distribution, wo, wi) {
+            self.fresnel(hit, wo.dot(lobe.m)) * lobe.value
+        } else if let Some(lobe) = glossy_transmission(&self.distribution, wo, wi, eta) {
+            (Color::new(1.0, 1.0, 1.0) - self.fresnel(hit, wo.dot(lobe.m))) * lobe.value
+        } else {
+            Color::ZERO
+        }
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+        let local = frame.to_local(wo);
+        if local.z <= 0.0 {
+            return None;
+        }
+
+        let eta = self.relative_index(hit);
+        let m = self.distribution.sample_visible_normal(
+            local,
+            raytracing::random_f64(),
+            raytracing::random_f64(),
+        );
+
+        // Pick reflection or refraction in proportion to the Fresnel term, averaged over the
+        // channels in case a thin film colors it.
+        let fresnel = self.fresnel(hit, local.dot(m));
+        let reflectance = average(fresnel);
+        let (wi, weight) = if raytracing::random_f64() < reflectance {
+            let wi = reflect(local, m);
+            if wi.z <= 0.0 {
+                return None;
+            }
+            (wi, fresnel / reflectance)
+        } else {
+            let wi = refract(local, m, eta)?;
+            if wi.z >= 0.0 {
+                return None;
+            }
+            (
+                wi,
+                (Color::new(1.0, 1.0, 1.0) - fresnel) / (1.0 - reflectance),
+            )
+        };
+
+        // For clear glass, the Fresnel term cancels out, but for the tint of a thin film.
+        let direction = frame.to_world(wi);
+        if self.distribution.is_smooth() {
+            return Some(Sample {
+                direction,
+                value: weight,
+                pdf: 1.0,
+                is_delta: true,
+                exit: None,
+            });
+        }
+        Some(Sample {
+            direction,
+            value: self.eval(hit, wo, direction),
+            pdf: self.pdf(hit, wo, direction),
+            is_delta: false,
+            exit: None,
+        })
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        if self.distribution.is_smooth() {
+            return 0.0;
+        }
+        let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+        let (wo, wi) = (frame.to_local(wo), frame.to_local(wi));
+        let eta = self.relative_index(hit);
+
+        if let Some(lobe) = glossy_reflection(&self.distribution, wo, wi) {
+            average(self.fresnel(hit, wo.dot(lobe.m))) * lobe.pdf
+        } else if let Some(lobe) = glossy_transmission(&self.distribution, wo, wi, eta) {
+            (1.0 - average(self.fresnel(hit, wo.dot(lobe.m)))) * lobe.pdf
+        } else {
+            0.0
+        }
+    }
+
+    fn is_delta(&self, _hit: &HitRecord) -> bool {
+        self.distribution.is_smooth()
+    }
+
+    fn is_dispersive(&self, _hit: &HitRecord) -> bool {
+        self.dispersion.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use raytracing::microfacet::ThinFilm;
+    use raytracing::spectrum::{Dispersion, MAX_WAVELENGTH, MIN_WAVELENGTH, Wavelengths};
+
+    use crate::renderer::material::tests::{
+        albedo, assert_albedo, assert_consistent, direction, hit,
+    };
+
+    /// A conductor which reflects almost all light, for checking how much the microfacets lose.
+    fn mirror(roughness: f64) -> Conductor {
+        Conductor::new(
+            Color::new(1.0, 1.0, 1.0),
+            Color::new(1e3, 1e3, 1e3),
+            roughness,
+        )
+    }
+
+    /// A film thick enough to color the reflection strongly.
+    fn film() -> ThinFilm {
+        ThinFilm {
+            thickness: 300.0,
+            refractive_index: 2.4,
+        }
+    }
+
+    #[test]
+    fn samples_match_eval_and_pdf() {
+        let materials: [Rc<dyn Material>; 6] = [
+            Rc::new(Conductor::gold(0.3)),
+            Rc::new(Conductor {
+                distribution: TrowbridgeReitz::anisotropic(0.1, 0.4),
+                ..Conductor::aluminum(0.0)
+            }),
+            Rc::new(Conductor {
+                thin_film: Some(film()),
+                ..Conductor::aluminum(0.3)
+            }),
+            Rc::new(Dielectric::new(1.5, 0.3)),
+            Rc::new(Dielectric::new(1.5, 0.05)),
+            Rc::new(Dielectric {
+                thin_film: Some(film()),
+                ..Dielectric::new(1.5, 0.3)
+            }),
+        ];
+        for material in materials {
+            for angle in [0.0, 45.0, 80.0] {
+                assert_consistent(material.clone(), direction(angle));
+            }
+        }
+    }
+
+    #[test]
+    fn white_conductor_keeps_nearly_all_energy() {
+        assert_albedo(albedo(Rc::new(mirror(0.0)), direction(30.0)), 0.999, 1.0);
+        // Light bouncing between microfacets more than once is left out, which loses a little.
+        assert_albedo(albedo(Rc::new(mirror(0.3)), direction(30.0)), 0.9, 1.01);
+    }
+
+    #[test]
+    fn dielectric_keeps_nearly_all_energy() {
+        let clear = Rc::new(Dielectric::new(1.5, 0.0));
+        assert_albedo(albedo(clear, direction(30.0)), 0.999, 1.001);
+        let frosted = Rc::new(Dielectric::new(1.5, 0.3));
+        assert_albedo(albedo(frosted, direction(30.0)), 0.9, 1.01);
+    }
+
+    #[test]
+    fn thin_film_colors_the_reflection() {
+        let plain = Dielectric::new(1.5, 0.0);
+        let filmed = Dielectric {
+            thin_film: Some(film()),
+            ..Dielectric::new(1.5, 0.0)
+        };
+        let hit = hit(Rc::new(Dielectric::new(1.5, 0.0)));
+        let spread = |color: Color| {
+            let channels = [color.x, color.y, color.z];
+            channels.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+                - channels.iter().copied().fold(f64::INFINITY, f64::min)
+        };
+
+        for wavelengths in [None, Some(Wavelengths::sample(0.3))] {
+            let hit = ShadingContext {
+                hit: &hit,
+                wavelengths,
+            };
+            assert!(spread(plain.fresnel(&hit, 1.0)) < 1e-12);
+            assert!(spread(filmed.fresnel(&hit, 1.0)) > 0.1);
+        }
+    }
+
+    #[test]
+    fn dispersion_refracts_blue_further_than_red() {
+        let prism = Rc::new(Dielectric::dispersive(Dispersion::DENSE_FLINT, 0.0));
+        let hit = hit(prism.clone());
+        let wo = direction(60.0);
+
+        // Get how far along the surface light of the hero wavelength leaves, once it refracts.
+        let refracted = |wavelength: f64| {
+            let hit = ShadingContext {
+                hit: &hit,
+                wavelengths: Some(Wavelengths::sample(
+                    (wavelength - MIN_WAVELENGTH) / (MAX_WAVELENGTH - MIN_WAVELENGTH),
+                )),
+            };
+            assert!(prism.is_dispersive(&hit));
+            loop {
+                let sample = prism.sample(&hit, wo).unwrap();
+                if sample.direction.z < 0.0 {
+                    return sample.direction.x;
+                }
+            }
+        };
+
+        // The refracted light heads away from where it came in, and blue bends toward the normal
+        // more.
+        let (blue, red) = (refracted(450.0), refracted(650.0));
+        assert!(blue < 0.0 && red < 0.0);
+        assert!(
+            blue.abs() < red.abs() - 0.01,
+            "{blue} for blue, {red} for red"
+        );
+    }
+}
@@ -1,14 +1,23 @@
+use raytracing::atmosphere::Atmosphere;
 use raytracing::camera::Camera;
 use raytracing::color::Color;
 use raytracing::image::Image;
 use raytracing::math::Vec3;
 use raytracing::math::interval::Interval;
 use raytracing::math::ray::Ray;
+use raytracing::spectrum::Wavelengths;
 use raytracing::ui;
 
 use crate::hittable::Hittable;
+use crate::renderer::material::ShadingContext;
 
+pub mod coated;
+pub mod hair;
 pub mod material;
+pub mod microfacet;
+pub mod normal_map;
+pub mod principled;
+pub mod subsurface;
 
 /// Get a random vector in the `[-0.5, 0.5]^2` product space.
 fn sample_square() -> Vec3 {
@@ -35,35 +44,121 @@ fn get_ray(
     Ray::new(eye, pixel_center - eye)
 }
 
-fn get_pixel_color(ray: Ray, depth: i32, scene: &dyn Hittable) -> Color {
+/// Get the color of the sky in the given direction.
+fn sky(direction: Vec3) -> Color {
+    let intensity = (direction.unit_vector().y + 1.0) * 0.5;
+    Color::new(1.0, 1.0, 1.0) * (1.0 - intensity) + Color::new(0.5, 0.7, 1.0) * intensity
+}
+
+/// The density of picking any one direction toward the sky, which is sampled uniformly.
+const SKY_PDF: f64 = 1.0 / (4.0 * std::f64::consts::PI);
+
+/// Weigh a sample by the power heuristic, given its own density and that of the other strategy
+/// which could have produced the same direction.
+fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    if a + b > 0.0 { a / (a + b) } else { 0.0 }
+}
+
+/// Trace a ray into the scene and get the light arriving back along it.
+///
+/// Light from the sky reaches every hit both by sampling the sky directly and by following the
+/// direction the material samples, and the two are combined with multiple importance sampling.
+/// The density the material picked the ray with is given for the second of these, and is
+/// missing for rays from the camera and from delta lobes, which the sky is never sampled for.
+///
+/// When rendering spectrally, the path carries the given wavelengths, and the channels of the
+/// result hold the light at each of them. The sky and the atmosphere are upsampled to spectra here,
+/// while materials give their values at the wavelengths themselves.
+fn get_pixel_color(
+    ray: Ray,
+    depth: i32,
+    scene: &dyn Hittable,
+    atmosphere: Option<&Atmosphere>,
+    material_pdf: Option<f64>,
+    mut wavelengths: Option<Wavelengths>,
+) -> Color {
     const EPSILON: f64 = 0.001;
 
     if depth <= 0 {
         return Color::ZERO;
     }
 
-    if let Some(hit) = scene.hit(ray, Interval::new(EPSILON, f64::INFINITY)) {
-        return if let Some(scattered) = hit.clone().material.scatter(ray, hit) {
-            let next_color = get_pixel_color(scattered.ray, depth - 1, scene);
-
-            Color::new(
-                scattered.attenuation.x * next_color.x,
-                scattered.attenuation.y * next_color.y,
-                scattered.attenuation.z * next_color.z,
-            )
-        } else {
-            Color::ZERO
-        };
+    let to_path = move |color: Color| match wavelengths {
+        Some(wavelengths) => wavelengths.upsample(color),
+        None => color,
+    };
+
+    // Whatever a ray ends up seeing, it sees through the atmosphere.
+    let through_atmosphere = move |ray: Ray, time: f64, color: Color| match atmosphere {
+        Some(atmosphere) => atmosphere.apply(ray, time, color, wavelengths),
+        None => color,
+    };
+    let multiply = |a: Color, b: Color| Color::new(a.x * b.x, a.y * b.y, a.z * b.z);
+
+    let Some(hit) = scene.hit(ray, Interval::new(EPSILON, f64::INFINITY)) else {
+        let weight = material_pdf.map_or(1.0, |pdf| power_heuristic(pdf, SKY_PDF));
+        return through_atmosphere(ray, f64::INFINITY, to_path(sky(ray.direction))) * weight;
+    };
+    let shading = ShadingContext {
+        hit: &hit,
+        wavelengths,
+    };
+
+    let wo = -ray.direction.unit_vector();
+    let material = hit.material.clone();
+    let dispersed = match &mut wavelengths {
+        Some(wavelengths) if !wavelengths.secondary_terminated && material.is_dispersive(&hit) => {
+            wavelengths.terminate_secondary();
+            true
+        }
+        _ => false,
+    };
+    let mut color = Color::ZERO;
+
+    if !material.is_delta(&hit) {
+        let wi = Vec3::random_unit_vector();
+        let value = material.eval(&shading, wo, wi);
+        if !value.is_near_zero() {
+            let shadow = Ray::new(hit.point, wi);
+            if scene
+                .hit(shadow, Interval::new(EPSILON, f64::INFINITY))
+                .is_none()
+            {
+                let light = through_atmosphere(shadow, f64::INFINITY, to_path(sky(wi)));
+                let weight = power_heuristic(SKY_PDF, material.pdf(&shading, wo, wi));
+                color += multiply(value, light) * (weight / SKY_PDF);
+            }
+        }
     }
 
-    let direction = ray.direction.unit_vector();
-    let intensity = (direction.y + 1.0) * 0.5;
-    Color::new(1.0, 1.0, 1.0) * (1.0 - intensity) + Color::new(0.5, 0.7, 1.0) * intensity
+    if let Some(sample) = material.sample(&shading, wo)
+        && sample.pdf > 0.0
+    {
+        let scattered = Ray::new(sample.exit.unwrap_or(hit.point), sample.direction);
+        let pdf = (!sample.is_delta).then_some(sample.pdf);
+        let next_color = get_pixel_color(scattered, depth - 1, scene, atmosphere, pdf, wavelengths);
+        color += multiply(sample.value / sample.pdf, next_color);
+    }
+
+    if dispersed {
+        color = Wavelengths::hero_only(color);
+    }
+    through_atmosphere(ray, hit.time, color)
 }
 
 /// Render the scene to an image.
+///
+/// If an atmosphere is given, it fills all of the space around the objects in the scene. When
+/// rendering spectrally, each sample traces light at a few random wavelengths instead of in red,
+/// green and blue, which is slower and noisier but lets materials disperse light into colors.
 #[must_use]
-pub fn render_scene<I>(camera: Camera, scene: &dyn Hittable) -> I
+pub fn render_scene<I>(
+    camera: Camera,
+    scene: &dyn Hittable,
+    atmosphere: Option<&Atmosphere>,
+    spectral: bool,
+) -> I
 where
     I: Image,
 {
@@ -101,7 +196,15 @@ where
                     start_pos,
                     camera.position,
                 );
-                color += get_pixel_color(ray, raytracing::MAX_DEPTH, scene);
+                let depth = raytracing::MAX_DEPTH;
+                color += if spectral {
+                    let wavelengths = Wavelengths::sample(raytracing::random_f64());
+                    let values =
+                        get_pixel_color(ray, depth, scene, atmosphere, None, Some(wavelengths));
+                    wavelengths.to_rgb(values)
+                } else {
+                    get_pixel_color(ray, depth, scene, atmosphere, None, None)
+                };
             }
 
             image.set_pixel(x, y, color * pixel_samples_scale);
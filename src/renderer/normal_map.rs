@@ -0,0 +1,162 @@
+use std::rc::Rc;
+
+use raytracing::color::{self, Color};
+use raytracing::math::Vec3;
+use raytracing::texture::Texture;
+
+use crate::hittable::HitRecord;
+use crate::renderer::material::{Material, Sample, ShadingContext};
+
+/// A material whose shading normal is read from a tangent space normal map.
+///
+/// The red, green and blue channels of the map hold the components of the normal along `dpdu`,
+/// `dpdv` and the surface normal, remapped from `[-1, 1]` to `[0, 1]`. The perturbed normal is
+/// handed to the base material in place of the geometric one.
+pub struct NormalMap {
+    pub base: Rc<dyn Material>,
+    pub map: Rc<dyn Texture>,
+
+    /// How far the map is allowed to tilt the normal. A strength of 1 uses the map as is, while 0
+    /// leaves the surface flat.
+    pub strength: f64,
+}
+
+impl NormalMap {
+    /// Wrap a material with a normal map at full strength.
+    pub fn new(base: Rc<dyn Material>, map: Rc<dyn Texture>) -> Self {
+        Self {
+            base,
+            map,
+            strength: 1.0,
+        }
+    }
+
+    /// Get the hit with its normal replaced by the one from the map.
+    fn perturb(&self, hit: &HitRecord) -> HitRecord {
+        let mut hit = hit.clone();
+        let encoded = self.map.value(hit.u, hit.v, hit.point);
+        let local = Vec3::new(
+            (2.0 * encoded.x - 1.0) * self.strength,
+            (2.0 * encoded.y - 1.0) * self.strength,
+            2.0 * encoded.z - 1.0,
+        );
+
+        // The tangent is made perpendicular to the normal with a Gram-Schmidt step, since the
+        // surface derivatives are not guaranteed to be orthogonal to it.
+        let normal = hit.normal;
+        let tangent = hit.dpdu - normal * normal.dot(hit.dpdu);
+        if !local.is_near_zero() && !tangent.is_near_zero() {
+            let tangent = tangent.unit_vector();
+            let bitangent = normal.cross(tangent);
+            let perturbed = tangent * local.x + bitangent * local.y + normal * local.z;
+
+            // A map can tilt the normal past the horizon, in which case it is not usable.
+            if perturbed.dot(normal) > 0.0 {
+                hit.normal = perturbed.unit_vector();
+            }
+        }
+
+        hit
+    }
+}
+
+impl Material for NormalMap {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        self.base.eval(&hit.with_hit(&self.perturb(hit)), wo, wi)
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        self.base.sample(&hit.with_hit(&self.perturb(hit)), wo)
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        self.base.pdf(&hit.with_hit(&self.perturb(hit)), wo, wi)
+    }
+
+    fn is_delta(&self, hit: &HitRecord) -> bool {
+        self.base.is_delta(&self.perturb(hit))
+    }
+
+    fn is_dispersive(&self, hit: &HitRecord) -> bool {
+        self.base.is_dispersive(hit)
+    }
+
+    fn is_cut_out(&self, hit: &HitRecord) -> bool {
+        self.base.is_cut_out(hit)
+    }
+}
+
+/// A material whose shading normal is perturbed by a scalar height map.
+///
+/// The surface is treated as if it were displaced along its normal by the luminance of the height
+/// texture times the scale, and the normal of the displaced surface is handed to the base
+/// material. The geometry itself is left untouched.
+pub struct BumpMap {
+    pub base: Rc<dyn Material>,
+    pub height: Rc<dyn Texture>,
+    pub scale: f64,
+}
+
+impl BumpMap {
+    /// The step in surface coordinates used to take finite differences of the height map.
+    const DELTA: f64 = 0.0005;
+
+    /// Sample the height of the displaced surface at an offset from the hit.
+    fn height_at(&self, hit: &HitRecord, du: f64, dv: f64) -> f64 {
+        let point = hit.point + hit.dpdu * du + hit.dpdv * dv;
+        let value = self.height.value(hit.u + du, hit.v + dv, point);
+        color::luminance(value) * self.scale
+    }
+
+    /// Get the hit with its normal replaced by that of the displaced surface.
+    fn perturb(&self, hit: &HitRecord) -> HitRecord {
+        let mut hit = hit.clone();
+        let height = self.height_at(&hit, 0.0, 0.0);
+        let dhdu = (self.height_at(&hit, Self::DELTA, 0.0) - height) / Self::DELTA;
+        let dhdv = (self.height_at(&hit, 0.0, Self::DELTA) - height) / Self::DELTA;
+
+        // The change in the normal along the surface is ignored, which is fine as long as the
+        // bumps are small compared to the curvature of the surface.
+        let normal = hit.normal;
+        let dpdu = hit.dpdu + normal * dhdu;
+        let dpdv = hit.dpdv + normal * dhdv;
+        let perturbed = dpdu.cross(dpdv);
+
+        if !perturbed.is_near_zero() {
+            let perturbed = perturbed.unit_vector();
+            hit.normal = if perturbed.dot(normal) < 0.0 {
+                -perturbed
+            } else {
+                perturbed
+            };
+        }
+
+        hit
+    }
+}
+
+impl Material for BumpMap {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        self.base.eval(&hit.with_hit(&self.perturb(hit)), wo, wi)
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        self.base.sample(&hit.with_hit(&self.perturb(hit)), wo)
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        self.base.pdf(&hit.with_hit(&self.perturb(hit)), wo, wi)
+    }
+
+    fn is_delta(&self, hit: &HitRecord) -> bool {
+        self.base.is_delta(&self.perturb(hit))
+    }
+
+    fn is_dispersive(&self, hit: &HitRecord) -> bool {
+        self.base.is_dispersive(hit)
+    }
+
+    fn is_cut_out(&self, hit: &HitRecord) -> bool {
+        self.base.is_cut_out(hit)
+    }
+}
@@ -0,0 +1,654 @@
+use std::f64::consts::PI;
+use std::path::Path;
+use std::rc::Rc;
+
+use raytracing::color::{self, Color};
+use raytracing::gltf::GltfMaterial;
+use raytracing::math::Vec3;
+use raytracing::microfacet::{self, TrowbridgeReitz};
+use raytracing::mtl::MtlMaterial;
+use raytracing::texture::{Channel, ImageTexture, Multiply, SolidColor, Texture};
+
+use crate::hittable::HitRecord;
+use crate::renderer::material::{Material, Sample, ShadingContext, random_cosine_direction};
+use crate::renderer::microfacet::{
+    ShadingFrame, glossy_reflection, glossy_transmission, reflect, refract,
+};
+
+/// The refractive index of the clearcoat layer.
+const CLEARCOAT_INDEX: f64 = 1.5;
+
+/// The reflectance head on of a dielectric with a specular value of 1.
+const MAX_SPECULAR: f64 = 0.08;
+
+/// The lowest clearcoat roughness used, which keeps the varnish wide enough to be evaluated.
+const MIN_CLEARCOAT_ROUGHNESS: f64 = 0.05;
+
+/// A single material covering most real surfaces, after the Disney principled model.
+///
+/// A surface is blended from a handful of layers, each controlled by a parameter between 0 and 1:
+///
+/// - `metallic` turns the surface into a metal tinted by `base_color`.
+/// - `roughness` spreads out the reflections of the metal, the specular layer and the
+///   transmission. At zero these are perfect mirrors and clear glass, as for a
+///   [`Dielectric`](crate::renderer::microfacet::Dielectric).
+/// - `anisotropic` makes the surface rougher across its tangent than along it, like brushed
+///   metal with grooves along the tangent. The tangent can be turned by `tangent_rotation`, as
+///   for a [`Conductor`](crate::renderer::microfacet::Conductor).
+/// - `specular` sets the strength of the reflection off non-metals, with 0.5 matching a
+///   refractive index of 1.5.
+/// - `sheen` adds a soft rim of the given color at grazing angles, as seen on cloth.
+/// - `clearcoat` adds a colorless varnish on top, with its own `clearcoat_roughness`. The
+///   varnish is never perfectly smooth, as very low values are raised to a small minimum.
+/// - `transmission` turns the surface into glass tinted by `base_color`, with the given
+///   `refractive_index`.
+/// - `subsurface` flattens the diffuse layer, as light scattering under the surface does. This
+///   is only an approximation, and no light actually travels below the surface.
+///
+/// Every parameter is a texture, so it can vary over the surface. Scalar parameters are read from
+/// the luminance of their texture.
+pub struct Principled {
+    pub base_color: Rc<dyn Texture>,
+    pub metallic: Rc<dyn Texture>,
+    pub roughness: Rc<dyn Texture>,
+    pub anisotropic: Rc<dyn Texture>,
+    pub tangent_rotation: Option<Rc<dyn Texture>>,
+    pub specular: Rc<dyn Texture>,
+    pub sheen: Rc<dyn Texture>,
+    pub clearcoat: Rc<dyn Texture>,
+    pub clearcoat_roughness: Rc<dyn Texture>,
+    pub transmission: Rc<dyn Texture>,
+    pub subsurface: Rc<dyn Texture>,
+    pub refractive_index: f64,
+}
+
+/// Make a texture of a constant gray value.
+fn gray(value: f64) -> Rc<dyn Texture> {
+    Rc::new(SolidColor::new(Color::new(value, value, value)))
+}
+
+/// Multiply an optional texture by a factor, like material formats do.
+fn scaled(factor: Color, texture: Option<Rc<dyn Texture>>) -> Rc<dyn Texture> {
+    let factor: Rc<dyn Texture> = Rc::new(SolidColor::new(factor));
+    match texture {
+        Some(texture) => Rc::new(Multiply {
+            left: factor,
+            right: texture,
+        }),
+        None => factor,
+    }
+}
+
+/// Multiply a single channel of an optional texture by a factor.
+fn scaled_channel(
+    factor: f64,
+    texture: Option<Rc<dyn Texture>>,
+    channel: usize,
+) -> Rc<dyn Texture> {
+    scaled(
+        Color::new(factor, factor, factor),
+        texture.map(|texture| Rc::new(Channel { texture, channel }) as Rc<dyn Texture>),
+    )
+}
+
+/// Read a scalar parameter from the luminance of its texture at a hit.
+fn scalar(texture: &Rc<dyn Texture>, hit: &HitRecord) -> f64 {
+    color::luminance(texture.value(hit.u, hit.v, hit.point)).clamp(0.0, 1.0)
+}
+
+/// Get the Schlick weight, which approximates how the Fresnel term grows toward grazing angles.
+fn schlick_weight(cos: f64) -> f64 {
+    (1.0 - cos.clamp(0.0, 1.0)).powi(5)
+}
+
+impl Principled {
+    /// Create a plain dielectric principled material of the given color.
+    ///
+    /// The surface is non-metallic with medium roughness and the specular reflection of glass,
+    /// and has no sheen, clearcoat, transmission or subsurface scattering.
+    pub fn new(base_color: Color) -> Self {
+        Self {
+            base_color: Rc::new(SolidColor::new(base_color)),
+            metallic: gray(0.0),
+            roughness: gray(0.5),
+            anisotropic: gray(0.0),
+            tangent_rotation: None,
+            specular: gray(0.5),
+            sheen: gray(0.0),
+            clearcoat: gray(0.0),
+            clearcoat_roughness: gray(0.05),
+            transmission: gray(0.0),
+            subsurface: gray(0.0),
+            refractive_index: 1.5,
+        }
+    }
+
+    /// Create a principled material from an MTL material.
+    ///
+    /// The physically based extension is used where present. Otherwise, the roughness is derived
+    /// from the Phong exponent, and transmission from how transparent the material is. Texture
+    /// maps are loaded relative to the given directory, and fail the whole material if they cannot
+    /// be opened.
+    ///
+    /// The principled model tints both the diffuse layer and the transmission with the base
+    /// color, so the diffuse color and the transmission filter (`Tf`) are blended by how
+    /// transparent the material is. The strength of the specular reflection follows from the
+    /// refractive index alone, so the specular color (`Ks`) is not used, and neither is the
+    /// emission (`Ke`), as nothing in the renderer gives off light.
+    pub fn from_mtl<P: AsRef<Path>>(material: &MtlMaterial, directory: P) -> std::io::Result<Self> {
+        let directory = directory.as_ref();
+        let open = |path: &Option<String>, linear: bool| -> std::io::Result<_> {
+            path.as_ref()
+                .map(|path| {
+                    let path = directory.join(path);
+                    let texture = if linear {
+                        ImageTexture::open_linear(path)?
+                    } else {
+                        ImageTexture::open(path)?
+                    };
+                    Ok(Rc::new(texture) as Rc<dyn Texture>)
+                })
+                .transpose()
+        };
+        let gray_factor = |value: f64| Color::new(value, value, value);
+
+        // The usual conversion from a Phong exponent to a microfacet width, which is the square
+        // of the roughness.
+        let roughness = material.roughness.unwrap_or_else(|| {
+            let shininess = material.shininess.unwrap_or(10.0).max(0.0);
+            (2.0 / (shininess + 2.0)).powf(0.25)
+        });
+        let refractive_index = material.refractive_index.unwrap_or(1.5).max(1.0);
+        let reflectance = ((refractive_index - 1.0) / (refractive_index + 1.0)).powi(2);
+        let transmission = 1.0 - material.dissolve.unwrap_or(1.0).clamp(0.0, 1.0);
+        let diffuse = material.diffuse.unwrap_or(Color::new(0.8, 0.8, 0.8));
+        let filter = material.transmission_filter.unwrap_or(diffuse);
+
+        Ok(Self {
+            base_color: scaled(
+                diffuse * (1.0 - transmission) + filter * transmission,
+                open(&material.diffuse_map, false)?,
+            ),
+            metallic: scaled(
+                gray_factor(material.metallic.unwrap_or(0.0)),
+                open(&material.metallic_map, true)?,
+            ),
+            roughness: scaled(gray_factor(roughness), open(&material.roughness_map, true)?),
+            anisotropic: gray(0.0),
+            tangent_rotation: None,
+            specular: gray((reflectance / MAX_SPECULAR).min(1.0)),
+            sheen: scaled(
+                gray_factor(material.sheen.unwrap_or(0.0)),
+                open(&material.sheen_map, true)?,
+            ),
+            clearcoat: gray(material.clearcoat.unwrap_or(0.0)),
+            clearcoat_roughness: gray(material.clearcoat_roughness.unwrap_or(0.05)),
+            transmission: gray(transmission),
+            subsurface: gray(0.0),
+            refractive_index,
+        })
+    }
+
+    /// Create a principled material from a glTF material.
+    ///
+    /// glTF has no subsurface parameter, so that is left at zero. The refractive index of glTF is
+    /// a single factor, and so is that of the material, while every other parameter can be
+    /// textured.
+    pub fn from_gltf(material: &GltfMaterial) -> Self {
+        let material = material.clone();
+        let ior = material.ior.max(1.0);
+        let reflectance = ((ior - 1.0) / (ior + 1.0)).powi(2);
+
+        Self {
+            base_color: scaled(material.base_color_factor, material.base_color_texture),
+            metallic: scaled_channel(
+                material.metallic_factor,
+                material.metallic_roughness_texture.clone(),
+                2,
+            ),
+            roughness: scaled_channel(
+                material.roughness_factor,
+                material.metallic_roughness_texture,
+                1,
+            ),
+            anisotropic: gray(0.0),
+            tangent_rotation: None,
+            specular: scaled_channel(
+                (material.specular_factor * reflectance / MAX_SPECULAR).min(1.0),
+                material.specular_alpha_texture,
+                0,
+            ),
+            sheen: scaled(material.sheen_color_factor, material.sheen_color_texture),
+            clearcoat: scaled_channel(material.clearcoat_factor, material.clearcoat_texture, 0),
+            clearcoat_roughness: scaled_channel(
+                material.clearcoat_roughness_factor,
+                material.clearcoat_roughness_texture,
+                1,
+            ),
+            transmission: scaled_channel(
+                material.transmission_factor,
+                material.transmission_texture,
+                0,
+            ),
+            subsurface: gray(0.0),
+            refractive_index: ior,
+        }
+    }
+}
+
+/// The parameters of a [`Principled`] material at one point, with everything set up for
+/// evaluating it in the local shading frame.
+struct Parameters {
+    base_color: Color,
+    roughness: f64,
+    specular: f64,
+    sheen: Color,
+    subsurface: f64,
+    eta: f64,
+    distribution: TrowbridgeReitz,
+    clearcoat_distribution: TrowbridgeReitz,
+
+    /// The clearcoat parameter itself, which the reflection off the clearcoat is scaled by along
+    /// with its own Fresnel term.
+    clearcoat_strength: f64,
+
+    /// The probabilities of picking the clearcoat, metal and transmission layers, each counted
+    /// among whatever the layers before it left over.
+    clearcoat: f64,
+    metallic: f64,
+    transmission: f64,
+}
+
+impl Parameters {
+    /// Get the probability of picking the specular lobe of the dielectric base.
+    fn specular_probability(&self, wo: Vec3) -> f64 {
+        self.schlick(wo.z)
+    }
+
+    /// Get Schlick's approximation of the reflectance of the dielectric base.
+    fn schlick(&self, cos: f64) -> f64 {
+        let reflectance = MAX_SPECULAR * self.specular;
+        reflectance + (1.0 - reflectance) * schlick_weight(cos)
+    }
+
+    /// Get the reflectance of the metal, which is tinted head on and turns white toward grazing
+    /// angles.
+    fn metal(&self, cos: f64) -> Color {
+        let white = Color::new(1.0, 1.0, 1.0);
+        self.base_color + (white - self.base_color) * schlick_weight(cos)
+    }
+
+    /// Evaluate the diffuse lobes for a pair of directions above the surface.
+    fn diffuse(&self, wo: Vec3, wi: Vec3) -> Color {
+        let cos_d = wi.dot((wi + wo).unit_vector());
+        let (fo, fi) = (schlick_weight(wo.z), schlick_weight(wi.z));
+
+        // The retro-reflection of the Disney diffuse lobe, and Hanrahan and Krueger's flattened
+        // lobe for subsurface scattering, which are blended by the subsurface parameter.
+        let retro = 0.5 + 2.0 * self.roughness * cos_d * cos_d;
+        let diffuse = (1.0 + (retro - 1.0) * fo) * (1.0 + (retro - 1.0) * fi);
+        let flat = self.roughness * cos_d * cos_d;
+        let flattened = (1.0 + (flat - 1.0) * fo) * (1.0 + (flat - 1.0) * fi);
+        let subsurface = 1.25 * (flattened * (1.0 / (wo.z + wi.z) - 0.5) + 0.5);
+        let lobe = diffuse + (subsurface - diffuse) * self.subsurface;
+
+        (self.base_color * lobe + self.sheen * schlick_weight(cos_d)) * (wi.z / PI)
+    }
+
+    /// Evaluate the layers that scatter over a range of directions, leaving out the delta lobes
+    /// of a smooth surface.
+    fn eval(&self, wo: Vec3, wi: Vec3) -> Color {
+        let white = Color::new(1.0, 1.0, 1.0);
+        let glossy = !self.distribution.is_smooth();
+        let under_coat = 1.0 - self.clearcoat;
+        let dielectric = under_coat * (1.0 - self.metallic);
+        let mut value = Color::ZERO;
+
+        if let Some(lobe) = glossy_reflection(&self.clearcoat_distribution, wo, wi) {
+            let fresnel = microfacet::fresnel_dielectric(wo.dot(lobe.m), CLEARCOAT_INDEX);
+            value += white * (self.clearcoat_strength * fresnel * lobe.value);
+        }
+        if glossy && let Some(lobe) = glossy_reflection(&self.distribution, wo, wi) {
+            let cos = wo.dot(lobe.m);
+            let fresnel = microfacet::fresnel_dielectric(cos, self.eta);
+            value += self.metal(cos) * (under_coat * self.metallic * lobe.value);
+            value += white
+                * (dielectric
+                    * lobe.value
+                    * (self.transmission * fresnel
+                        + (1.0 - self.transmission) * self.schlick(cos)));
+        }
+        if glossy && let Some(lobe) = glossy_transmission(&self.distribution, wo, wi, self.eta) {
+            let fresnel = microfacet::fresnel_dielectric(wo.dot(lobe.m), self.eta);
+            value +=
+                self.base_color * (dielectric * self.transmission * (1.0 - fresnel) * lobe.value);
+        }
+        if wo.z > 0.0 && wi.z > 0.0 {
+            let opaque = dielectric * (1.0 - self.transmission);
+            value += self.diffuse(wo, wi) * (opaque * (1.0 - self.specular_probability(wo)));
+        }
+        value
+    }
+
+    fn pdf(&self, wo: Vec3, wi: Vec3) -> f64 {
+        let glossy = !self.distribution.is_smooth();
+        let under_coat = 1.0 - self.clearcoat;
+        let dielectric = under_coat * (1.0 - self.metallic);
+        let opaque = dielectric * (1.0 - self.transmission);
+        let specular = self.specular_probability(wo);
+        let mut pdf = 0.0;
+
+        if let Some(lobe) = glossy_reflection(&self.clearcoat_distribution, wo, wi) {
+            pdf += self.clearcoat * lobe.pdf;
+        }
+        if glossy && let Some(lobe) = glossy_reflection(&self.distribution, wo, wi) {
+            let fresnel = microfacet::fresnel_dielectric(wo.dot(lobe.m), self.eta);
+            pdf += lobe.pdf
+                * (under_coat * self.metallic
+                    + dielectric * self.transmission * fresnel
+                    + opaque * specular);
+        }
+        if glossy && let Some(lobe) = glossy_transmission(&self.distribution, wo, wi, self.eta) {
+            let fresnel = microfacet::fresnel_dielectric(wo.dot(lobe.m), self.eta);
+            pdf += dielectric * self.transmission * (1.0 - fresnel) * lobe.pdf;
+        }
+        if wo.z > 0.0 && wi.z > 0.0 {
+            pdf += opaque * (1.0 - specular) * wi.z / PI;
+        }
+        pdf
+    }
+
+    /// Pick a direction by walking down the layers, in the same proportions [`Parameters::pdf`]
+    /// assumes.
+    ///
+    /// On a smooth surface, the metal, specular and transmission layers are delta lobes, which
+    /// come with their value divided by the probability of picking them.
+    fn sample(&self, wo: Vec3) -> Option<(Vec3, Option<Color>)> {
+        let white = Color::new(1.0, 1.0, 1.0);
+        let smooth = self.distribution.is_smooth();
+        let sample_normal = |distribution: &TrowbridgeReitz| {
+            distribution.sample_visible_normal(
+                wo,
+                raytracing::random_f64(),
+                raytracing::random_f64(),
+            )
+        };
+
+        // The Fresnel terms of the transmission and specular layers cancel out against the
+        // probabilities of picking them, and that of the metal does too but for its tint.
+        let (wi, delta) = if raytracing::random_f64() < self.clearcoat {
+            (
+                reflect(wo, sample_normal(&self.clearcoat_distribution)),
+                None,
+            )
+        } else if raytracing::random_f64() < self.metallic {
+            let m = sample_normal(&self.distribution);
+            (reflect(wo, m), smooth.then(|| self.metal(wo.dot(m))))
+        } else if raytracing::random_f64() < self.transmission {
+            let m = sample_normal(&self.distribution);
+            if raytracing::random_f64() < microfacet::fresnel_dielectric(wo.dot(m), self.eta) {
+                (reflect(wo, m), smooth.then_some(white))
+            } else {
+                (refract(wo, m, self.eta)?, smooth.then_some(self.base_color))
+            }
+        } else if raytracing::random_f64() < self.specular_probability(wo) {
+            (
+                reflect(wo, sample_normal(&self.distribution)),
+                smooth.then_some(white),
+            )
+        } else {
+            (random_cosine_direction(Vec3::new(0.0, 0.0, 1.0)), None)
+        };
+        (wi.z != 0.0).then_some((wi, delta))
+    }
+}
+
+impl Principled {
+    /// Look up the parameters at the point of a hit.
+    fn parameters(&self, hit: &ShadingContext, wo: Vec3) -> Parameters {
+        let (u, v, point) = (hit.u, hit.v, hit.point);
+        let clearcoat_roughness =
+            scalar(&self.clearcoat_roughness, hit).max(MIN_CLEARCOAT_ROUGHNESS);
+
+        Parameters {
+            base_color: hit.color(self.base_color.value(u, v, point)),
+            roughness: scalar(&self.roughness, hit),
+            specular: scalar(&self.specular, hit),
+            sheen: hit.color(self.sheen.value(u, v, point)),
+            subsurface: scalar(&self.subsurface, hit),
+            eta: if hit.front_face {
+                self.refractive_index
+            } else {
+                1.0 / self.refractive_index
+            },
+            distribution: self.distribution(hit),
+            clearcoat_distribution: TrowbridgeReitz::isotropic(clearcoat_roughness),
+            clearcoat_strength: scalar(&self.clearcoat, hit),
+            clearcoat: scalar(&self.clearcoat, hit)
+                * microfacet::fresnel_dielectric(wo.z, CLEARCOAT_INDEX),
+            metallic: scalar(&self.metallic, hit),
+            transmission: scalar(&self.transmission, hit),
+        }
+    }
+
+    /// Get the distribution of the metal, specular and transmission layers at a hit.
+    fn distribution(&self, hit: &HitRecord) -> TrowbridgeReitz {
+        let roughness = scalar(&self.roughness, hit);
+        // The Disney model divides and multiplies the width of the distribution by this aspect
+        // ratio along each axis, which keeps the area of the highlight about the same. Roughness
+        // is the square root of the width, so it takes the square root of the ratio.
+        let aspect = (1.0 - 0.9 * scalar(&self.anisotropic, hit)).sqrt();
+        TrowbridgeReitz::anisotropic(roughness * aspect.sqrt(), roughness / aspect.sqrt())
+    }
+}
+
+impl Material for Principled {
+    fn eval(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> Color {
+        let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+        let wo = frame.to_local(wo);
+        if wo.z <= 0.0 {
+            return Color::ZERO;
+        }
+        self.parameters(hit, wo).eval(wo, frame.to_local(wi))
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+        let local = frame.to_local(wo);
+        if local.z <= 0.0 {
+            return None;
+        }
+        let parameters = self.parameters(hit, local);
+        let (wi, delta) = parameters.sample(local)?;
+        if let Some(value) = delta {
+            return Some(Sample {
+                direction: frame.to_world(wi),
+                value,
+                pdf: 1.0,
+                is_delta: true,
+                exit: None,
+            });
+        }
+
+        Some(Sample {
+            direction: frame.to_world(wi),
+            value: parameters.eval(local, wi),
+            pdf: parameters.pdf(local, wi),
+            is_delta: false,
+            exit: None,
+        })
+    }
+
+    fn pdf(&self, hit: &ShadingContext, wo: Vec3, wi: Vec3) -> f64 {
+        let frame = ShadingFrame::oriented(hit, self.tangent_rotation.as_deref());
+        let wo = frame.to_local(wo);
+        if wo.z <= 0.0 {
+            return 0.0;
+        }
+        self.parameters(hit, wo).pdf(wo, frame.to_local(wi))
+    }
+
+    fn is_delta(&self, hit: &HitRecord) -> bool {
+        // A smooth surface has nothing but delta lobes once the clearcoat and the diffuse layer
+        // are gone, and the diffuse layer is gone from pure metal and pure glass.
+        self.distribution(hit).is_smooth()
+            && scalar(&self.clearcoat, hit) == 0.0
+            && (scalar(&self.metallic, hit) == 1.0 || scalar(&self.transmission, hit) == 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::material::tests::{
+        albedo, assert_albedo, assert_consistent, direction, hit,
+    };
+
+    #[test]
+    fn samples_match_eval_and_pdf() {
+        let mut metal = Principled::new(Color::new(0.9, 0.6, 0.3));
+        metal.metallic = gray(1.0);
+        let mut glass = Principled::new(Color::new(1.0, 1.0, 1.0));
+        glass.transmission = gray(1.0);
+        let mut varnished = Principled::new(Color::new(0.2, 0.4, 0.8));
+        varnished.clearcoat = gray(1.0);
+        varnished.sheen = gray(0.5);
+        varnished.subsurface = gray(0.5);
+        let mut brushed = Principled::new(Color::new(0.9, 0.9, 0.9));
+        brushed.metallic = gray(0.5);
+        brushed.anisotropic = gray(0.8);
+        brushed.tangent_rotation = Some(gray(0.1));
+        let mut smooth_plastic = Principled::new(Color::new(0.8, 0.2, 0.2));
+        smooth_plastic.roughness = gray(0.0);
+        smooth_plastic.clearcoat = gray(1.0);
+        let mut smooth_metal = Principled::new(Color::new(0.9, 0.6, 0.3));
+        smooth_metal.roughness = gray(0.0);
+        smooth_metal.metallic = gray(0.5);
+        let mut smooth_glass = Principled::new(Color::new(1.0, 1.0, 1.0));
+        smooth_glass.roughness = gray(0.0);
+        smooth_glass.transmission = gray(0.5);
+
+        for material in [
+            Principled::new(Color::new(0.8, 0.8, 0.8)),
+            metal,
+            glass,
+            varnished,
+            brushed,
+            smooth_plastic,
+            smooth_metal,
+            smooth_glass,
+        ] {
+            let material = Rc::new(material);
+            for angle in [0.0, 45.0, 80.0] {
+                assert_consistent(material.clone(), direction(angle));
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_glass_and_metal_keep_all_energy() {
+        let mut glass = Principled::new(Color::new(1.0, 1.0, 1.0));
+        glass.roughness = gray(0.0);
+        glass.transmission = gray(1.0);
+        let mut metal = Principled::new(Color::new(1.0, 1.0, 1.0));
+        metal.roughness = gray(0.0);
+        metal.metallic = gray(1.0);
+
+        for material in [glass, metal] {
+            let material = Rc::new(material);
+            assert!(material.is_delta(&hit(material.clone())));
+            for angle in [0.0, 45.0, 80.0] {
+                assert_albedo(albedo(material.clone(), direction(angle)), 0.999, 1.001);
+            }
+        }
+    }
+
+    #[test]
+    fn clearcoat_reflects_like_a_dielectric() {
+        let mut coat = Principled::new(Color::ZERO);
+        coat.specular = gray(0.0);
+        coat.clearcoat = gray(1.0);
+
+        // Head on, a smooth interface with a refractive index of 1.5 reflects 4% of the light.
+        assert_albedo(albedo(Rc::new(coat), direction(0.0)), 0.035, 0.045);
+    }
+
+    #[test]
+    fn anisotropic_highlights_stretch_across_the_tangent() {
+        // The test hit has its tangent along x, so a quarter turn puts it along y.
+        for (turn, across) in [
+            (0.0, Vec3::new(0.0, 0.5, 1.0)),
+            (0.25, Vec3::new(0.5, 0.0, 1.0)),
+        ] {
+            let mut brushed = Principled::new(Color::new(0.9, 0.9, 0.9));
+            brushed.metallic = gray(1.0);
+            brushed.roughness = gray(0.3);
+            brushed.anisotropic = gray(1.0);
+            brushed.tangent_rotation = Some(gray(turn));
+            let brushed = Rc::new(brushed);
+
+            let hit = hit(brushed.clone());
+            let hit = ShadingContext {
+                hit: &hit,
+                wavelengths: None,
+            };
+            let along = Vec3::new(across.y, across.x, 1.0);
+            let wo = Vec3::new(0.0, 0.0, 1.0);
+            let value = |wi: Vec3| brushed.eval(&hit, wo, wi.unit_vector()).x;
+            assert!(value(across) > 2.0 * value(along), "turn {turn}");
+        }
+    }
+
+    #[test]
+    fn gltf_parameters_come_from_their_channels() {
+        let solid =
+            |r, g, b| Some(Rc::new(SolidColor::new(Color::new(r, g, b))) as Rc<dyn Texture>);
+        let gltf = GltfMaterial {
+            base_color_factor: Color::new(0.5, 0.5, 0.5),
+            base_color_texture: solid(0.2, 0.4, 0.8),
+            metallic_roughness_texture: solid(0.9, 0.6, 0.3),
+            specular_alpha_texture: solid(0.6, 0.1, 0.2),
+            clearcoat_factor: 1.0,
+            clearcoat_texture: solid(0.7, 0.2, 0.1),
+            clearcoat_roughness_factor: 1.0,
+            clearcoat_roughness_texture: solid(0.1, 0.4, 0.9),
+            transmission_factor: 0.5,
+            transmission_texture: solid(0.8, 0.3, 0.1),
+            ..GltfMaterial::default()
+        };
+        let material = Principled::from_gltf(&gltf);
+        let hit = hit(Rc::new(Principled::new(Color::ZERO)));
+        let at = |texture: &Rc<dyn Texture>| scalar(texture, &hit);
+
+        let base_color = material.base_color.value(hit.u, hit.v, hit.point);
+        assert!((base_color - Color::new(0.1, 0.2, 0.4)).length() < 1e-12);
+        assert!((at(&material.metallic) - 0.3).abs() < 1e-12);
+        assert!((at(&material.roughness) - 0.6).abs() < 1e-12);
+        // An index of 1.5 reflects half as much as the strongest specular value allows.
+        assert!((at(&material.specular) - 0.3).abs() < 1e-12);
+        assert!((at(&material.clearcoat) - 0.7).abs() < 1e-12);
+        assert!((at(&material.clearcoat_roughness) - 0.4).abs() < 1e-12);
+        assert!((at(&material.transmission) - 0.4).abs() < 1e-12);
+    }
+
+    #[test]
+    fn mtl_parameters_are_converted() {
+        let mtl = raytracing::mtl::parse(
+            "newmtl tinted_glass\nKd 0.8 0.2 0.2\nTf 0.2 0.6 1.0\nd 0.25\nNs 98\nNi 1.5\n",
+        )
+        .unwrap();
+        let material = Principled::from_mtl(&mtl[0], ".").unwrap();
+        let hit = hit(Rc::new(Principled::new(Color::ZERO)));
+        let at = |texture: &Rc<dyn Texture>| scalar(texture, &hit);
+
+        // A dissolve of a quarter leaves the rest to the transmission filter.
+        let base_color = material.base_color.value(hit.u, hit.v, hit.point);
+        assert!((base_color - Color::new(0.35, 0.5, 0.8)).length() < 1e-12);
+        assert!((at(&material.transmission) - 0.75).abs() < 1e-12);
+
+        // A Phong exponent of 98 is a microfacet width of the square root of 0.02.
+        assert!((at(&material.roughness) - 0.02_f64.powf(0.25)).abs() < 1e-12);
+        assert!((at(&material.specular) - 0.5).abs() < 1e-12);
+        assert!((material.refractive_index - 1.5).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,346 @@
+use std::rc::Rc;
+
+use raytracing::color::Color;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{Point3, Vec3};
+use raytracing::microfacet;
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::hittable_list::HittableList;
+use crate::renderer::material::{Material, Sample, ShadingContext};
+use crate::renderer::microfacet::{reflect, refract};
+
+/// The most times light can scatter beneath the surface before it is taken to be absorbed.
+const MAX_BOUNCES: usize = 256;
+
+/// How far along a ray inside the material the walk starts looking for the surface again.
+const EPSILON: f64 = 0.0001;
+
+/// The shortest mean free path used, as the spectrum of a color can fall to zero in places.
+const MIN_MEAN_FREE_PATH: f64 = 1e-4;
+
+/// A translucent material which light travels beneath the surface of, like skin, wax, marble or
+/// milk.
+///
+/// Light refracts in through a smooth surface, and then wanders around inside the closed
+/// `boundary` shape in a random walk, scattering off particles in random directions, until it
+/// finds its way back out. The material is put in the scene on its boundary with a
+/// [`Translucent`](crate::translucent::Translucent).
+///
+/// Objects embedded in the material go in its `contents`, which the walk traces as well. Light
+/// meeting one scatters off it with its own material, and is lost if that sends it into the
+/// object rather than back out into the material.
+///
+/// The `albedo` is the color the material ends up looking, and the `mean_free_path` is how far
+/// light of each channel gets on average between scattering events. The longer it is, the deeper
+/// light goes and the more translucent the material looks.
+pub struct Subsurface {
+    pub boundary: Rc<dyn Hittable>,
+    pub contents: HittableList,
+    pub albedo: Color,
+    pub mean_free_path: Color,
+    pub refractive_index: f64,
+}
+
+impl Subsurface {
+    /// Fill a closed shape with a translucent material, with the refractive index of skin.
+    pub fn new(boundary: Rc<dyn Hittable>, albedo: Color, mean_free_path: Color) -> Self {
+        Self {
+            boundary,
+            contents: HittableList::new(),
+            albedo,
+            mean_free_path,
+            refractive_index: 1.4,
+        }
+    }
+
+    /// Get the fraction of light arriving at the surface from inside, equally from every
+    /// direction, which the surface reflects back in.
+    fn internal_reflectance(&self) -> f64 {
+        const STEPS: usize = 64;
+
+        let eta = 1.0 / self.refractive_index;
+        let sum: f64 = (0..STEPS)
+            .map(|i| {
+                let cos = (i as f64 + 0.5) / STEPS as f64;
+                2.0 * cos * microfacet::fresnel_dielectric(cos, eta)
+            })
+            .sum();
+        sum / STEPS as f64
+    }
+
+    /// Get the chance of light surviving each scattering event for every channel.
+    ///
+    /// This is a fit by Chiang et al. which makes a thick slab of the material reflect the given
+    /// albedo overall, after any number of bounces beneath the surface. That fit is for a slab
+    /// light leaves as soon as it reaches the surface, while this one reflects some of it back in
+    /// each time, giving it more chances to be absorbed. The albedo the fit is given is raised to
+    /// make up for that, so the slab still comes out at the albedo asked for. The spectra of colors
+    /// can overshoot slightly, so albedos are clamped to at most one first.
+    fn single_scattering_albedo(&self, albedo: Color) -> [f64; 3] {
+        let reflectance = self.internal_reflectance();
+        [albedo.x, albedo.y, albedo.z].map(|albedo| {
+            let albedo = albedo.clamp(0.0, 1.0);
+
+            // Light reaching the surface from inside leaves with the chance of not reflecting
+            // back in, and otherwise goes round again, which sums to this over every round.
+            let albedo = albedo / (1.0 - reflectance + reflectance * albedo);
+            let s = 4.09712 + 4.20863 * albedo
+                - (9.59217 + 41.6808 * albedo + 17.7126 * albedo * albedo).sqrt();
+            1.0 - s * s
+        })
+    }
+
+    /// Follow light from a point just inside the surface until it leaves the material again.
+    ///
+    /// Returns the point and direction it leaves in, along with the fraction of each channel which
+    /// survives, or nothing if all of it is absorbed. Distances are sampled using one channel
+    /// picked at random, and weighted by the density of picking them using any of the three. When
+    /// rendering spectrally, the channels are the wavelengths of the path, and the albedo and mean
+    /// free path are taken from their spectra.
+    fn walk(
+        &self,
+        hit: &ShadingContext,
+        mut point: Point3,
+        mut direction: Vec3,
+    ) -> Option<(Point3, Vec3, Color)> {
+        let mean_free_path = hit.color(self.mean_free_path);
+        let extinction = [mean_free_path.x, mean_free_path.y, mean_free_path.z]
+            .map(|path| 1.0 / path.max(MIN_MEAN_FREE_PATH));
+        let albedo = self.single_scattering_albedo(hit.color(self.albedo));
+        let mut throughput = [1.0; 3];
+
+        for _ in 0..MAX_BOUNCES {
+            // Channels carrying more light are picked more often, which keeps any one of them
+            // from being weighted up too far.
+            let total: f64 = throughput.iter().sum();
+            if total <= 0.0 {
+                return None;
+            }
+            let chance = throughput.map(|value| value / total);
+            let mut pick = raytracing::random_f64();
+            let channel = (0..2)
+                .find(|&i| {
+                    pick -= chance[i];
+                    pick < 0.0
+                })
+                .unwrap_or(2);
+
+            let distance = -(1.0 - raytracing::random_f64()).ln() / extinction[channel];
+            let ray = Ray::new(point, direction);
+            let boundary = self
+                .boundary
+                .hit(ray, Interval::new(EPSILON, f64::INFINITY))?;
+            let content = self
+                .contents
+                .hit(ray, Interval::new(EPSILON, boundary.time));
+            let surface = content.as_ref().unwrap_or(&boundary);
+
+            if distance < surface.time {
+                // Scatter off a particle in a random direction.
+                let transmittance = extinction.map(|sigma| (-sigma * distance).exp());
+                let pdf: f64 = (0..3)
+                    .map(|i| chance[i] * extinction[i] * transmittance[i])
+                    .sum();
+                for i in 0..3 {
+                    throughput[i] *= albedo[i] * extinction[i] * transmittance[i] / pdf;
+                }
+                point = ray.at(distance);
+                direction = Vec3::random_unit_vector();
+                continue;
+            }
+
+            let transmittance = extinction.map(|sigma| (-sigma * surface.time).exp());
+            let probability: f64 = (0..3).map(|i| chance[i] * transmittance[i]).sum();
+            for i in 0..3 {
+                throughput[i] *= transmittance[i] / probability;
+            }
+
+            let wo = -direction;
+            if let Some(content) = &content {
+                let sample = content.material.sample(&hit.with_hit(content), wo)?;
+                if sample.direction.dot(content.normal) <= 0.0 {
+                    return None;
+                }
+                let weight = sample.value / sample.pdf;
+                for (value, weight) in throughput.iter_mut().zip([weight.x, weight.y, weight.z]) {
+                    *value *= weight;
+                }
+                point = sample.exit.unwrap_or(content.point);
+                direction = sample.direction;
+                continue;
+            }
+
+            // Reaching the surface, the light either reflects back in or refracts out.
+            let eta = 1.0 / self.refractive_index;
+            point = surface.point;
+            if raytracing::random_f64()
+                < microfacet::fresnel_dielectric(wo.dot(surface.normal), eta)
+            {
+                direction = reflect(wo, surface.normal);
+                continue;
+            }
+            let out = refract(wo, surface.normal, eta)?;
+            let [r, g, b] = throughput;
+            return Some((point, out, Color::new(r, g, b)));
+        }
+
+        None
+    }
+}
+
+impl Material for Subsurface {
+    fn eval(&self, _hit: &ShadingContext, _wo: Vec3, _wi: Vec3) -> Color {
+        Color::ZERO
+    }
+
+    fn sample(&self, hit: &ShadingContext, wo: Vec3) -> Option<Sample> {
+        let normal = hit.normal;
+        let cos_o = wo.dot(normal);
+        if cos_o <= 0.0 {
+            return None;
+        }
+
+        if raytracing::random_f64() < microfacet::fresnel_dielectric(cos_o, self.refractive_index) {
+            return Some(Sample {
+                direction: reflect(wo, normal),
+                value: Color::new(1.0, 1.0, 1.0),
+                pdf: 1.0,
+                is_delta: true,
+                exit: None,
+            });
+        }
+
+        let inside = refract(wo, normal, self.refractive_index)?;
+        let (exit, direction, value) = self.walk(hit, hit.point, inside)?;
+        Some(Sample {
+            direction,
+            value,
+            pdf: 1.0,
+            is_delta: true,
+            exit: Some(exit),
+        })
+    }
+
+    fn pdf(&self, _hit: &ShadingContext, _wo: Vec3, _wi: Vec3) -> f64 {
+        0.0
+    }
+
+    fn is_delta(&self, _hit: &HitRecord) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raytracing::math::Point3;
+
+    use raytracing::spectrum::Wavelengths;
+
+    use super::*;
+    use crate::renderer::material::Lambertian;
+    use crate::renderer::material::tests::{self, albedo, direction, hit};
+    use crate::sphere::Sphere;
+
+    /// Fill a ball touching the test hit from below with a translucent material.
+    fn ball(
+        albedo: Color,
+        mean_free_path: Color,
+        contents: impl IntoIterator<Item = Rc<dyn Hittable>>,
+    ) -> Rc<dyn Material> {
+        let black = Rc::new(Lambertian {
+            albedo: Color::ZERO,
+        });
+        let center = Point3::new(0.0, 0.0, -1.0);
+        let boundary = Rc::new(Sphere::new(center, 1.0, black));
+        let mut material = Subsurface::new(boundary, albedo, mean_free_path);
+        for object in contents {
+            material.contents.add(object);
+        }
+        Rc::new(material)
+    }
+
+    /// Fill a ball with a white translucent material.
+    fn white_ball(contents: impl IntoIterator<Item = Rc<dyn Hittable>>) -> Rc<dyn Material> {
+        ball(
+            Color::new(0.9, 0.9, 0.9),
+            Color::new(0.2, 0.2, 0.2),
+            contents,
+        )
+    }
+
+    #[test]
+    fn contents_absorb_light_walking_into_them() {
+        let core: Rc<dyn Hittable> = Rc::new(Sphere::new(
+            Point3::new(0.0, 0.0, -1.0),
+            0.9,
+            Rc::new(Lambertian {
+                albedo: Color::ZERO,
+            }),
+        ));
+        let clear = albedo(white_ball([]), direction(0.0));
+        let cored = albedo(white_ball([core]), direction(0.0));
+        assert!(
+            cored.x < 0.5 * clear.x,
+            "{} with a core, {} without",
+            cored.x,
+            clear.x
+        );
+    }
+
+    #[test]
+    fn thick_material_looks_like_its_albedo() {
+        let target = Color::new(0.8, 0.5, 0.2);
+        let thick = ball(target, Color::new(0.02, 0.02, 0.02), []);
+        let albedo = albedo(thick, direction(0.0));
+        assert!((albedo - target).length() < 0.03, "{albedo}");
+    }
+
+    #[test]
+    fn longer_mean_free_path_lets_more_of_its_channel_through() {
+        // Light of the blue channel passes through the thin ball scattering only a few times,
+        // so less of it is absorbed than of the others.
+        let gray = Color::new(0.5, 0.5, 0.5);
+        let material = ball(gray, Color::new(0.05, 0.05, 2.0), []);
+        let albedo = albedo(material, direction(0.0));
+        assert!((albedo.x - albedo.y).abs() < 0.02, "{albedo}");
+        assert!(albedo.z > albedo.x + 0.2, "{albedo}");
+    }
+
+    #[test]
+    fn spectral_walks_are_at_the_path_wavelengths() {
+        const SAMPLES: usize = 100_000;
+
+        let albedo = Color::new(0.8, 0.3, 0.1);
+        let mean_free_path = Color::new(0.4, 0.1, 0.05);
+        let wavelengths = Wavelengths::sample(0.2);
+
+        // Walking spectrally should be the same as walking in a material whose channels already
+        // hold the spectra at each wavelength.
+        let spectral = ball(albedo, mean_free_path, []);
+        let hit = hit(spectral.clone());
+        let hit = ShadingContext {
+            hit: &hit,
+            wavelengths: Some(wavelengths),
+        };
+        let mut sum = Color::ZERO;
+        for _ in 0..SAMPLES {
+            if let Some(sample) = spectral.sample(&hit, direction(0.0)) {
+                sum += sample.value / sample.pdf;
+            }
+        }
+        let spectral = sum / SAMPLES as f64;
+
+        let upsampled = ball(
+            wavelengths.upsample(albedo),
+            wavelengths.upsample(mean_free_path),
+            [],
+        );
+        let expected = tests::albedo(upsampled, direction(0.0));
+        assert!(
+            (spectral - expected).length() < 0.02,
+            "{spectral} spectrally, {expected} expected"
+        );
+    }
+}
@@ -0,0 +1,1630 @@
+use std::io::ErrorKind;
+use std::rc::Rc;
+
+use raytracing::atmosphere::Atmosphere;
+use raytracing::camera::Camera;
+use raytracing::color::Color;
+use raytracing::density_grid::DensityGrid;
+use raytracing::gltf::GltfMaterial;
+use raytracing::image::pixmap::PixmapImage;
+use raytracing::implicit::{Metaball, Metaballs};
+use raytracing::math::aabb::Aabb;
+use raytracing::math::{Point3, Vec3};
+use raytracing::mesh::{Mesh, Subdivision};
+use raytracing::microfacet::{ThinFilm, TrowbridgeReitz};
+use raytracing::patches;
+use raytracing::point_cloud::PointCloud;
+use raytracing::sdf::{self, Displace, Mandelbulb, Repeat, Scale, SmoothUnion, Translate, Twist};
+use raytracing::spectrum::Dispersion;
+use raytracing::texture::{
+    Checker, ImageTexture, Multiply, NoiseTexture, Perlin, SolidColor, Texture,
+};
+
+use crate::bezier_patch::BezierPatch;
+use crate::cone::Cone;
+use crate::constant_medium::ConstantMedium;
+use crate::csg::Csg;
+use crate::cuboid::Cuboid;
+use crate::curve::{Curve, CurveKind};
+use crate::cylinder::Cylinder;
+use crate::disk::Disk;
+use crate::heightfield::Heightfield;
+use crate::heterogeneous_medium::HeterogeneousMedium;
+use crate::hittable_list::HittableList;
+use crate::implicit_surface::ImplicitSurface;
+use crate::plane::Plane;
+use crate::points::{PointShape, Points};
+use crate::quad::Quad;
+use crate::quadric::Quadric;
+use crate::ray_marched::RayMarched;
+use crate::renderer::coated::Coated;
+use crate::renderer::hair::Hair;
+use crate::renderer::material::{
+    AlphaMask, HenyeyGreenstein, Lambertian, Material, Metal, Mix, OrenNayar, TwoSided,
+};
+use crate::renderer::microfacet::{Conductor, Dielectric};
+use crate::renderer::normal_map::{BumpMap, NormalMap};
+use crate::renderer::principled::Principled;
+use crate::renderer::subsurface::Subsurface;
+use crate::sphere::Sphere;
+use crate::torus::Torus;
+use crate::translucent::Translucent;
+use crate::triangle::Triangle;
+use crate::triangle_mesh::TriangleMesh;
+
+/// Everything needed to render a picture.
+pub struct Scene {
+    pub camera: Camera,
+    pub world: HittableList,
+    pub atmosphere: Option<Atmosphere>,
+
+    /// Whether the scene needs to be rendered spectrally to look right.
+    pub spectral: bool,
+}
+
+impl Scene {
+    /// Create a scene without an atmosphere, to be rendered in RGB.
+    pub fn new(camera: Camera, world: HittableList) -> Self {
+        Self {
+            camera,
+            world,
+            atmosphere: None,
+            spectral: false,
+        }
+    }
+}
+
+/// The camera most of the scenes are viewed through.
+fn default_camera() -> Camera {
+    Camera {
+        position: Vec3::ZERO,
+        focal_length: 1.0,
+        viewport_height: 2.0,
+        viewport_width: 32.0 / 9.0,
+    }
+}
+
+/// Three spheres, one matte and two metal, sitting on a large ground sphere.
+pub fn spheres() -> Scene {
+    let material_ground = Rc::new(Lambertian {
+        albedo: Color::new(0.8, 0.8, 0.0),
+    });
+    let material_center = Rc::new(Lambertian {
+        albedo: Color::new(0.1, 0.2, 0.5),
+    });
+    let material_left = Rc::new(Metal {
+        albedo: Color::new(0.8, 0.8, 0.8),
+    });
+    let material_right = Rc::new(Metal {
+        albedo: Color::new(0.8, 0.6, 0.2),
+    });
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -100.5, -1.0),
+        100.0,
+        material_ground,
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 0.0, -1.2),
+        0.5,
+        material_center,
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-1.0, 0.0, -1.0),
+        0.5,
+        material_left,
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(1.0, 0.0, -1.0),
+        0.5,
+        material_right,
+    )));
+
+    Scene::new(default_camera(), world)
+}
+
+/// A bump mapped sphere and a normal mapped sphere standing on a triangle.
+///
+/// The normal map is read from `textures/normal.ppm`. If that file does not exist, a flat map is
+/// used instead, and any other error reading it is returned.
+pub fn surface_detail() -> std::io::Result<Scene> {
+    let clay = Rc::new(Lambertian {
+        albedo: Color::new(0.7, 0.5, 0.4),
+    });
+    let bumpy = Rc::new(BumpMap {
+        base: clay,
+        height: Rc::new(NoiseTexture::new(8.0)),
+        scale: 0.02,
+    });
+
+    let normal_map: Rc<dyn Texture> = match ImageTexture::open_linear("textures/normal.ppm") {
+        Ok(texture) => Rc::new(texture),
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            Rc::new(SolidColor::new(Color::new(0.5, 0.5, 1.0)))
+        }
+        Err(error) => return Err(error),
+    };
+    let tiled = Rc::new(NormalMap::new(
+        Rc::new(Metal {
+            albedo: Color::new(0.8, 0.8, 0.8),
+        }),
+        normal_map,
+    ));
+
+    let ground = Rc::new(Lambertian {
+        albedo: Color::new(0.5, 0.5, 0.5),
+    });
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Triangle::new(
+        [
+            Point3::new(-40.0, -0.5, 20.0),
+            Point3::new(40.0, -0.5, 20.0),
+            Point3::new(0.0, -0.5, -60.0),
+        ],
+        ground,
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-0.6, 0.0, -1.2),
+        0.5,
+        bumpy,
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.6, 0.0, -1.2),
+        0.5,
+        tiled,
+    )));
+
+    Ok(Scene::new(default_camera(), world))
+}
+
+/// A sphere seen through a lattice cut out of a quad.
+pub fn cutout() -> Scene {
+    let lattice = Rc::new(AlphaMask::new(
+        Rc::new(Lambertian {
+            albedo: Color::new(0.4, 0.25, 0.1),
+        }),
+        Rc::new(Checker::new(
+            0.1,
+            Color::new(1.0, 1.0, 1.0),
+            Color::new(0.0, 0.0, 0.0),
+        )),
+    ));
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Quad::new(
+        Point3::new(-1.2, -0.5, -0.8),
+        Vec3::new(2.4, 0.0, 0.0),
+        Vec3::new(0.0, 1.2, 0.0),
+        lattice,
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 0.0, -1.6),
+        0.5,
+        Rc::new(Lambertian {
+            albedo: Color::new(0.1, 0.2, 0.5),
+        }),
+    )));
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.8, 0.8, 0.0),
+        }),
+    )));
+
+    Scene::new(default_camera(), world)
+}
+
+/// A box, a disk and a mirror quad standing on the floor.
+pub fn shapes() -> Scene {
+    let floor = Rc::new(Lambertian {
+        albedo: Color::new(0.5, 0.5, 0.5),
+    });
+    let red = Rc::new(Lambertian {
+        albedo: Color::new(0.65, 0.05, 0.05),
+    });
+    let green = Rc::new(Lambertian {
+        albedo: Color::new(0.12, 0.45, 0.15),
+    });
+    let mirror = Rc::new(Metal {
+        albedo: Color::new(0.8, 0.8, 0.9),
+    });
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        floor,
+    )));
+    world.add(Rc::new(Cuboid::new(
+        Point3::new(-1.6, -0.5, -2.0),
+        Point3::new(-0.8, 0.3, -1.2),
+        red,
+    )));
+    world.add(Rc::new(Disk::new(
+        Point3::new(0.0, 0.0, -1.6),
+        Vec3::new(0.0, 0.3, 1.0),
+        0.5,
+        green,
+    )));
+    world.add(Rc::new(Quad::new(
+        Point3::new(0.8, -0.5, -2.0),
+        Vec3::new(0.6, 0.0, 0.4),
+        Vec3::new(0.0, 1.0, 0.0),
+        mirror,
+    )));
+
+    Scene::new(default_camera(), world)
+}
+
+/// A pipe, a cone and a ring made from analytic quadric and quartic surfaces.
+pub fn pipes() -> Scene {
+    let steel = Rc::new(Metal {
+        albedo: Color::new(0.7, 0.7, 0.75),
+    });
+    let copper = Rc::new(Metal {
+        albedo: Color::new(0.85, 0.5, 0.3),
+    });
+    let paint = Rc::new(Lambertian {
+        albedo: Color::new(0.8, 0.3, 0.1),
+    });
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+    world.add(Rc::new(Cylinder::new(
+        Point3::new(-1.2, -0.5, -1.6),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.3,
+        1.0,
+        steel,
+    )));
+    world.add(Rc::new(Cone::new(
+        Point3::new(0.0, -0.5, -1.6),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.4,
+        0.9,
+        paint,
+    )));
+    world.add(Rc::new(Torus::new(
+        Point3::new(1.1, -0.1, -1.5),
+        Vec3::new(0.3, 1.0, 0.6),
+        0.35,
+        0.12,
+        copper,
+    )));
+
+    Scene::new(default_camera(), world)
+}
+
+/// A lens, a drilled dome and a carved sphere built with constructive solid geometry.
+pub fn carved() -> Scene {
+    let blue = Rc::new(Lambertian {
+        albedo: Color::new(0.1, 0.2, 0.5),
+    });
+    let brass = Rc::new(Metal {
+        albedo: Color::new(0.8, 0.6, 0.2),
+    });
+    let stone = Rc::new(Lambertian {
+        albedo: Color::new(0.6, 0.6, 0.55),
+    });
+    let red = Rc::new(Lambertian {
+        albedo: Color::new(0.65, 0.05, 0.05),
+    });
+
+    let lens = Csg::intersection(
+        Rc::new(Sphere::new(Point3::new(-1.9, 0.1, -1.6), 0.9, blue.clone())),
+        Rc::new(Sphere::new(Point3::new(-0.5, 0.1, -1.6), 0.9, blue)),
+    );
+    let domed = Csg::union(
+        Rc::new(Cuboid::new(
+            Point3::new(-0.35, -0.5, -1.8),
+            Point3::new(0.35, 0.2, -1.1),
+            stone.clone(),
+        )),
+        Rc::new(Sphere::new(
+            Point3::new(0.0, 0.2, -1.45),
+            0.3,
+            stone.clone(),
+        )),
+    );
+    let drilled = Csg::difference(
+        Rc::new(domed),
+        Rc::new(Cylinder::new(
+            Point3::new(0.0, -0.15, -0.9),
+            Vec3::new(0.0, 0.0, -1.0),
+            0.2,
+            1.2,
+            stone,
+        )),
+    );
+    let carved = Csg::difference(
+        Rc::new(Sphere::new(Point3::new(1.2, 0.0, -1.5), 0.5, brass)),
+        Rc::new(Cuboid::new(
+            Point3::new(1.2, 0.0, -1.5),
+            Point3::new(2.0, 0.8, -0.5),
+            red,
+        )),
+    );
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+    world.add(Rc::new(lens));
+    world.add(Rc::new(drilled));
+    world.add(Rc::new(carved));
+
+    Scene::new(default_camera(), world)
+}
+
+/// A fractal, a twisted box, a blobby ring and a row of beads, all traced by ray marching.
+pub fn distance_fields() -> Scene {
+    let bulb = Translate {
+        inner: Rc::new(Scale {
+            inner: Rc::new(Mandelbulb {
+                power: 8.0,
+                iterations: 12,
+            }),
+            factor: 0.45,
+        }),
+        offset: Vec3::new(-1.2, 0.0, -1.6),
+    };
+    let twisted = Translate {
+        inner: Rc::new(Twist {
+            inner: Rc::new(sdf::Cuboid {
+                half_extents: Vec3::new(0.2, 0.45, 0.2),
+                rounding: 0.03,
+            }),
+            rate: 2.5,
+        }),
+        offset: Vec3::new(0.0, -0.05, -1.6),
+    };
+    let blob = Translate {
+        inner: Rc::new(Displace {
+            inner: Rc::new(SmoothUnion {
+                left: Rc::new(sdf::Sphere { radius: 0.22 }),
+                right: Rc::new(sdf::Torus {
+                    major_radius: 0.3,
+                    minor_radius: 0.08,
+                }),
+                smoothness: 0.15,
+            }),
+            displacement: Rc::new(|p: Point3| {
+                0.015 * (20.0 * p.x).sin() * (20.0 * p.y).sin() * (20.0 * p.z).sin()
+            }),
+        }),
+        offset: Vec3::new(1.2, -0.1, -1.6),
+    };
+    let beads = Translate {
+        inner: Rc::new(Repeat {
+            inner: Rc::new(sdf::Sphere { radius: 0.08 }),
+            period: Vec3::new(0.25, 0.0, 0.0),
+        }),
+        offset: Vec3::new(0.0, -0.42, -0.9),
+    };
+
+    let bounds = |center: Point3, extent: f64| {
+        let extent = Vec3::new(extent, extent, extent);
+        Aabb::from_points(center - extent, center + extent)
+    };
+    let gold = Rc::new(Metal {
+        albedo: Color::new(0.8, 0.6, 0.2),
+    });
+    let clay = Rc::new(Lambertian {
+        albedo: Color::new(0.7, 0.4, 0.3),
+    });
+
+    let mut twisted = RayMarched::new(
+        Rc::new(twisted),
+        bounds(Point3::new(0.0, -0.05, -1.6), 0.5),
+        clay.clone(),
+    );
+    twisted.step_scale = 0.5;
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+    world.add(Rc::new(RayMarched::new(
+        Rc::new(bulb),
+        bounds(Point3::new(-1.2, 0.0, -1.6), 0.55),
+        gold.clone(),
+    )));
+    world.add(Rc::new(twisted));
+    world.add(Rc::new(RayMarched::new(
+        Rc::new(blob),
+        bounds(Point3::new(1.2, -0.1, -1.6), 0.42),
+        clay,
+    )));
+    world.add(Rc::new(RayMarched::new(
+        Rc::new(beads),
+        Aabb::from_points(Point3::new(-2.0, -0.5, -1.0), Point3::new(2.0, -0.34, -0.8)),
+        gold,
+    )));
+
+    Scene::new(default_camera(), world)
+}
+
+/// A block of dark smoke and a ball of thick white fog.
+pub fn smoke() -> Scene {
+    let boundary_material = Rc::new(Lambertian {
+        albedo: Color::ZERO,
+    });
+    let smoke_box = Rc::new(Cuboid::new(
+        Point3::new(-1.4, -0.5, -2.0),
+        Point3::new(-0.3, 0.6, -1.2),
+        boundary_material.clone(),
+    ));
+    let fog_ball = Rc::new(Sphere::new(
+        Point3::new(0.8, 0.0, -1.5),
+        0.5,
+        boundary_material,
+    ));
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+    world.add(Rc::new(ConstantMedium::new(
+        smoke_box,
+        2.0,
+        Color::new(0.2, 0.2, 0.2),
+    )));
+    world.add(Rc::new(ConstantMedium::new(
+        fog_ball,
+        12.0,
+        Color::new(0.9, 0.9, 0.9),
+    )));
+
+    Scene::new(default_camera(), world)
+}
+
+/// A cloud loaded from a voxel grid.
+///
+/// The grid is read from `volumes/cloud.txt`. If that file does not exist, a puffy ball of noise
+/// is generated instead.
+pub fn cloud() -> Scene {
+    let grid = DensityGrid::load_text("volumes/cloud.txt").unwrap_or_else(|_| {
+        const SIZE: usize = 48;
+
+        let noise = Perlin::new();
+        let mut values = Vec::with_capacity(SIZE * SIZE * SIZE);
+        for z in 0..SIZE {
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    let point = Point3::new(x as f64, y as f64, z as f64) / SIZE as f64;
+                    let falloff = 1.0 - (point - Point3::new(0.5, 0.5, 0.5)).length() * 2.0;
+                    let turbulence = noise.turbulence(point * 4.0, 5);
+                    values.push((falloff + turbulence - 0.4).max(0.0));
+                }
+            }
+        }
+        DensityGrid::new([SIZE; 3], values)
+    });
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.3, 0.45, 0.2),
+        }),
+    )));
+    world.add(Rc::new(HeterogeneousMedium {
+        grid: Rc::new(grid),
+        bounds: Aabb::from_points(Point3::new(-1.0, -0.4, -2.4), Point3::new(1.0, 1.0, -1.0)),
+        density_scale: 20.0,
+        phase_function: Rc::new(HenyeyGreenstein::new(Color::new(0.95, 0.95, 0.95), 0.6)),
+    }));
+
+    Scene::new(default_camera(), world)
+}
+
+/// Rows of trees receding into hazy hills.
+pub fn landscape() -> Scene {
+    let grass = Rc::new(Lambertian {
+        albedo: Color::new(0.3, 0.5, 0.2),
+    });
+    let foliage = Rc::new(Lambertian {
+        albedo: Color::new(0.1, 0.35, 0.1),
+    });
+    let bark = Rc::new(Lambertian {
+        albedo: Color::new(0.35, 0.2, 0.1),
+    });
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        grass.clone(),
+    )));
+    for row in 0..12 {
+        let z = -2.0 - 3.0 * row as f64;
+        for column in -3..=3 {
+            let x = 1.5 * column as f64 + if row % 2 == 0 { 0.0 } else { 0.75 };
+            world.add(Rc::new(Cylinder::new(
+                Point3::new(x, -0.5, z),
+                Vec3::new(0.0, 1.0, 0.0),
+                0.05,
+                0.4,
+                bark.clone(),
+            )));
+            world.add(Rc::new(Cone::new(
+                Point3::new(x, -0.2, z),
+                Vec3::new(0.0, 1.0, 0.0),
+                0.3,
+                0.9,
+                foliage.clone(),
+            )));
+        }
+    }
+    for (center, radius) in [
+        (Point3::new(-30.0, -25.0, -60.0), 30.0),
+        (Point3::new(25.0, -30.0, -70.0), 35.0),
+    ] {
+        world.add(Rc::new(Sphere::new(center, radius, grass.clone())));
+    }
+
+    let mut scene = Scene::new(default_camera(), world);
+    scene.atmosphere = Some(Atmosphere::height_fog(
+        0.03,
+        -0.5,
+        0.6,
+        Color::new(0.75, 0.8, 0.9),
+    ));
+    scene
+}
+
+/// Rolling hills seen from above, fading into the haze.
+///
+/// The terrain is read from the grayscale image `textures/heightmap.ppm`. If that file does not
+/// exist, the hills are generated from noise instead, and any other error reading it, such as the
+/// image being too small, is returned.
+pub fn terrain() -> std::io::Result<Scene> {
+    let corner = Point3::new(-20.0, -2.0, -40.0);
+    let size = Vec3::new(40.0, 4.0, 40.0);
+    let grass = Rc::new(Lambertian {
+        albedo: Color::new(0.35, 0.5, 0.25),
+    });
+
+    let loaded = PixmapImage::load("textures/heightmap.ppm")
+        .and_then(|image| Heightfield::from_image(&image, corner, size, grass.clone()));
+    let terrain = match loaded {
+        Ok(terrain) => terrain,
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            const SIZE: usize = 256;
+
+            let noise = Perlin::new();
+            let mut heights = Vec::with_capacity(SIZE * SIZE);
+            for j in 0..SIZE {
+                for i in 0..SIZE {
+                    let point = Point3::new(i as f64, 0.0, j as f64) / SIZE as f64 * 6.0;
+                    heights.push(noise.turbulence(point, 6));
+                }
+            }
+            Heightfield::new([SIZE; 2], heights, corner, size, grass)
+        }
+        Err(error) => return Err(error),
+    };
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(terrain));
+
+    let mut scene = Scene::new(
+        Camera {
+            position: Point3::new(0.0, 1.5, 0.0),
+            ..default_camera()
+        },
+        world,
+    );
+    scene.atmosphere = Some(Atmosphere::height_fog(
+        0.06,
+        -2.0,
+        0.4,
+        Color::new(0.75, 0.8, 0.9),
+    ));
+    Ok(scene)
+}
+
+/// A blobby molecule next to a tangle cube.
+pub fn blobs() -> Scene {
+    let atom = |x: f64, y: f64, z: f64, radius: f64| Metaball {
+        center: Point3::new(x, y, z),
+        radius,
+        strength: 1.0,
+    };
+    let molecule = Metaballs {
+        balls: vec![
+            atom(-0.7, 0.0, -1.6, 0.6),
+            atom(-1.05, 0.3, -1.5, 0.4),
+            atom(-0.35, 0.3, -1.5, 0.4),
+            atom(-0.7, -0.25, -1.3, 0.35),
+        ],
+        threshold: 0.2,
+    };
+    let molecule_bounds = molecule.bounding_box();
+
+    // The tangle cube is a quartic surface shaped like a cube with its corners and faces hollowed
+    // out. Here it is shrunk to a fifth of its usual size.
+    let tangle = |p: Point3| {
+        let p = (p - Point3::new(0.8, 0.0, -1.6)) * 5.0;
+        let term = |x: f64| x.powi(4) - 5.0 * x * x;
+        term(p.x) + term(p.y) + term(p.z) + 11.8
+    };
+    let tangle_bounds =
+        Aabb::from_points(Point3::new(0.2, -0.6, -2.2), Point3::new(1.4, 0.6, -1.0));
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.6, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+    world.add(Rc::new(ImplicitSurface::new(
+        Rc::new(molecule),
+        molecule_bounds,
+        Rc::new(Lambertian {
+            albedo: Color::new(0.7, 0.15, 0.15),
+        }),
+    )));
+    world.add(Rc::new(ImplicitSurface::new(
+        Rc::new(tangle),
+        tangle_bounds,
+        Rc::new(Metal {
+            albedo: Color::new(0.8, 0.8, 0.85),
+        }),
+    )));
+
+    Scene::new(default_camera(), world)
+}
+
+/// A furry ball sitting in a tuft of grass, with a cable looping over them.
+pub fn fur() -> Scene {
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.6, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.4, 0.3, 0.2),
+        }),
+    )));
+
+    // Strands grow straight out of the ball and droop a little toward their tips.
+    let center = Point3::new(0.0, -0.15, -1.8);
+    let fur = Rc::new(Hair::new(Color::new(0.8, 0.55, 0.3)));
+    world.add(Rc::new(Sphere::new(
+        center,
+        0.4,
+        Rc::new(Lambertian {
+            albedo: Color::new(0.3, 0.18, 0.08),
+        }),
+    )));
+    for _ in 0..1500 {
+        let normal = Vec3::random_unit_vector();
+        let root = center + normal * 0.4;
+        let droop = Vec3::new(0.0, -0.04, 0.0);
+        world.add(Rc::new(Curve::new(
+            [
+                root,
+                root + normal * 0.06,
+                root + normal * 0.12 + droop,
+                root + normal * 0.16 + droop * 2.5,
+            ],
+            [0.006, 0.001],
+            CurveKind::Ribbon,
+            fur.clone(),
+        )));
+    }
+
+    // Blades of grass taper to a point and bend over in random directions.
+    let grass = Rc::new(Lambertian {
+        albedo: Color::new(0.25, 0.5, 0.15),
+    });
+    for _ in 0..400 {
+        let root = Point3::new(
+            raytracing::random_f64_range(-1.6, 1.6),
+            -0.6,
+            raytracing::random_f64_range(-2.6, -1.3),
+        );
+        let height = raytracing::random_f64_range(0.15, 0.35);
+        let lean = Vec3::new(
+            raytracing::random_f64_range(-0.15, 0.15),
+            0.0,
+            raytracing::random_f64_range(-0.15, 0.15),
+        );
+        world.add(Rc::new(Curve::new(
+            [
+                root,
+                root + Vec3::new(0.0, height * 0.5, 0.0),
+                root + Vec3::new(0.0, height, 0.0) + lean * 0.5,
+                root + Vec3::new(0.0, height * 0.9, 0.0) + lean,
+            ],
+            [0.02, 0.0],
+            CurveKind::Ribbon,
+            grass.clone(),
+        )));
+    }
+
+    // The cable is made of two curves meeting smoothly at the top of the loop.
+    let cable = Rc::new(Metal {
+        albedo: Color::new(0.2, 0.2, 0.25),
+    });
+    let segments = [
+        [
+            Point3::new(-1.4, -0.6, -2.2),
+            Point3::new(-1.3, 0.4, -2.0),
+            Point3::new(-0.9, 0.6, -2.3),
+            Point3::new(0.0, 0.6, -2.4),
+        ],
+        [
+            Point3::new(0.0, 0.6, -2.4),
+            Point3::new(0.9, 0.6, -2.5),
+            Point3::new(1.3, 0.4, -2.2),
+            Point3::new(1.4, -0.6, -2.0),
+        ],
+    ];
+    for points in segments {
+        world.add(Rc::new(Curve::new(
+            points,
+            [0.05, 0.05],
+            CurveKind::Tube,
+            cable.clone(),
+        )));
+    }
+
+    Scene::new(default_camera(), world)
+}
+
+/// A scanned landscape drawn straight from its point cloud.
+///
+/// The scan is read from `models/scan.ply`, and drawn with oriented splats if it has normals and
+/// with small spheres otherwise. If that file does not exist, a hillside with a boulder on it is
+/// made up from noise instead, the ground drawn with splats and the boulder with spheres. Any other
+/// error reading it is returned.
+pub fn scan() -> std::io::Result<Scene> {
+    let gray: Rc<dyn Material> = Rc::new(Lambertian {
+        albedo: Color::new(0.6, 0.6, 0.6),
+    });
+    // Clouds with colors get a white material, which each point tints with its own color.
+    let colored = |cloud: &PointCloud| -> Rc<dyn Material> {
+        match cloud.colors {
+            Some(_) => Rc::new(Lambertian {
+                albedo: Color::new(1.0, 1.0, 1.0),
+            }),
+            None => gray.clone(),
+        }
+    };
+    let mut world = HittableList::new();
+
+    match PointCloud::load("models/scan.ply") {
+        Ok(cloud) => {
+            let shape = if cloud.normals.is_some() {
+                PointShape::Disk
+            } else {
+                PointShape::Sphere
+            };
+            let material = colored(&cloud);
+            world.add(Rc::new(Points::new(cloud, 0.01, shape, material)));
+        }
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            const SIZE: usize = 500;
+
+            let noise = Perlin::new();
+            let height = |x: f64, z: f64| noise.turbulence(Point3::new(x, 0.0, z), 5) * 0.8 - 0.8;
+            let mut ground = PointCloud {
+                positions: Vec::new(),
+                colors: Some(Vec::new()),
+                normals: Some(Vec::new()),
+            };
+            for _ in 0..SIZE * SIZE {
+                let x = raytracing::random_f64_range(-3.0, 3.0);
+                let z = raytracing::random_f64_range(-5.0, -1.0);
+                let y = height(x, z);
+                let slope_x = (height(x + 1e-3, z) - y) / 1e-3;
+                let slope_z = (height(x, z + 1e-3) - y) / 1e-3;
+                let grass = (y + 0.8) / 0.8;
+                ground.positions.push(Point3::new(x, y, z));
+                if let Some(colors) = &mut ground.colors {
+                    colors.push(
+                        Color::new(0.2, 0.35, 0.1) * (1.0 - grass)
+                            + Color::new(0.55, 0.5, 0.35) * grass,
+                    );
+                }
+                if let Some(normals) = &mut ground.normals {
+                    normals.push(Vec3::new(-slope_x, 1.0, -slope_z));
+                }
+            }
+            let material = colored(&ground);
+            world.add(Rc::new(Points::new(
+                ground,
+                0.015,
+                PointShape::Disk,
+                material,
+            )));
+
+            let center = Point3::new(0.3, -0.3, -2.2);
+            let boulder = PointCloud {
+                positions: (0..100_000)
+                    .map(|_| {
+                        let direction = Vec3::random_unit_vector();
+                        let bump = noise.turbulence(direction * 3.0, 4) * 0.1;
+                        center + direction * (0.35 + bump)
+                    })
+                    .collect(),
+                colors: None,
+                normals: None,
+            };
+            world.add(Rc::new(Points::new(
+                boulder,
+                0.005,
+                PointShape::Sphere,
+                gray.clone(),
+            )));
+        }
+        Err(error) => return Err(error),
+    }
+
+    Ok(Scene::new(default_camera(), world))
+}
+
+/// A teapot made of Bézier patches among a few quadric surfaces.
+///
+/// The teapot is read from `models/teapot.bpt`, which is the usual form the Utah teapot is
+/// distributed in. If that file does not exist, a vase is made out of patches instead, and any
+/// other error reading it is returned.
+pub fn quadrics() -> std::io::Result<Scene> {
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.6, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+    world.add(Rc::new(Quadric::ellipsoid(
+        Point3::new(-1.5, -0.3, -2.2),
+        Vec3::new(0.35, 0.3, 0.2),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.7, 0.2, 0.2),
+        }),
+    )));
+    world.add(Rc::new(Quadric::paraboloid(
+        Point3::new(1.5, -0.4, -2.4),
+        0.45,
+        0.4,
+        Rc::new(Metal {
+            albedo: Color::new(0.8, 0.8, 0.85),
+        }),
+    )));
+    world.add(Rc::new(Quadric::hyperboloid(
+        Point3::new(-0.8, -0.1, -3.4),
+        0.25,
+        0.5,
+        1.0,
+        Rc::new(Lambertian {
+            albedo: Color::new(0.8, 0.75, 0.6),
+        }),
+    )));
+
+    let porcelain = Rc::new(Lambertian {
+        albedo: Color::new(0.3, 0.45, 0.7),
+    });
+    let patches = match patches::load_bpt("models/teapot.bpt") {
+        // The teapot stands on the xy plane with z up, and is about three units across.
+        Ok(patches) => patches
+            .into_iter()
+            .map(|patch| {
+                patch.map(|row| {
+                    row.map(|p| Point3::new(p.x, p.z, -p.y) * 0.3 + Point3::new(0.2, -0.6, -2.4))
+                })
+            })
+            .collect(),
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            // Sweep a profile around the vertical axis, one quarter turn at a time. A quarter
+            // circle is close to a cubic Bézier curve with its inner control points this far along
+            // the tangents.
+            const K: f64 = 0.5523;
+
+            let profile = [
+                [(0.3, 0.0), (0.55, 0.2), (0.5, 0.6), (0.25, 0.75)],
+                [(0.25, 0.75), (0.0, 0.9), (0.15, 1.05), (0.3, 1.15)],
+            ];
+            let arc = [(1.0, 0.0), (1.0, K), (K, 1.0), (0.0, 1.0)];
+            let base = Point3::new(0.2, -0.6, -2.4);
+
+            let mut patches = Vec::new();
+            for segment in profile {
+                for quarter in 0..4 {
+                    let (sin, cos) = (quarter as f64 * std::f64::consts::FRAC_PI_2).sin_cos();
+                    patches.push(segment.map(|(radius, height)| {
+                        arc.map(|(a, b)| {
+                            let (x, z) = (a * cos - b * sin, a * sin + b * cos);
+                            base + Point3::new(x * radius, height, z * radius) * 0.9
+                        })
+                    }));
+                }
+            }
+            patches
+        }
+        Err(error) => return Err(error),
+    };
+    for patch in patches {
+        world.add(Rc::new(BezierPatch::new(patch, 1e-3, porcelain.clone())));
+    }
+
+    Ok(Scene::new(default_camera(), world))
+}
+
+/// Subdivision surfaces grown from simple control cages.
+///
+/// From left to right, a cube cage as it is, smoothed with Catmull-Clark subdivision, and smoothed
+/// again with the edges around its top creased, and then an octahedron smoothed with Loop
+/// subdivision. If `models/cage.obj` exists, it is smoothed and shown in the middle instead, and
+/// any error reading it other than it not existing is returned.
+pub fn subdivision() -> std::io::Result<Scene> {
+    let cube = Mesh::new(
+        vec![
+            Point3::new(-1.0, -1.0, -1.0),
+            Point3::new(1.0, -1.0, -1.0),
+            Point3::new(1.0, 1.0, -1.0),
+            Point3::new(-1.0, 1.0, -1.0),
+            Point3::new(-1.0, -1.0, 1.0),
+            Point3::new(1.0, -1.0, 1.0),
+            Point3::new(1.0, 1.0, 1.0),
+            Point3::new(-1.0, 1.0, 1.0),
+        ],
+        vec![
+            vec![0, 3, 2, 1],
+            vec![4, 5, 6, 7],
+            vec![0, 1, 5, 4],
+            vec![2, 3, 7, 6],
+            vec![0, 4, 7, 3],
+            vec![1, 2, 6, 5],
+        ],
+    );
+    let mut creased = cube.clone();
+    for (a, b) in [(2, 3), (3, 7), (7, 6), (6, 2)] {
+        creased.set_crease(a, b, f64::INFINITY);
+    }
+    let octahedron = Mesh::new(
+        vec![
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(0.0, -1.0, 0.0),
+            Point3::new(0.0, 0.0, 1.0),
+            Point3::new(0.0, 0.0, -1.0),
+        ],
+        vec![
+            vec![0, 2, 4],
+            vec![4, 2, 1],
+            vec![1, 2, 5],
+            vec![5, 2, 0],
+            vec![4, 3, 0],
+            vec![1, 3, 4],
+            vec![5, 3, 1],
+            vec![0, 3, 5],
+        ],
+    );
+
+    let place = |mut mesh: Mesh, center: Point3, size: f64| {
+        for position in &mut mesh.positions {
+            *position = center + *position * size;
+        }
+        mesh
+    };
+    let clay = Rc::new(Lambertian {
+        albedo: Color::new(0.75, 0.55, 0.4),
+    });
+
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.6, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+
+    let middle = match Mesh::load_obj("models/cage.obj") {
+        Ok(cage) => cage.subdivide(Subdivision::CatmullClark, 3),
+        Err(error) if error.kind() == ErrorKind::NotFound => place(
+            cube.subdivide(Subdivision::CatmullClark, 3),
+            Point3::new(-0.55, -0.3, -2.4),
+            0.35,
+        ),
+        Err(error) => return Err(error),
+    };
+    let meshes = [
+        place(cube, Point3::new(-1.6, -0.35, -2.4), 0.25),
+        middle,
+        place(
+            creased.subdivide(Subdivision::CatmullClark, 3),
+            Point3::new(0.55, -0.3, -2.4),
+            0.3,
+        ),
+        place(
+            octahedron.subdivide(Subdivision::Loop, 3),
+            Point3::new(1.6, -0.2, -2.4),
+            0.4,
+        ),
+    ];
+    for mesh in meshes {
+        world.add(Rc::new(TriangleMesh::new(&mesh, clay.clone())));
+    }
+
+    Ok(Scene::new(default_camera(), world))
+}
+
+/// Gold, copper and aluminum of increasing roughness, next to clear and frosted glass.
+pub fn microfacets() -> Scene {
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+
+    let spheres: [(f64, Rc<dyn Material>); 5] = [
+        (-2.0, Rc::new(Conductor::gold(0.0))),
+        (-1.0, Rc::new(Conductor::copper(0.3))),
+        (0.0, Rc::new(Conductor::aluminum(0.6))),
+        (1.0, Rc::new(Dielectric::new(1.5, 0.0))),
+        (2.0, Rc::new(Dielectric::new(1.5, 0.3))),
+    ];
+    for (x, material) in spheres {
+        world.add(Rc::new(Sphere::new(
+            Point3::new(x, -0.1, -2.8),
+            0.4,
+            material,
+        )));
+    }
+
+    // Colored balls behind the glass show off how it bends and blurs what is behind it.
+    for (x, color) in [
+        (0.8, Color::new(0.7, 0.1, 0.1)),
+        (1.2, Color::new(0.1, 0.5, 0.1)),
+        (1.8, Color::new(0.1, 0.2, 0.7)),
+        (2.2, Color::new(0.7, 0.6, 0.1)),
+    ] {
+        world.add(Rc::new(Sphere::new(
+            Point3::new(x, -0.35, -3.8),
+            0.15,
+            Rc::new(Lambertian { albedo: color }),
+        )));
+    }
+
+    Scene::new(default_camera(), world)
+}
+
+pub fn principled() -> Scene {
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+
+    // Materials as an exporter would write them, one per sphere.
+    let library = "\
+        newmtl plastic\nKd 0.7 0.1 0.1\nNs 200\nNi 1.5\n\
+        newmtl brass\nKd 0.9 0.7 0.3\nPm 1\nPr 0.35\n\
+        newmtl velvet\nKd 0.25 0.05 0.3\nPr 0.9\nPs 1\n";
+    let mut materials: Vec<Rc<dyn Material>> = raytracing::mtl::parse(library)
+        .and_then(|library| {
+            library
+                .iter()
+                .map(|material| {
+                    Principled::from_mtl(material, ".")
+                        .map(|material| Rc::new(material) as Rc<dyn Material>)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut car_paint = Principled::new(Color::new(0.05, 0.15, 0.6));
+    car_paint.metallic = Rc::new(SolidColor::new(Color::new(0.3, 0.3, 0.3)));
+    car_paint.clearcoat = Rc::new(SolidColor::new(Color::new(1.0, 1.0, 1.0)));
+    materials.push(Rc::new(car_paint));
+
+    let mut wax = Principled::new(Color::new(0.9, 0.8, 0.6));
+    wax.subsurface = Rc::new(SolidColor::new(Color::new(1.0, 1.0, 1.0)));
+    materials.push(Rc::new(wax));
+
+    materials.push(Rc::new(Principled::from_gltf(&GltfMaterial {
+        base_color_factor: Color::new(0.8, 1.0, 0.9),
+        metallic_factor: 0.0,
+        roughness_factor: 0.1,
+        transmission_factor: 1.0,
+        ..GltfMaterial::default()
+    })));
+
+    let count = materials.len();
+    for (index, material) in materials.into_iter().enumerate() {
+        let x = (index as f64 - (count - 1) as f64 / 2.0) * 0.85;
+        world.add(Rc::new(Sphere::new(
+            Point3::new(x, -0.12, -3.0),
+            0.38,
+            material,
+        )));
+    }
+
+    // A checkered sphere behind the glass shows off how it bends what is behind it.
+    world.add(Rc::new(Sphere::new(
+        Point3::new(2.8, -0.3, -4.2),
+        0.2,
+        Rc::new(Principled::from_gltf(&GltfMaterial {
+            base_color_texture: Some(Rc::new(Checker::new(
+                0.1,
+                Color::new(0.9, 0.9, 0.9),
+                Color::new(0.1, 0.1, 0.1),
+            ))),
+            metallic_factor: 0.0,
+            ..GltfMaterial::default()
+        })),
+    )));
+
+    Scene::new(default_camera(), world)
+}
+
+pub fn clay() -> Scene {
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(OrenNayar::new(Color::new(0.45, 0.45, 0.42), 30.0)),
+    )));
+
+    // The same terracotta with increasingly rough surfaces, from plastic looking on the left to
+    // flat and dusty on the right.
+    let terracotta = Color::new(0.7, 0.35, 0.2);
+    let spheres: [(f64, Rc<dyn Material>); 4] = [
+        (-1.5, Rc::new(Lambertian { albedo: terracotta })),
+        (-0.5, Rc::new(OrenNayar::new(terracotta, 15.0))),
+        (0.5, Rc::new(OrenNayar::new(terracotta, 35.0))),
+        (1.5, Rc::new(OrenNayar::new(terracotta, 60.0))),
+    ];
+    for (x, material) in spheres {
+        world.add(Rc::new(Sphere::new(
+            Point3::new(x, -0.05, -2.6),
+            0.45,
+            material,
+        )));
+    }
+
+    Scene::new(default_camera(), world)
+}
+
+pub fn coated() -> Scene {
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+
+    // Car paint, with a mirror-like clear coat over a matte red.
+    let paint = Coated::new(
+        Rc::new(Lambertian {
+            albedo: Color::new(0.6, 0.05, 0.05),
+        }),
+        0.0,
+    );
+
+    // Varnished wood, with an amber coat over rings of pale wood.
+    let mut grain = Principled::new(Color::ZERO);
+    grain.base_color = Rc::new(Multiply {
+        left: Rc::new(NoiseTexture::new(6.0)),
+        right: Rc::new(SolidColor::new(Color::new(1.0, 0.8, 0.6))),
+    });
+    grain.roughness = Rc::new(SolidColor::new(Color::new(0.9, 0.9, 0.9)));
+    let mut varnish = Coated::new(Rc::new(grain), 0.15);
+    varnish.tint = Color::new(0.95, 0.75, 0.45);
+
+    // A glossy coat over brushed copper and over a polished mirror.
+    let lacquer = Coated::new(Rc::new(Conductor::copper(0.5)), 0.05);
+    let mut smoked = Coated::new(
+        Rc::new(Metal {
+            albedo: Color::new(0.9, 0.9, 0.9),
+        }),
+        0.3,
+    );
+    smoked.tint = Color::new(0.4, 0.45, 0.6);
+
+    let spheres: [(f64, Rc<dyn Material>); 4] = [
+        (-1.5, Rc::new(paint)),
+        (-0.5, Rc::new(varnish)),
+        (0.5, Rc::new(lacquer)),
+        (1.5, Rc::new(smoked)),
+    ];
+    for (x, material) in spheres {
+        world.add(Rc::new(Sphere::new(
+            Point3::new(x, -0.05, -2.6),
+            0.45,
+            material,
+        )));
+    }
+
+    Scene::new(default_camera(), world)
+}
+
+pub fn iridescence() -> Scene {
+    let mut world = HittableList::new();
+
+    // An oil slick floating on deep, dark water. A conductor without any extinction reflects the
+    // same as a dielectric, and absorbing everything else stands in for the depth of the water.
+    let mut oil = Conductor::new(Color::new(1.33, 1.33, 1.33), Color::ZERO, 0.0);
+    oil.thin_film = Some(ThinFilm {
+        thickness: 450.0,
+        refractive_index: 1.5,
+    });
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(oil),
+    )));
+
+    // A soap bubble is nothing but a film, with air on both sides.
+    let mut bubble = Dielectric::new(1.0, 0.0);
+    bubble.thin_film = Some(ThinFilm {
+        thickness: 380.0,
+        refractive_index: 1.33,
+    });
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 0.05, -2.6),
+        0.5,
+        Rc::new(bubble),
+    )));
+
+    // Anodized titanium gets its color from how thick the oxide is grown.
+    for (x, thickness) in [(-1.6, 45.0), (1.6, 70.0)] {
+        let mut titanium =
+            Conductor::new(Color::new(2.7, 2.5, 2.2), Color::new(3.8, 3.4, 3.0), 0.2);
+        titanium.thin_film = Some(ThinFilm {
+            thickness,
+            refractive_index: 2.4,
+        });
+        world.add(Rc::new(Sphere::new(
+            Point3::new(x, -0.1, -2.9),
+            0.4,
+            Rc::new(titanium),
+        )));
+    }
+
+    Scene::new(default_camera(), world)
+}
+
+/// Glass which splits white light into colors, rendered spectrally.
+///
+/// A dense flint prism on the left and a cut diamond on the right sit on a checkered floor, whose
+/// edges fringe with color where they are seen through either of them.
+pub fn dispersion() -> Scene {
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Principled::from_gltf(&GltfMaterial {
+            base_color_texture: Some(Rc::new(Checker::new(
+                0.15,
+                Color::new(0.9, 0.9, 0.9),
+                Color::new(0.05, 0.05, 0.05),
+            ))),
+            metallic_factor: 0.0,
+            ..GltfMaterial::default()
+        })),
+    )));
+
+    // Add a solid made of flat triangles, turning each to face away from the center.
+    let mut add_solid =
+        |triangles: Vec<[Point3; 3]>, center: Point3, material: Rc<dyn Material>| {
+            for [a, b, c] in triangles {
+                let outward = (b - a).cross(c - a).dot(a - center) > 0.0;
+                let vertices = if outward { [a, b, c] } else { [a, c, b] };
+                world.add(Rc::new(Triangle::new(vertices, material.clone())));
+            }
+        };
+
+    // A prism lying on its side, with its triangular ends facing left and right.
+    let flint = Rc::new(Dielectric::dispersive(Dispersion::DENSE_FLINT, 0.0));
+    let end = [
+        Point3::new(0.0, -0.5, -1.9),
+        Point3::new(0.0, -0.5, -2.6),
+        Point3::new(0.0, 0.1, -2.25),
+    ];
+    let (left, right) = (Vec3::new(-1.8, 0.0, 0.0), Vec3::new(-0.3, 0.0, 0.0));
+    let [l0, l1, l2] = end.map(|p| p + left);
+    let [r0, r1, r2] = end.map(|p| p + right);
+    add_solid(
+        vec![
+            [l0, l1, l2],
+            [r0, r1, r2],
+            [l0, l1, r1],
+            [l0, r1, r0],
+            [l1, l2, r2],
+            [l1, r2, r1],
+            [l2, l0, r0],
+            [l2, r0, r2],
+        ],
+        (l0 + l1 + l2 + r0 + r1 + r2) / 6.0,
+        flint,
+    );
+
+    // A round brilliant, simplified to eight sides, resting on the point of its pavilion.
+    const SIDES: usize = 8;
+    let center = Point3::new(0.9, 0.0, -2.2);
+    let ring = |radius: f64, height: f64, turn: f64| -> Vec<Point3> {
+        (0..SIDES)
+            .map(|i| {
+                let angle = (i as f64 + turn) * std::f64::consts::TAU / SIDES as f64;
+                center + Vec3::new(radius * angle.cos(), height, radius * angle.sin())
+            })
+            .collect()
+    };
+    let table = ring(0.3, 0.2, 0.5);
+    let girdle = ring(0.55, 0.0, 0.0);
+    let table_center = center + Vec3::new(0.0, 0.2, 0.0);
+    let culet = center + Vec3::new(0.0, -0.5, 0.0);
+    let mut facets = Vec::new();
+    for i in 0..SIDES {
+        let next = (i + 1) % SIDES;
+        facets.push([table_center, table[i], table[next]]);
+        facets.push([table[i], girdle[i], girdle[next]]);
+        facets.push([table[i], girdle[next], table[next]]);
+        facets.push([girdle[i], culet, girdle[next]]);
+    }
+    let diamond = Rc::new(Dielectric::dispersive(Dispersion::DIAMOND, 0.0));
+    add_solid(facets, center, diamond);
+
+    let mut scene = Scene::new(default_camera(), world);
+    scene.spectral = true;
+    scene
+}
+
+/// Marble, skin, wax and milk, which light travels beneath the surface of.
+///
+/// Each has a different mean free path for each channel. Skin lets red light in the furthest,
+/// which gives it its warm glow, and the wax is a rounded block made from a subdivided mesh, with
+/// a dark stone set inside it.
+pub fn subsurface() -> Scene {
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+
+    // The shapes get their material from the subsurface scattering they are filled with.
+    let unused = Rc::new(Lambertian {
+        albedo: Color::ZERO,
+    });
+    let balls = [
+        (
+            -2.0,
+            Color::new(0.9, 0.9, 0.88),
+            Color::new(0.08, 0.08, 0.07),
+        ),
+        (
+            -1.0,
+            Color::new(0.85, 0.55, 0.45),
+            Color::new(0.12, 0.05, 0.03),
+        ),
+        (
+            1.0,
+            Color::new(0.95, 0.93, 0.88),
+            Color::new(0.02, 0.02, 0.015),
+        ),
+    ];
+    for (x, albedo, mean_free_path) in balls {
+        let ball = Rc::new(Sphere::new(Point3::new(x, -0.1, -2.8), 0.4, unused.clone()));
+        let material = Subsurface::new(ball, albedo, mean_free_path);
+        world.add(Rc::new(Translucent::new(material)));
+    }
+
+    let mut block = Mesh::new(
+        vec![
+            Point3::new(-1.0, -1.0, -1.0),
+            Point3::new(1.0, -1.0, -1.0),
+            Point3::new(1.0, 1.0, -1.0),
+            Point3::new(-1.0, 1.0, -1.0),
+            Point3::new(-1.0, -1.0, 1.0),
+            Point3::new(1.0, -1.0, 1.0),
+            Point3::new(1.0, 1.0, 1.0),
+            Point3::new(-1.0, 1.0, 1.0),
+        ],
+        vec![
+            vec![0, 3, 2, 1],
+            vec![4, 5, 6, 7],
+            vec![0, 1, 5, 4],
+            vec![2, 3, 7, 6],
+            vec![0, 4, 7, 3],
+            vec![1, 2, 6, 5],
+        ],
+    )
+    .subdivide(Subdivision::CatmullClark, 3);
+    for position in &mut block.positions {
+        *position = Point3::new(0.0, -0.15, -2.8) + *position * 0.35;
+    }
+    let mut wax = Subsurface::new(
+        Rc::new(TriangleMesh::new(&block, unused)),
+        Color::new(0.9, 0.6, 0.25),
+        Color::new(0.2, 0.12, 0.06),
+    );
+    wax.contents.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -0.15, -2.8),
+        0.15,
+        Rc::new(Lambertian {
+            albedo: Color::new(0.05, 0.04, 0.03),
+        }),
+    )));
+    world.add(Rc::new(Translucent::new(wax)));
+
+    Scene::new(default_camera(), world)
+}
+
+/// Materials layered by masks, and cards with a different material on each side.
+///
+/// From left to right, steel patched with rust, a card showing its front, a card showing its
+/// back, and white paint with a checkered gold decal.
+pub fn layers() -> Scene {
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5),
+        }),
+    )));
+
+    let rust_mask = Rc::new(Multiply {
+        left: Rc::new(NoiseTexture::new(5.0)),
+        right: Rc::new(SolidColor::new(Color::new(1.8, 1.8, 1.8))),
+    });
+    let rusty = Mix::with_texture(
+        Rc::new(Conductor::aluminum(0.2)),
+        Rc::new(OrenNayar::new(Color::new(0.45, 0.18, 0.06), 30.0)),
+        rust_mask,
+    );
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-1.7, -0.1, -2.8),
+        0.4,
+        Rc::new(rusty),
+    )));
+
+    let card = Rc::new(TwoSided {
+        front: Rc::new(Lambertian {
+            albedo: Color::new(0.7, 0.1, 0.1),
+        }),
+        back: Rc::new(Mix::new(
+            Rc::new(Lambertian {
+                albedo: Color::new(0.1, 0.2, 0.6),
+            }),
+            Rc::new(Conductor::gold(0.3)),
+            0.3,
+        )),
+    });
+    // Swapping the sides of the second card turns its back to the camera.
+    let (across, up) = (Vec3::new(0.6, 0.0, 0.2), Vec3::new(0.0, 0.85, 0.0));
+    world.add(Rc::new(Quad::new(
+        Point3::new(-1.0, -0.5, -3.0),
+        across,
+        up,
+        card.clone(),
+    )));
+    world.add(Rc::new(Quad::new(
+        Point3::new(0.2, -0.5, -2.8),
+        up,
+        Vec3::new(0.6, 0.0, -0.2),
+        card,
+    )));
+
+    let decal = Mix::with_texture(
+        Rc::new(Lambertian {
+            albedo: Color::new(0.9, 0.9, 0.9),
+        }),
+        Rc::new(Conductor::gold(0.1)),
+        Rc::new(Checker::new(
+            0.1,
+            Color::new(1.0, 1.0, 1.0),
+            Color::new(0.0, 0.0, 0.0),
+        )),
+    );
+    world.add(Rc::new(Sphere::new(
+        Point3::new(1.7, -0.1, -2.8),
+        0.4,
+        Rc::new(decal),
+    )));
+
+    Scene::new(default_camera(), world)
+}
+
+/// Brushed metals, whose highlights stretch across the grooves left by brushing.
+///
+/// From left to right, an aluminum ball brushed around its equator, a spun aluminum disk brushed
+/// in circles, a copper ball whose grooves turn a quarter between checkered tiles, and a brushed
+/// brass ball made with the principled material.
+pub fn brushed() -> Scene {
+    let mut world = HittableList::new();
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, -0.5, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Principled::from_gltf(&GltfMaterial {
+            base_color_texture: Some(Rc::new(Checker::new(
+                0.15,
+                Color::new(0.9, 0.9, 0.9),
+                Color::new(0.05, 0.05, 0.05),
+            ))),
+            metallic_factor: 0.0,
+            ..GltfMaterial::default()
+        })),
+    )));
+
+    let mut aluminum = Conductor::aluminum(0.0);
+    aluminum.distribution = TrowbridgeReitz::anisotropic(0.05, 0.4);
+    let aluminum = Rc::new(aluminum);
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-2.1, -0.05, -2.8),
+        0.45,
+        aluminum.clone(),
+    )));
+    world.add(Rc::new(Disk::new(
+        Point3::new(-0.7, 0.0, -3.0),
+        Vec3::new(0.0, -0.3, 1.0),
+        0.5,
+        aluminum,
+    )));
+
+    let mut copper = Conductor::copper(0.0);
+    copper.distribution = TrowbridgeReitz::anisotropic(0.05, 0.4);
+    copper.tangent_rotation = Some(Rc::new(Checker::new(
+        0.1,
+        Color::new(0.25, 0.25, 0.25),
+        Color::ZERO,
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.7, -0.05, -2.8),
+        0.45,
+        Rc::new(copper),
+    )));
+
+    let mut brass = Principled::new(Color::new(0.95, 0.8, 0.45));
+    brass.metallic = Rc::new(SolidColor::new(Color::new(1.0, 1.0, 1.0)));
+    brass.roughness = Rc::new(SolidColor::new(Color::new(0.4, 0.4, 0.4)));
+    brass.anisotropic = Rc::new(SolidColor::new(Color::new(0.9, 0.9, 0.9)));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(2.1, -0.05, -2.8),
+        0.45,
+        Rc::new(brass),
+    )));
+
+    Scene::new(default_camera(), world)
+}
@@ -0,0 +1,382 @@
+use std::rc::Rc;
+
+use crate::math::{Point3, Vec3};
+
+/// A signed distance function.
+///
+/// A signed distance function gives, for every point in space, the distance to the nearest point
+/// of some surface. The distance is negative inside the surface and positive outside. Functions
+/// which only ever underestimate the distance are fine too, since all they do is make a ray
+/// marcher take smaller steps.
+///
+/// Any closure taking a point and returning a distance is a signed distance function, so shapes
+/// can be written inline without defining a type for them.
+pub trait Sdf {
+    /// Get the signed distance from the point to the surface.
+    fn distance(&self, point: Point3) -> f64;
+}
+
+impl<F: Fn(Point3) -> f64> Sdf for F {
+    fn distance(&self, point: Point3) -> f64 {
+        self(point)
+    }
+}
+
+/// A sphere centered at the origin.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::math::Point3;
+/// use raytracing::sdf::{self, Sdf};
+///
+/// let sphere = sdf::Sphere { radius: 1.0 };
+///
+/// assert_eq!(sphere.distance(Point3::new(3.0, 0.0, 0.0)), 2.0);
+/// assert_eq!(sphere.distance(Point3::ZERO), -1.0);
+/// ```
+pub struct Sphere {
+    pub radius: f64,
+}
+
+impl Sdf for Sphere {
+    fn distance(&self, point: Point3) -> f64 {
+        point.length() - self.radius
+    }
+}
+
+/// A box centered at the origin, with its edges rounded off by `rounding`.
+pub struct Cuboid {
+    pub half_extents: Vec3,
+    pub rounding: f64,
+}
+
+impl Sdf for Cuboid {
+    fn distance(&self, point: Point3) -> f64 {
+        let q = Vec3::new(
+            point.x.abs() - self.half_extents.x + self.rounding,
+            point.y.abs() - self.half_extents.y + self.rounding,
+            point.z.abs() - self.half_extents.z + self.rounding,
+        );
+        let outside = Vec3::new(q.x.max(0.0), q.y.max(0.0), q.z.max(0.0)).length();
+        let inside = q.x.max(q.y).max(q.z).min(0.0);
+        outside + inside - self.rounding
+    }
+}
+
+/// A torus centered at the origin, lying in the xz plane.
+pub struct Torus {
+    pub major_radius: f64,
+    pub minor_radius: f64,
+}
+
+impl Sdf for Torus {
+    fn distance(&self, point: Point3) -> f64 {
+        let ring = (point.x * point.x + point.z * point.z).sqrt() - self.major_radius;
+        (ring * ring + point.y * point.y).sqrt() - self.minor_radius
+    }
+}
+
+/// The Mandelbulb fractal, a three dimensional relative of the Mandelbrot set.
+///
+/// This is a distance estimate rather than an exact distance, which works well enough for ray
+/// marching as long as the steps are scaled down a little.
+pub struct Mandelbulb {
+    pub power: f64,
+    pub iterations: u32,
+}
+
+impl Sdf for Mandelbulb {
+    fn distance(&self, point: Point3) -> f64 {
+        let mut z = point;
+        let mut derivative = 1.0;
+        let mut radius = z.length();
+
+        for _ in 0..self.iterations {
+            // Points which escape are outside, and the origin stays where it is forever.
+            if !(1e-12..=2.0).contains(&radius) {
+                break;
+            }
+
+            // Raise z to the given power in spherical coordinates, keeping track of the running
+            // derivative for the distance estimate.
+            let theta = (z.z / radius).clamp(-1.0, 1.0).acos() * self.power;
+            let phi = z.y.atan2(z.x) * self.power;
+            derivative = radius.powf(self.power - 1.0) * self.power * derivative + 1.0;
+
+            let scaled = radius.powf(self.power);
+            z = Vec3::new(
+                theta.sin() * phi.cos(),
+                phi.sin() * theta.sin(),
+                theta.cos(),
+            ) * scaled
+                + point;
+            radius = z.length();
+        }
+
+        if radius < 1e-12 {
+            return 0.0;
+        }
+        0.5 * radius.ln() * radius / derivative
+    }
+}
+
+/// A shape moved away from the origin.
+pub struct Translate {
+    pub inner: Rc<dyn Sdf>,
+    pub offset: Vec3,
+}
+
+impl Sdf for Translate {
+    fn distance(&self, point: Point3) -> f64 {
+        self.inner.distance(point - self.offset)
+    }
+}
+
+/// A shape scaled uniformly about the origin.
+pub struct Scale {
+    pub inner: Rc<dyn Sdf>,
+    pub factor: f64,
+}
+
+impl Sdf for Scale {
+    fn distance(&self, point: Point3) -> f64 {
+        self.inner.distance(point / self.factor) * self.factor
+    }
+}
+
+/// Everything inside either of two shapes, with the seam between them filleted.
+///
+/// The `smoothness` is roughly the size of the region over which the shapes blend together. At a
+/// smoothness of zero, this is an ordinary union.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use raytracing::math::Point3;
+/// use raytracing::sdf::{self, Sdf, SmoothUnion};
+///
+/// let blend = SmoothUnion {
+///     left: Rc::new(sdf::Sphere { radius: 1.0 }),
+///     right: Rc::new(|p: Point3| p.y + 1.0),
+///     smoothness: 0.0,
+/// };
+///
+/// assert_eq!(blend.distance(Point3::new(0.0, -3.0, 0.0)), -2.0);
+/// ```
+pub struct SmoothUnion {
+    pub left: Rc<dyn Sdf>,
+    pub right: Rc<dyn Sdf>,
+    pub smoothness: f64,
+}
+
+impl Sdf for SmoothUnion {
+    fn distance(&self, point: Point3) -> f64 {
+        let a = self.left.distance(point);
+        let b = self.right.distance(point);
+        if self.smoothness <= 0.0 {
+            return a.min(b);
+        }
+
+        // This is the polynomial smooth minimum, which only differs from the ordinary minimum
+        // where the two distances are within the smoothness of each other.
+        let h = (self.smoothness - (a - b).abs()).max(0.0) / self.smoothness;
+        a.min(b) - h * h * self.smoothness / 4.0
+    }
+}
+
+/// Infinitely many copies of a shape, laid out on a grid.
+///
+/// The shape is repeated every `period` units along each axis. A period of zero along an axis
+/// leaves that axis alone. For the result to be correct, the shape should fit within a single cell
+/// of the grid.
+pub struct Repeat {
+    pub inner: Rc<dyn Sdf>,
+    pub period: Vec3,
+}
+
+impl Sdf for Repeat {
+    fn distance(&self, point: Point3) -> f64 {
+        let wrap = |x: f64, period: f64| {
+            if period > 0.0 {
+                x - period * (x / period).round()
+            } else {
+                x
+            }
+        };
+
+        self.inner.distance(Point3::new(
+            wrap(point.x, self.period.x),
+            wrap(point.y, self.period.y),
+            wrap(point.z, self.period.z),
+        ))
+    }
+}
+
+/// A shape twisted about the y axis.
+///
+/// Each slice of the shape is rotated by `rate` radians per unit of height. Twisting stretches
+/// space, so the result is only a distance estimate, and a poorer one the faster the twist.
+pub struct Twist {
+    pub inner: Rc<dyn Sdf>,
+    pub rate: f64,
+}
+
+impl Sdf for Twist {
+    fn distance(&self, point: Point3) -> f64 {
+        let (sin, cos) = (self.rate * point.y).sin_cos();
+        self.inner.distance(Point3::new(
+            cos * point.x - sin * point.z,
+            point.y,
+            sin * point.x + cos * point.z,
+        ))
+    }
+}
+
+/// A shape whose surface is pushed out by a displacement function.
+///
+/// Positive displacements grow the shape and negative ones shrink it. Like [`Twist`], this only
+/// gives a distance estimate, which gets worse the faster the displacement varies.
+pub struct Displace {
+    pub inner: Rc<dyn Sdf>,
+    pub displacement: Rc<dyn Fn(Point3) -> f64>,
+}
+
+impl Sdf for Displace {
+    fn distance(&self, point: Point3) -> f64 {
+        self.inner.distance(point) - (self.displacement)(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} instead of {expected}"
+        );
+    }
+
+    #[test]
+    fn cuboid_distances() {
+        let cuboid = Cuboid {
+            half_extents: Vec3::new(1.0, 2.0, 3.0),
+            rounding: 0.0,
+        };
+        assert_near(cuboid.distance(Point3::new(3.0, 0.0, 0.0)), 2.0);
+        assert_near(cuboid.distance(Point3::new(2.0, 3.0, 3.0)), 2.0_f64.sqrt());
+        assert_near(cuboid.distance(Point3::ZERO), -1.0);
+
+        // Rounding pulls the corners in without moving the faces.
+        let rounded = Cuboid {
+            rounding: 0.5,
+            ..cuboid
+        };
+        assert_near(rounded.distance(Point3::new(3.0, 0.0, 0.0)), 2.0);
+        assert_near(
+            rounded.distance(Point3::new(2.0, 3.0, 3.0)),
+            4.75_f64.sqrt() - 0.5,
+        );
+    }
+
+    #[test]
+    fn torus_distances() {
+        let torus = Torus {
+            major_radius: 2.0,
+            minor_radius: 0.5,
+        };
+        assert_near(torus.distance(Point3::new(0.0, 0.0, 2.0)), -0.5);
+        assert_near(torus.distance(Point3::new(0.0, 1.0, 2.0)), 0.5);
+        assert_near(torus.distance(Point3::ZERO), 1.5);
+    }
+
+    #[test]
+    fn mandelbulb_contains_the_origin_and_not_far_points() {
+        let bulb = Mandelbulb {
+            power: 8.0,
+            iterations: 16,
+        };
+        assert!(bulb.distance(Point3::ZERO) <= 0.0);
+        let far = bulb.distance(Point3::new(3.0, 0.0, 0.0));
+        assert!(far > 0.0 && far <= 2.0, "{far}");
+    }
+
+    #[test]
+    fn translate_and_scale_move_the_surface() {
+        let unit: Rc<dyn Sdf> = Rc::new(Sphere { radius: 1.0 });
+        let moved = Translate {
+            inner: unit.clone(),
+            offset: Vec3::new(0.0, 5.0, 0.0),
+        };
+        assert_near(moved.distance(Point3::new(0.0, 5.0, 0.0)), -1.0);
+        assert_near(moved.distance(Point3::ZERO), 4.0);
+
+        let scaled = Scale {
+            inner: unit,
+            factor: 3.0,
+        };
+        assert_near(scaled.distance(Point3::new(5.0, 0.0, 0.0)), 2.0);
+        assert_near(scaled.distance(Point3::ZERO), -3.0);
+    }
+
+    #[test]
+    fn smooth_union_only_blends_near_the_seam() {
+        let blend = SmoothUnion {
+            left: Rc::new(Sphere { radius: 1.0 }),
+            right: Rc::new(Translate {
+                inner: Rc::new(Sphere { radius: 1.0 }),
+                offset: Vec3::new(3.0, 0.0, 0.0),
+            }),
+            smoothness: 0.5,
+        };
+
+        // Far from the seam, it is the nearer of the two shapes.
+        assert_near(blend.distance(Point3::new(-2.0, 0.0, 0.0)), 1.0);
+
+        // Halfway between, both are half a unit away, and the blend bulges toward the point.
+        assert_near(blend.distance(Point3::new(1.5, 0.0, 0.0)), 0.5 - 0.5 / 4.0);
+    }
+
+    #[test]
+    fn repeat_copies_the_shape_along_each_axis() {
+        let grid = Repeat {
+            inner: Rc::new(Sphere { radius: 1.0 }),
+            period: Vec3::new(4.0, 0.0, 4.0),
+        };
+        assert_near(grid.distance(Point3::new(8.0, 0.0, -4.0)), -1.0);
+        assert_near(grid.distance(Point3::new(6.0, 0.0, 0.0)), 1.0);
+
+        // A period of zero leaves the axis alone.
+        assert_near(grid.distance(Point3::new(0.0, 4.0, 0.0)), 3.0);
+    }
+
+    #[test]
+    fn twist_turns_each_slice_about_the_y_axis() {
+        let bar = Twist {
+            inner: Rc::new(Cuboid {
+                half_extents: Vec3::new(2.0, 10.0, 0.5),
+                rounding: 0.0,
+            }),
+            rate: std::f64::consts::FRAC_PI_2,
+        };
+
+        // The bar lies along x at the bottom, and a quarter turn later, along z.
+        assert!(bar.distance(Point3::new(1.5, 0.0, 0.0)) < 0.0);
+        assert!(bar.distance(Point3::new(1.5, 1.0, 0.0)) > 0.0);
+        assert!(bar.distance(Point3::new(0.0, 1.0, 1.5)) < 0.0);
+    }
+
+    #[test]
+    fn displace_pushes_the_surface_out() {
+        let bumpy = Displace {
+            inner: Rc::new(Sphere { radius: 1.0 }),
+            displacement: Rc::new(|point: Point3| if point.x > 0.0 { 0.5 } else { -0.5 }),
+        };
+        assert_near(bumpy.distance(Point3::new(2.0, 0.0, 0.0)), 0.5);
+        assert_near(bumpy.distance(Point3::new(-2.0, 0.0, 0.0)), 1.5);
+    }
+}
@@ -0,0 +1,325 @@
+use std::sync::LazyLock;
+
+use crate::color::Color;
+use crate::math::Vec3;
+
+/// The shortest wavelength traced, in nanometers.
+pub const MIN_WAVELENGTH: f64 = 380.0;
+
+/// The longest wavelength traced, in nanometers.
+pub const MAX_WAVELENGTH: f64 = 720.0;
+
+/// The number of wavelengths each path carries.
+pub const WAVELENGTH_COUNT: usize = 3;
+
+/// Evaluate one lobe of the piecewise Gaussian fit to the CIE color matching functions.
+fn lobe(wavelength: f64, mean: f64, below: f64, above: f64) -> f64 {
+    let deviation = if wavelength < mean { below } else { above };
+    let t = (wavelength - mean) / deviation;
+    (-0.5 * t * t).exp()
+}
+
+/// Evaluate the CIE 1931 color matching functions at a wavelength in nanometers.
+///
+/// This uses the multi-lobe fit of Wyman, Sloan and Shirley, which is within a few percent of the
+/// tabulated functions everywhere. The components are the `x`, `y` and `z` functions.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::spectrum;
+///
+/// // The eye is most sensitive to green light.
+/// assert!(spectrum::color_matching(555.0).y > 0.99);
+/// assert!(spectrum::color_matching(450.0).y < 0.1);
+/// ```
+pub fn color_matching(wavelength: f64) -> Vec3 {
+    let l = wavelength;
+    Vec3::new(
+        1.056 * lobe(l, 599.8, 37.9, 31.0) + 0.362 * lobe(l, 442.0, 16.0, 26.7)
+            - 0.065 * lobe(l, 501.1, 20.4, 26.2),
+        0.821 * lobe(l, 568.8, 46.9, 40.5) + 0.286 * lobe(l, 530.9, 16.3, 31.1),
+        1.217 * lobe(l, 437.0, 11.8, 36.0) + 0.681 * lobe(l, 459.0, 26.0, 13.8),
+    )
+}
+
+/// Convert a CIE XYZ color to linear sRGB.
+pub fn xyz_to_linear_srgb(xyz: Vec3) -> Color {
+    Color::new(
+        3.2406 * xyz.x - 1.5372 * xyz.y - 0.4986 * xyz.z,
+        -0.9689 * xyz.x + 1.8758 * xyz.y + 0.0415 * xyz.z,
+        0.0557 * xyz.x - 0.2040 * xyz.y + 1.0570 * xyz.z,
+    )
+}
+
+/// The spectra Smits' method builds every color out of, sampled in ten even bins over the
+/// traced wavelengths.
+const SMITS_WHITE: [f64; 10] = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const SMITS_CYAN: [f64; 10] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const SMITS_MAGENTA: [f64; 10] = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const SMITS_YELLOW: [f64; 10] = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const SMITS_RED: [f64; 10] = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const SMITS_GREEN: [f64; 10] = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const SMITS_BLUE: [f64; 10] = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+/// Get the value of a smooth spectrum with the given linear sRGB color at a wavelength.
+///
+/// This is Smits' method, which adds up a white spectrum, one of cyan, magenta or yellow, and one
+/// of red, green or blue. The spectra are smooth and stay between 0 and 1 for colors that do,
+/// which suits the reflectance of surfaces.
+///
+/// # Examples
+///
+/// ```
+/// use raytracing::color::Color;
+/// use raytracing::spectrum;
+///
+/// let red = Color::new(1.0, 0.0, 0.0);
+///
+/// assert!(spectrum::rgb_to_spectrum(red, 680.0) > 0.9);
+/// assert!(spectrum::rgb_to_spectrum(red, 450.0) < 0.1);
+/// ```
+pub fn rgb_to_spectrum(rgb: Color, wavelength: f64) -> f64 {
+    let position = (wavelength - MIN_WAVELENGTH) / (MAX_WAVELENGTH - MIN_WAVELENGTH) * 10.0;
+    let bin = (position.max(0.0) as usize).min(9);
+    let (r, g, b) = (rgb.x, rgb.y, rgb.z);
+
+    if r <= g && r <= b {
+        let mut value = r * SMITS_WHITE[bin];
+        if g <= b {
+            value += (g - r) * SMITS_CYAN[bin] + (b - g) * SMITS_BLUE[bin];
+        } else {
+            value += (b - r) * SMITS_CYAN[bin] + (g - b) * SMITS_GREEN[bin];
+        }
+        value
+    } else if g <= r && g <= b {
+        let mut value = g * SMITS_WHITE[bin];
+        if r <= b {
+            value += (r - g) * SMITS_MAGENTA[bin] + (b - r) * SMITS_BLUE[bin];
+        } else {
+            value += (b - g) * SMITS_MAGENTA[bin] + (r - b) * SMITS_RED[bin];
+        }
+        value
+    } else {
+        let mut value = b * SMITS_WHITE[bin];
+        if r <= g {
+            value += (r - b) * SMITS_YELLOW[bin] + (g - r) * SMITS_GREEN[bin];
+        } else {
+            value += (g - b) * SMITS_YELLOW[bin] + (r - g) * SMITS_RED[bin];
+        }
+        value
+    }
+}
+
+/// The integral of the `y` color matching function over the traced wavelengths, and the linear
+/// sRGB color of a constant spectrum of 1 before white balancing.
+static NORMALIZATION: LazyLock<(f64, Color)> = LazyLock::new(|| {
+    let mut xyz = Vec3::ZERO;
+    let mut wavelength = MIN_WAVELENGTH + 0.5;
+    while wavelength < MAX_WAVELENGTH {
+        xyz += color_matching(wavelength);
+        wavelength += 1.0;
+    }
+    (xyz.y, xyz_to_linear_srgb(xyz / xyz.y))
+});
+
+/// The wavelengths a single path is traced at.
+///
+/// The first is the hero wavelength, picked uniformly at random, and the others are spread evenly
+/// from it across the traced range. While rendering spectrally, the three channels of a
+/// [`Color`] carried along a path hold the values at these wavelengths rather than red, green and
+/// blue.
+///
+/// Where light of different wavelengths goes different ways, as it does when glass disperses it,
+/// only the hero wavelength can be followed. The others are then terminated and their
+/// contribution is made up for by counting the hero wavelength more.
+#[derive(Clone, Copy)]
+pub struct Wavelengths {
+    pub wavelengths: [f64; WAVELENGTH_COUNT],
+    pub secondary_terminated: bool,
+}
+
+impl Wavelengths {
+    /// Pick the wavelengths for a path from a random number between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::spectrum::Wavelengths;
+    ///
+    /// let wavelengths = Wavelengths::sample(0.5);
+    ///
+    /// assert_eq!(wavelengths.wavelengths[0], 550.0);
+    /// assert!(wavelengths.wavelengths.iter().all(|&w| (380.0..720.0).contains(&w)));
+    /// ```
+    pub fn sample(u: f64) -> Self {
+        let range = MAX_WAVELENGTH - MIN_WAVELENGTH;
+        Self {
+            wavelengths: std::array::from_fn(|i| {
+                let offset = (u + i as f64 / WAVELENGTH_COUNT as f64).fract();
+                MIN_WAVELENGTH + offset * range
+            }),
+            secondary_terminated: false,
+        }
+    }
+
+    /// Get the hero wavelength, which every path can follow.
+    pub fn hero(&self) -> f64 {
+        self.wavelengths[0]
+    }
+
+    /// Get the values of the spectrum of a color at each of the wavelengths.
+    pub fn upsample(&self, rgb: Color) -> Color {
+        self.map(|wavelength| rgb_to_spectrum(rgb, wavelength))
+    }
+
+    /// Evaluate a quantity which depends on the wavelength at each of the wavelengths.
+    pub fn map<F>(&self, f: F) -> Color
+    where
+        F: Fn(f64) -> f64,
+    {
+        let [a, b, c] = self.wavelengths.map(f);
+        Color::new(a, b, c)
+    }
+
+    /// Drop every wavelength but the hero from here on.
+    pub fn terminate_secondary(&mut self) {
+        self.secondary_terminated = true;
+    }
+
+    /// Keep only the value carried at the hero wavelength, scaled up to make up for the others.
+    pub fn hero_only(values: Color) -> Color {
+        Color::new(values.x * WAVELENGTH_COUNT as f64, 0.0, 0.0)
+    }
+
+    /// Convert the values carried at each of the wavelengths to linear sRGB.
+    ///
+    /// The result is white balanced so that a constant spectrum of 1 comes out white, which makes
+    /// spectral and RGB renders of the same scene match.
+    pub fn to_rgb(&self, values: Color) -> Color {
+        let (y_integral, white) = *NORMALIZATION;
+        let values = [values.x, values.y, values.z];
+        let range = MAX_WAVELENGTH - MIN_WAVELENGTH;
+
+        let xyz = self
+            .wavelengths
+            .iter()
+            .zip(values)
+            .fold(Vec3::ZERO, |xyz, (&wavelength, value)| {
+                xyz + color_matching(wavelength) * value
+            })
+            * (range / (WAVELENGTH_COUNT as f64 * y_integral));
+
+        let rgb = xyz_to_linear_srgb(xyz);
+        Color::new(rgb.x / white.x, rgb.y / white.y, rgb.z / white.z)
+    }
+}
+
+/// How the refractive index of a transparent material varies with wavelength.
+///
+/// Wavelengths are given in nanometers, though both formulas are stated for micrometers.
+#[derive(Clone, Copy)]
+pub enum Dispersion {
+    /// Cauchy's equation, `n = a + b / λ^2`, which is good enough for most glasses in the visible
+    /// range.
+    Cauchy { a: f64, b: f64 },
+
+    /// The Sellmeier equation, `n^2 = 1 + Σ b λ^2 / (λ^2 - c)`, which glass makers publish
+    /// coefficients for.
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    /// Schott BK7, the most common optical glass.
+    pub const BK7: Self = Self::Sellmeier {
+        b: [1.039_612_12, 0.231_792_344, 1.010_469_45],
+        c: [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+    };
+
+    /// Schott SF11, a dense flint glass which spreads colors far apart, as used for prisms.
+    pub const DENSE_FLINT: Self = Self::Sellmeier {
+        b: [1.737_596_95, 0.313_747_346, 1.898_781_01],
+        c: [0.013_188_707, 0.062_306_814_2, 155.236_29],
+    };
+
+    /// Diamond, whose strong dispersion is what makes it sparkle in colors.
+    pub const DIAMOND: Self = Self::Sellmeier {
+        b: [0.3306, 4.3356, 0.0],
+        c: [0.030_625, 0.011_236, 0.0],
+    };
+
+    /// The wavelength of the helium d line, where refractive indices are usually quoted.
+    pub const D_LINE: f64 = 587.6;
+
+    /// Get the refractive index at a wavelength in nanometers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use raytracing::spectrum::Dispersion;
+    ///
+    /// let bk7 = Dispersion::BK7;
+    ///
+    /// assert!((bk7.index(Dispersion::D_LINE) - 1.5168).abs() < 1e-4);
+    /// // Blue light is bent more than red light.
+    /// assert!(bk7.index(450.0) > bk7.index(650.0));
+    /// ```
+    pub fn index(&self, wavelength: f64) -> f64 {
+        let micrometers = wavelength / 1000.0;
+        let l2 = micrometers * micrometers;
+        match *self {
+            Self::Cauchy { a, b } => a + b / l2,
+            Self::Sellmeier { b, c } => {
+                let sum: f64 = b.iter().zip(c).map(|(&b, c)| b * l2 / (l2 - c)).sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Average the colors of a spectrum traced at evenly spread wavelengths.
+    fn average_rgb(values: impl Fn(Wavelengths) -> Color) -> Color {
+        const SAMPLES: usize = 1000;
+
+        let sum = (0..SAMPLES).fold(Vec3::ZERO, |sum, i| {
+            let wavelengths = Wavelengths::sample((i as f64 + 0.5) / SAMPLES as f64);
+            sum + wavelengths.to_rgb(values(wavelengths))
+        });
+        sum / SAMPLES as f64
+    }
+
+    #[test]
+    fn constant_spectrum_comes_out_white() {
+        let white = average_rgb(|_| Color::new(1.0, 1.0, 1.0));
+        assert!((white - Color::new(1.0, 1.0, 1.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn hero_only_keeps_the_expected_value() {
+        let values = Color::new(0.2, 0.5, 0.7);
+        let hero = Wavelengths::hero_only(values);
+        assert!((hero - Color::new(0.6, 0.0, 0.0)).length() < 1e-12);
+
+        // Over every hero wavelength, the hero alone sees as much light as all of them together.
+        let white = average_rgb(|_| Wavelengths::hero_only(Color::new(1.0, 1.0, 1.0)));
+        assert!((white - Color::new(1.0, 1.0, 1.0)).length() < 1e-3);
+    }
+}
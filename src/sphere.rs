@@ -1,9 +1,12 @@
 use std::cmp::Ordering;
 use std::rc::Rc;
 
-use raytracing::math::Point3;
+use std::f64::consts::PI;
+
+use raytracing::math::aabb::Aabb;
 use raytracing::math::interval::Interval;
 use raytracing::math::ray::Ray;
+use raytracing::math::{Point3, Vec3};
 
 use crate::hittable::{HitRecord, Hittable};
 use crate::renderer::material::Material;
@@ -37,6 +40,28 @@ impl Sphere {
             material,
         }
     }
+
+    /// Compute the surface coordinates and their derivatives at a point on the unit sphere.
+    ///
+    /// The `u` coordinate wraps around the y axis starting from `-x`, and the `v` coordinate runs
+    /// from the bottom pole to the top pole. The derivatives are those of the unit sphere, and so
+    /// need to be scaled by the radius.
+    pub(crate) fn surface_coordinates(point: Point3) -> (f64, f64, Vec3, Vec3) {
+        let theta = (-point.y).clamp(-1.0, 1.0).acos();
+        let phi = (-point.z).atan2(point.x) + PI;
+
+        // The derivative with respect to v degenerates at the poles, so the sine is kept away from
+        // zero to avoid dividing by it.
+        let sin_theta = theta.sin().max(1e-8);
+        let dpdu = Vec3::new(point.z, 0.0, -point.x) * (2.0 * PI);
+        let dpdv = Vec3::new(
+            -point.y * point.x / sin_theta,
+            sin_theta,
+            -point.y * point.z / sin_theta,
+        ) * PI;
+
+        (phi / (2.0 * PI), theta / PI, dpdu, dpdv)
+    }
 }
 
 impl Hittable for Sphere {
@@ -65,9 +90,15 @@ impl Hittable for Sphere {
 
         // Filling up the hit record with information. This is not a good comment.
         let hit_point = ray.at(root);
+        let outward_normal = (hit_point - self.center) / self.radius;
+        let (u, v, dpdu, dpdv) = Self::surface_coordinates(outward_normal);
         let mut record = HitRecord {
             point: hit_point,
-            normal: (hit_point - self.center) / self.radius,
+            normal: outward_normal,
+            u,
+            v,
+            dpdu: dpdu * self.radius,
+            dpdv: dpdv * self.radius,
             material: self.material.clone(),
             time: root,
             front_face: false,
@@ -76,4 +107,9 @@ impl Hittable for Sphere {
         record.set_face_normal(ray, record.normal);
         Some(record)
     }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::from_points(self.center - extent, self.center + extent)
+    }
 }
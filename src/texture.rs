@@ -0,0 +1,298 @@
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::color::{self, Color};
+use crate::image::pixmap::PixmapImage;
+use crate::math::interval::Interval;
+use crate::math::{Point3, Vec3};
+
+/// A spatially varying quantity which can be looked up on a surface.
+///
+/// Textures are queried with the surface coordinates `(u, v)` of a hit as well as the point in
+/// space where the hit occurred, so that both image based and solid textures fit the same
+/// interface.
+pub trait Texture {
+    /// Look up the value of the texture at the given coordinates.
+    fn value(&self, u: f64, v: f64, point: Point3) -> Color;
+}
+
+/// A texture which has the same value everywhere.
+pub struct SolidColor {
+    pub albedo: Color,
+}
+
+impl SolidColor {
+    /// Create a solid texture from a color.
+    pub fn new(albedo: Color) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f64, _v: f64, _point: Point3) -> Color {
+        self.albedo
+    }
+}
+
+/// A solid checkerboard alternating between two textures.
+///
+/// The pattern is determined by the point in space rather than the surface coordinates, so it is
+/// seamless on any shape. The scale is the side length of a single cell.
+pub struct Checker {
+    pub inverse_scale: f64,
+    pub even: Rc<dyn Texture>,
+    pub odd: Rc<dyn Texture>,
+}
+
+impl Checker {
+    /// Create a checkerboard from two colors.
+    pub fn new(scale: f64, even: Color, odd: Color) -> Self {
+        Self {
+            inverse_scale: 1.0 / scale,
+            even: Rc::new(SolidColor::new(even)),
+            odd: Rc::new(SolidColor::new(odd)),
+        }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f64, v: f64, point: Point3) -> Color {
+        let x = (self.inverse_scale * point.x).floor() as i64;
+        let y = (self.inverse_scale * point.y).floor() as i64;
+        let z = (self.inverse_scale * point.z).floor() as i64;
+
+        if (x + y + z) % 2 == 0 {
+            self.even.value(u, v, point)
+        } else {
+            self.odd.value(u, v, point)
+        }
+    }
+}
+
+/// A texture backed by an image.
+///
+/// The surface coordinates are mapped onto the image so that `(0, 0)` is the bottom left corner
+/// and `(1, 1)` is the top right corner. Coordinates outside of the unit square are clamped.
+pub struct ImageTexture {
+    image: PixmapImage,
+    gamma_encoded: bool,
+}
+
+impl ImageTexture {
+    /// Load a color texture from a PPM file.
+    ///
+    /// The image data is assumed to be gamma encoded, like the images the renderer produces, and
+    /// is converted back to linear space on lookup.
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(Self {
+            image: PixmapImage::load(path)?,
+            gamma_encoded: true,
+        })
+    }
+
+    /// Load a data texture from a PPM file.
+    ///
+    /// Unlike [`ImageTexture::open`], the stored values are returned as they are. This is what
+    /// normal maps, height maps and masks expect.
+    pub fn open_linear<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(Self {
+            image: PixmapImage::load(path)?,
+            gamma_encoded: false,
+        })
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _point: Point3) -> Color {
+        const UNIT: Interval = Interval::new(0.0, 1.0);
+
+        let (width, height) = (self.image.width(), self.image.height());
+        if width == 0 || height == 0 {
+            return Color::new(0.0, 1.0, 1.0);
+        }
+
+        // Image rows are stored from the top down, so v has to be flipped.
+        let u = UNIT.clamp(u);
+        let v = 1.0 - UNIT.clamp(v);
+        let x = ((u * width as f64) as u16).min(width - 1);
+        let y = ((v * height as f64) as u16).min(height - 1);
+
+        let (r, g, b) = self.image.pixel(x, y);
+        let decode = |component: u8| {
+            let component = component as f64 / 255.0;
+            if self.gamma_encoded {
+                color::gamma_to_linear(component)
+            } else {
+                component
+            }
+        };
+
+        Color::new(decode(r), decode(g), decode(b))
+    }
+}
+
+/// Ken Perlin's gradient noise.
+///
+/// The noise is built from a lattice of random unit vectors which are interpolated smoothly, so
+/// the result varies continuously in space. Its values lie roughly in `[-1, 1]`.
+pub struct Perlin {
+    gradients: Box<[Vec3]>,
+    permutation_x: Box<[usize]>,
+    permutation_y: Box<[usize]>,
+    permutation_z: Box<[usize]>,
+}
+
+impl Perlin {
+    const POINT_COUNT: usize = 256;
+
+    /// Create a new noise generator with a random lattice.
+    pub fn new() -> Self {
+        let gradients = (0..Self::POINT_COUNT)
+            .map(|_| Vec3::random_range(-1.0, 1.0).unit_vector())
+            .collect();
+
+        Self {
+            gradients,
+            permutation_x: Self::generate_permutation(),
+            permutation_y: Self::generate_permutation(),
+            permutation_z: Self::generate_permutation(),
+        }
+    }
+
+    fn generate_permutation() -> Box<[usize]> {
+        let mut permutation: Box<[usize]> = (0..Self::POINT_COUNT).collect();
+        for i in (1..Self::POINT_COUNT).rev() {
+            let target = rand::random_range(0..=i);
+            permutation.swap(i, target);
+        }
+        permutation
+    }
+
+    /// Sample the noise at a point.
+    pub fn noise(&self, point: Point3) -> f64 {
+        let floor = |x: f64| (x.floor(), x - x.floor());
+        let (fx, u) = floor(point.x);
+        let (fy, v) = floor(point.y);
+        let (fz, w) = floor(point.z);
+        let (i, j, k) = (fx as i64, fy as i64, fz as i64);
+
+        // Hermite smoothing hides the lattice from the final result.
+        let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+        let (uu, vv, ww) = (smooth(u), smooth(v), smooth(w));
+
+        let mask = |x: i64| (x & (Self::POINT_COUNT as i64 - 1)) as usize;
+        let mut accumulated = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let gradient = self.gradients[self.permutation_x[mask(i + di)]
+                        ^ self.permutation_y[mask(j + dj)]
+                        ^ self.permutation_z[mask(k + dk)]];
+                    let (di, dj, dk) = (di as f64, dj as f64, dk as f64);
+                    let weight = Vec3::new(u - di, v - dj, w - dk);
+
+                    accumulated += (di * uu + (1.0 - di) * (1.0 - uu))
+                        * (dj * vv + (1.0 - dj) * (1.0 - vv))
+                        * (dk * ww + (1.0 - dk) * (1.0 - ww))
+                        * gradient.dot(weight);
+                }
+            }
+        }
+        accumulated
+    }
+
+    /// Sum several octaves of noise, each at double the frequency and half the amplitude of the
+    /// last.
+    pub fn turbulence(&self, point: Point3, depth: u32) -> f64 {
+        let mut accumulated = 0.0;
+        let mut point = point;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accumulated += weight * self.noise(point);
+            weight *= 0.5;
+            point *= 2.0;
+        }
+        accumulated.abs()
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A grayscale texture made of turbulent Perlin noise.
+pub struct NoiseTexture {
+    pub noise: Perlin,
+    pub scale: f64,
+}
+
+impl NoiseTexture {
+    /// Create a noise texture whose features are roughly `1 / scale` units across.
+    pub fn new(scale: f64) -> Self {
+        Self {
+            noise: Perlin::new(),
+            scale,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, point: Point3) -> Color {
+        Color::new(1.0, 1.0, 1.0) * self.noise.turbulence(point * self.scale, 7)
+    }
+}
+
+/// A single channel of another texture, spread over all three.
+///
+/// Material formats often pack several grayscale maps into the channels of one image, like the
+/// roughness and metalness maps of glTF, and this picks one of them back out. Channels 0, 1 and 2
+/// are red, green and blue.
+pub struct Channel {
+    pub texture: Rc<dyn Texture>,
+    pub channel: usize,
+}
+
+impl Texture for Channel {
+    fn value(&self, u: f64, v: f64, point: Point3) -> Color {
+        let color = self.texture.value(u, v, point);
+        let value = [color.x, color.y, color.z][self.channel.min(2)];
+        Color::new(value, value, value)
+    }
+}
+
+/// The product of two textures, channel by channel.
+///
+/// This is mostly useful for scaling an image by a constant factor, as material formats tend to
+/// do.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use raytracing::color::Color;
+/// use raytracing::math::Point3;
+/// use raytracing::texture::{Multiply, SolidColor, Texture};
+///
+/// let product = Multiply {
+///     left: Rc::new(SolidColor::new(Color::new(0.5, 1.0, 1.0))),
+///     right: Rc::new(SolidColor::new(Color::new(0.5, 0.5, 0.0))),
+/// };
+///
+/// assert_eq!(product.value(0.0, 0.0, Point3::ZERO).x, 0.25);
+/// ```
+pub struct Multiply {
+    pub left: Rc<dyn Texture>,
+    pub right: Rc<dyn Texture>,
+}
+
+impl Texture for Multiply {
+    fn value(&self, u: f64, v: f64, point: Point3) -> Color {
+        let a = self.left.value(u, v, point);
+        let b = self.right.value(u, v, point);
+        Color::new(a.x * b.x, a.y * b.y, a.z * b.z)
+    }
+}
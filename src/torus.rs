@@ -0,0 +1,114 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::frame::Frame;
+use raytracing::math::interval::Interval;
+use raytracing::math::polynomial;
+use raytracing::math::ray::Ray;
+use raytracing::math::{Point3, Vec3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// Represents a ray traceable torus.
+///
+/// The torus is the surface swept out by a circle of radius `minor_radius` whose center travels
+/// around a circle of radius `major_radius` about the axis. The `u` coordinate goes once around
+/// the axis, and the `v` coordinate goes once around the tube, starting from the outer equator.
+#[derive(Clone)]
+pub struct Torus {
+    pub frame: Frame,
+    pub major_radius: f64,
+    pub minor_radius: f64,
+    pub material: Rc<dyn Material>,
+}
+
+impl Torus {
+    /// Create a new torus from its center, axis and radii.
+    pub fn new(
+        center: Point3,
+        axis: Vec3,
+        major_radius: f64,
+        minor_radius: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            frame: Frame::from_axis(center, axis),
+            major_radius: major_radius.max(0.0),
+            minor_radius: minor_radius.max(0.0),
+            material,
+        }
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        // Cheaply rule out rays which do not come near the torus before solving the quartic.
+        if !self.bounding_box().hit(ray, interval) {
+            return None;
+        }
+
+        // The quartic is much better conditioned with a unit direction, so distances along the
+        // local ray are scaled with respect to those along the original one.
+        let local = self.frame.ray_to_local(ray);
+        let scale = local.direction.length();
+        let (o, d) = (local.origin, local.direction / scale);
+
+        // Substituting the ray into (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + y^2) gives a quartic in t.
+        let r2 = self.major_radius * self.major_radius;
+        let m = d.length_squared();
+        let n = o.dot(d);
+        let q = o.length_squared() + r2 - self.minor_radius * self.minor_radius;
+        let root = polynomial::solve_quartic(
+            m * m,
+            4.0 * m * n,
+            4.0 * n * n + 2.0 * m * q - 4.0 * r2 * (d.x * d.x + d.y * d.y),
+            4.0 * n * q - 8.0 * r2 * (o.x * d.x + o.y * d.y),
+            q * q - 4.0 * r2 * (o.x * o.x + o.y * o.y),
+        )
+        .into_iter()
+        .map(|t| t / scale)
+        .find(|&t| interval.surrounds(t))?;
+
+        let p = local.at(root);
+        let radial = (p.x * p.x + p.y * p.y).sqrt();
+        let phi = p.y.atan2(p.x).rem_euclid(2.0 * PI);
+        let theta = p.z.atan2(radial - self.major_radius).rem_euclid(2.0 * PI);
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let (sin_theta, cos_theta) = theta.sin_cos();
+
+        // The normal is the gradient of the implicit function defining the torus.
+        let sum = p.length_squared() + r2 - self.minor_radius * self.minor_radius;
+        let outward_normal = p * (4.0 * sum) - Vec3::new(p.x, p.y, 0.0) * (8.0 * r2);
+        let outward_normal = self.frame.vector_to_world(outward_normal.unit_vector());
+
+        let ring_radius = self.major_radius + self.minor_radius * cos_theta;
+        let dpdu = Vec3::new(-sin_phi, cos_phi, 0.0) * (2.0 * PI * ring_radius);
+        let dpdv = Vec3::new(-sin_theta * cos_phi, -sin_theta * sin_phi, cos_theta)
+            * (2.0 * PI * self.minor_radius);
+
+        let mut record = HitRecord {
+            point: ray.at(root),
+            normal: outward_normal,
+            u: phi / (2.0 * PI),
+            v: theta / (2.0 * PI),
+            dpdu: self.frame.vector_to_world(dpdu),
+            dpdv: self.frame.vector_to_world(dpdv),
+            material: self.material.clone(),
+            time: root,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, outward_normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let outer = self.major_radius + self.minor_radius;
+        self.frame.box_to_world(Aabb::from_points(
+            Point3::new(-outer, -outer, -self.minor_radius),
+            Point3::new(outer, outer, self.minor_radius),
+        ))
+    }
+}
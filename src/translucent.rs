@@ -0,0 +1,41 @@
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+use crate::renderer::subsurface::Subsurface;
+
+/// A closed shape filled with a translucent material.
+///
+/// The shape is the boundary of the [`Subsurface`] material, so the walk beneath the surface
+/// traces the very same shape that is put in the scene. Whatever material the shape was made with
+/// is replaced.
+pub struct Translucent {
+    boundary: Rc<dyn Hittable>,
+    material: Rc<dyn Material>,
+}
+
+impl Translucent {
+    /// Put a subsurface material in the scene on its own boundary.
+    pub fn new(material: Subsurface) -> Self {
+        Self {
+            boundary: material.boundary.clone(),
+            material: Rc::new(material),
+        }
+    }
+}
+
+impl Hittable for Translucent {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let mut hit = self.boundary.hit(ray, interval)?;
+        hit.material = self.material.clone();
+        Some(hit)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
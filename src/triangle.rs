@@ -0,0 +1,127 @@
+use std::rc::Rc;
+
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{self, Point3};
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+
+/// Represents a ray traceable triangle.
+///
+/// Each vertex carries its own surface coordinates, which are interpolated across the face. The
+/// front face is the one from which the vertices appear in counterclockwise order.
+#[derive(Clone)]
+pub struct Triangle {
+    pub vertices: [Point3; 3],
+    pub uvs: [(f64, f64); 3],
+    pub material: Rc<dyn Material>,
+}
+
+impl Triangle {
+    /// Create a new triangle.
+    ///
+    /// The vertices are given the surface coordinates `(0, 0)`, `(1, 0)` and `(0, 1)`
+    /// respectively.
+    pub fn new(vertices: [Point3; 3], material: Rc<dyn Material>) -> Self {
+        Self::with_uvs(vertices, [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)], material)
+    }
+
+    /// Create a new triangle with explicit surface coordinates at each vertex.
+    pub fn with_uvs(
+        vertices: [Point3; 3],
+        uvs: [(f64, f64); 3],
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            vertices,
+            uvs,
+            material,
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let (root, b1, b2) = intersect(self.vertices, ray)?;
+        if !interval.surrounds(root) {
+            return None;
+        }
+
+        let [p0, p1, p2] = self.vertices;
+        let edge1 = p1 - p0;
+        let edge2 = p2 - p0;
+
+        let [(u0, v0), (u1, v1), (u2, v2)] = self.uvs;
+        let b0 = 1.0 - b1 - b2;
+        let outward_normal = edge1.cross(edge2).unit_vector();
+
+        // The tangents follow from writing both edges in terms of the change in surface
+        // coordinates along them. If the coordinates are degenerate, any frame will do.
+        let (du1, dv1, du2, dv2) = (u1 - u0, v1 - v0, u2 - u0, v2 - v0);
+        let uv_determinant = du1 * dv2 - dv1 * du2;
+        let (dpdu, dpdv) = if uv_determinant.abs() < 1e-12 {
+            math::orthonormal_basis(outward_normal)
+        } else {
+            (
+                (edge1 * dv2 - edge2 * dv1) / uv_determinant,
+                (edge2 * du1 - edge1 * du2) / uv_determinant,
+            )
+        };
+
+        let mut record = HitRecord {
+            point: ray.at(root),
+            normal: outward_normal,
+            u: b0 * u0 + b1 * u1 + b2 * u2,
+            v: b0 * v0 + b1 * v1 + b2 * v2,
+            dpdu,
+            dpdv,
+            material: self.material.clone(),
+            time: root,
+            front_face: false,
+        };
+
+        record.set_face_normal(ray, outward_normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let [p0, p1, p2] = self.vertices;
+        Aabb::from_points(p0, p1).union(Aabb::from_points(p0, p2))
+    }
+}
+
+/// Intersect a ray with the triangle with the given vertices.
+///
+/// If the ray hits the triangle, the time of the hit is returned along with the barycentric
+/// coordinates of the hit point with respect to the second and third vertices. Hits behind the
+/// origin of the ray are reported too, so callers need to check the time themselves.
+pub fn intersect(vertices: [Point3; 3], ray: Ray) -> Option<(f64, f64, f64)> {
+    // This is the Möller-Trumbore algorithm, which solves for the distance along the ray and the
+    // barycentric coordinates of the hit all at once using Cramer's rule.
+    let [p0, p1, p2] = vertices;
+    let edge1 = p1 - p0;
+    let edge2 = p2 - p0;
+
+    let pvec = ray.direction.cross(edge2);
+    let determinant = edge1.dot(pvec);
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+    let inverse_determinant = 1.0 / determinant;
+
+    let tvec = ray.origin - p0;
+    let b1 = tvec.dot(pvec) * inverse_determinant;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let qvec = tvec.cross(edge1);
+    let b2 = ray.direction.dot(qvec) * inverse_determinant;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    Some((edge2.dot(qvec) * inverse_determinant, b1, b2))
+}
@@ -0,0 +1,138 @@
+use std::rc::Rc;
+
+use raytracing::bvh::Bvh;
+use raytracing::math::aabb::Aabb;
+use raytracing::math::interval::Interval;
+use raytracing::math::ray::Ray;
+use raytracing::math::{self, Point3, Vec3};
+use raytracing::mesh::Mesh;
+
+use crate::hittable::{HitRecord, Hittable};
+use crate::renderer::material::Material;
+use crate::triangle;
+
+/// Represents a ray traceable mesh of triangles with smooth shading.
+///
+/// Polygons with more than three sides are split into fans of triangles. Normals are interpolated
+/// across each triangle from its corners, so that a finely subdivided mesh looks smooth, while
+/// creased edges keep separate normals on either side and stay sharp. The triangles are kept in a
+/// bounding volume hierarchy, so meshes can be large.
+///
+/// If the mesh has texture coordinates, they are interpolated across each triangle as well, and
+/// the tangents of the surface follow the directions they increase in. Otherwise, every hit has
+/// coordinates of zero, and tangents in no particular direction.
+pub struct TriangleMesh {
+    triangles: Vec<[Point3; 3]>,
+    normals: Vec<[Vec3; 3]>,
+    texture_coordinates: Vec<[[f64; 2]; 3]>,
+    tangents: Vec<Option<(Vec3, Vec3)>>,
+    bvh: Bvh,
+    pub material: Rc<dyn Material>,
+}
+
+impl TriangleMesh {
+    /// Create a new triangle mesh from a polygon mesh.
+    pub fn new(mesh: &Mesh, material: Rc<dyn Material>) -> Self {
+        let corner_normals = mesh.corner_normals();
+        let face_coordinates = mesh.texture_coordinates();
+        let mut triangles = Vec::new();
+        let mut normals = Vec::new();
+        let mut texture_coordinates = Vec::new();
+        for (f, (face, face_normals)) in mesh.faces.iter().zip(&corner_normals).enumerate() {
+            let coordinates = face_coordinates
+                .and_then(|coordinates| coordinates.get(f))
+                .filter(|coordinates| coordinates.len() == face.len());
+            for i in 1..face.len().saturating_sub(1) {
+                let corners = [0, i, i + 1];
+                triangles.push(corners.map(|c| mesh.positions[face[c]]));
+                normals.push(corners.map(|c| face_normals[c]));
+                texture_coordinates
+                    .push(coordinates.map_or([[0.0; 2]; 3], |uv| corners.map(|c| uv[c])));
+            }
+        }
+
+        // Solving for how the position changes with the texture coordinates gives the tangents.
+        // Triangles whose coordinates are all the same, or lie along a line, have none.
+        let tangents = triangles
+            .iter()
+            .zip(&texture_coordinates)
+            .map(|(&[p0, p1, p2], &[t0, t1, t2])| {
+                let (dp1, dp2) = (p1 - p0, p2 - p0);
+                let (du1, dv1) = (t1[0] - t0[0], t1[1] - t0[1]);
+                let (du2, dv2) = (t2[0] - t0[0], t2[1] - t0[1]);
+                let determinant = du1 * dv2 - dv1 * du2;
+                if determinant.abs() < 1e-12 {
+                    return None;
+                }
+                Some((
+                    (dp1 * dv2 - dp2 * dv1) / determinant,
+                    (dp2 * du1 - dp1 * du2) / determinant,
+                ))
+            })
+            .collect();
+
+        let bounds: Vec<Aabb> = triangles
+            .iter()
+            .map(|&[p0, p1, p2]| Aabb::from_points(p0, p1).union(Aabb::from_points(p0, p2)))
+            .collect();
+
+        Self {
+            bvh: Bvh::new(&bounds),
+            triangles,
+            normals,
+            texture_coordinates,
+            tangents,
+            material,
+        }
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<HitRecord> {
+        let mut barycentric = (0.0, 0.0);
+        let (index, time) = self.bvh.closest_hit(ray, interval, |index, interval| {
+            let (time, b1, b2) = triangle::intersect(self.triangles[index], ray)?;
+            if !interval.surrounds(time) {
+                return None;
+            }
+            barycentric = (b1, b2);
+            Some(time)
+        })?;
+
+        let (b1, b2) = barycentric;
+        let [p0, p1, p2] = self.triangles[index];
+        let [n0, n1, n2] = self.normals[index];
+        let geometric_normal = (p1 - p0).cross(p2 - p0).unit_vector();
+        let shading_normal = (n0 * (1.0 - b1 - b2) + n1 * b1 + n2 * b2).unit_vector();
+        let [t0, t1, t2] = self.texture_coordinates[index];
+        let b0 = 1.0 - b1 - b2;
+        let (dpdu, dpdv) =
+            self.tangents[index].unwrap_or_else(|| math::orthonormal_basis(shading_normal));
+
+        let mut record = HitRecord {
+            point: ray.at(time),
+            normal: geometric_normal,
+            u: t0[0] * b0 + t1[0] * b1 + t2[0] * b2,
+            v: t0[1] * b0 + t1[1] * b1 + t2[1] * b2,
+            dpdu,
+            dpdv,
+            material: self.material.clone(),
+            time,
+            front_face: false,
+        };
+
+        // Which side was hit is decided by the actual surface, but the normal used for shading is
+        // the interpolated one, turned to the same side.
+        record.set_face_normal(ray, geometric_normal);
+        record.normal = if record.front_face {
+            shading_normal
+        } else {
+            -shading_normal
+        };
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+}